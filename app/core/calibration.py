@@ -0,0 +1,237 @@
+"""
+Confidence Calibration
+Maps the heuristic confidence score onto the observed probability that an action was safe.
+
+Models are fitted per tenant on labelled /v3 results (storage.validation_feedback joined
+with the confidence stored at validation time) using isotonic regression (pool adjacent
+violators) or Platt scaling (one-feature logistic regression). Each fit is persisted as an
+immutable version; the tenant setting `confidence_calibration_version` selects the one
+applied to new validations. Tenants without it keep the raw heuristic score.
+"""
+
+from __future__ import annotations
+
+import math
+from bisect import bisect_right
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional, Sequence, Tuple
+
+from app.core.feedback import OUTCOME_SAFE
+from app.core.storage import (
+    get_confidence_calibration,
+    get_tenant_validation_settings,
+    insert_confidence_calibration,
+    list_confidence_calibration_samples,
+)
+
+CALIBRATION_METHODS = ("isotonic", "platt")
+CALIBRATION_SETTING = "confidence_calibration_version"
+MIN_CALIBRATION_SAMPLES = 30
+RELIABILITY_BINS = 10
+
+
+class CalibrationError(ValueError):
+    """Not enough (or not varied enough) labelled data to fit a calibration."""
+
+
+@dataclass(frozen=True)
+class CalibrationModel:
+    method: str
+    parameters: Dict[str, Any]
+    version: Optional[int] = None
+
+    def predict(self, raw_score: float) -> float:
+        if self.method == "platt":
+            z = self.parameters["slope"] * raw_score + self.parameters["intercept"]
+            return _sigmoid(z)
+        xs = self.parameters["x"]
+        ys = self.parameters["y"]
+        if raw_score <= xs[0]:
+            return ys[0]
+        if raw_score >= xs[-1]:
+            return ys[-1]
+        i = bisect_right(xs, raw_score)
+        x0, x1, y0, y1 = xs[i - 1], xs[i], ys[i - 1], ys[i]
+        if x1 == x0:
+            return y1
+        return y0 + (y1 - y0) * (raw_score - x0) / (x1 - x0)
+
+
+def _sigmoid(z: float) -> float:
+    if z >= 0:
+        return 1.0 / (1.0 + math.exp(-z))
+    e = math.exp(z)
+    return e / (1.0 + e)
+
+
+def fit_isotonic(scores: Sequence[float], labels: Sequence[int]) -> CalibrationModel:
+    """Non-decreasing step fit by pool adjacent violators; linear between pooled blocks."""
+    pairs = sorted(zip(scores, labels))
+    # Each block: [label sum, count, min score, max score]
+    blocks: List[List[float]] = []
+    for score, label in pairs:
+        if blocks and blocks[-1][3] == score:
+            blocks[-1][0] += label
+            blocks[-1][1] += 1
+        else:
+            blocks.append([float(label), 1.0, score, score])
+        while len(blocks) > 1 and blocks[-2][0] / blocks[-2][1] >= blocks[-1][0] / blocks[-1][1]:
+            total, count, _, high = blocks.pop()
+            blocks[-1][0] += total
+            blocks[-1][1] += count
+            blocks[-1][3] = high
+    xs: List[float] = []
+    ys: List[float] = []
+    for total, count, low, high in blocks:
+        value = round(total / count, 6)
+        xs.append(low)
+        ys.append(value)
+        if high != low:
+            xs.append(high)
+            ys.append(value)
+    return CalibrationModel(method="isotonic", parameters={"x": xs, "y": ys})
+
+
+def fit_platt(scores: Sequence[float], labels: Sequence[int], iterations: int = 100) -> CalibrationModel:
+    """
+    Logistic fit of P(safe) on the raw score (Newton-Raphson). Targets use Platt's prior
+    smoothing so perfectly separated labels do not drive the slope to infinity.
+    """
+    positives = sum(labels)
+    negatives = len(labels) - positives
+    high = (positives + 1.0) / (positives + 2.0)
+    low = 1.0 / (negatives + 2.0)
+    targets = [high if label else low for label in labels]
+
+    slope, intercept = 0.0, math.log((positives + 1.0) / (negatives + 1.0))
+    for _ in range(iterations):
+        g_slope = g_intercept = 0.0
+        h_ss = h_si = h_ii = 1e-9
+        for x, t in zip(scores, targets):
+            p = _sigmoid(slope * x + intercept)
+            d = p - t
+            w = p * (1.0 - p)
+            g_slope += d * x
+            g_intercept += d
+            h_ss += w * x * x
+            h_si += w * x
+            h_ii += w
+        det = h_ss * h_ii - h_si * h_si
+        if det <= 0:
+            break
+        step_slope = (h_ii * g_slope - h_si * g_intercept) / det
+        step_intercept = (h_ss * g_intercept - h_si * g_slope) / det
+        slope -= step_slope
+        intercept -= step_intercept
+        if abs(step_slope) < 1e-9 and abs(step_intercept) < 1e-9:
+            break
+    return CalibrationModel(method="platt", parameters={"slope": round(slope, 6), "intercept": round(intercept, 6)})
+
+
+def brier_score(probabilities: Sequence[float], labels: Sequence[int]) -> float:
+    return round(sum((p - y) ** 2 for p, y in zip(probabilities, labels)) / len(labels), 6)
+
+
+def reliability_diagram(
+    probabilities: Sequence[float],
+    labels: Sequence[int],
+    bins: int = RELIABILITY_BINS,
+) -> List[Dict[str, Any]]:
+    """Equal-width bins of predicted probability vs observed safe rate (empty bins omitted)."""
+    grouped: Dict[int, List[Tuple[float, int]]] = {}
+    for p, y in zip(probabilities, labels):
+        grouped.setdefault(min(int(p * bins), bins - 1), []).append((p, y))
+    return [
+        {
+            "lower": round(index / bins, 4),
+            "upper": round((index + 1) / bins, 4),
+            "count": len(members),
+            "mean_predicted": round(sum(p for p, _ in members) / len(members), 4),
+            "observed_rate": round(sum(y for _, y in members) / len(members), 4),
+        }
+        for index, members in sorted(grouped.items())
+    ]
+
+
+def format_reliability_diagram(diagram: List[Dict[str, Any]], width: int = 40) -> str:
+    """Text rendering for the CLI: predicted (#) vs observed (|) per bin."""
+    lines = ["bin          n   predicted  observed"]
+    for row in diagram:
+        bar = [" "] * (width + 1)
+        for i in range(int(round(row["mean_predicted"] * width))):
+            bar[i] = "#"
+        bar[int(round(row["observed_rate"] * width))] = "|"
+        lines.append(
+            f"{row['lower']:.1f}-{row['upper']:.1f} {row['count']:6d}   {row['mean_predicted']:.3f}     "
+            f"{row['observed_rate']:.3f}  {''.join(bar)}"
+        )
+    return "\n".join(lines)
+
+
+def raw_confidence(stored: Dict[str, Any]) -> float:
+    # Results stored before calibration existed only carry the raw heuristic as `score`
+    return stored["raw_score"] if "raw_score" in stored else stored["score"]
+
+
+def fit_tenant_calibration(
+    tenant_id: str,
+    method: str = "isotonic",
+    fitted_by: Optional[str] = None,
+    min_samples: int = MIN_CALIBRATION_SAMPLES,
+    bins: int = RELIABILITY_BINS,
+    persist: bool = True,
+) -> Dict[str, Any]:
+    """
+    Fit on every labelled result for the tenant and report raw vs calibrated Brier scores
+    and reliability. Metrics are in-sample. Persists a new version unless persist=False.
+    Raises CalibrationError when there are too few labels or only one outcome.
+    """
+    if method not in CALIBRATION_METHODS:
+        raise CalibrationError(f"method must be one of {list(CALIBRATION_METHODS)}")
+    samples = list_confidence_calibration_samples(tenant_id)
+    if len(samples) < min_samples:
+        raise CalibrationError(f"Need at least {min_samples} labelled results, have {len(samples)}")
+    scores = [raw_confidence(sample["confidence"]) for sample in samples]
+    labels = [1 if sample["outcome"] == OUTCOME_SAFE else 0 for sample in samples]
+    if len(set(labels)) < 2:
+        raise CalibrationError("Labels must include both safe and unsafe outcomes")
+
+    model = fit_isotonic(scores, labels) if method == "isotonic" else fit_platt(scores, labels)
+    calibrated = [model.predict(score) for score in scores]
+    metrics = {
+        "brier_raw": brier_score(scores, labels),
+        "brier_calibrated": brier_score(calibrated, labels),
+        "safe_rate": round(sum(labels) / len(labels), 4),
+        "reliability_raw": reliability_diagram(scores, labels, bins),
+        "reliability_calibrated": reliability_diagram(calibrated, labels, bins),
+    }
+    if not persist:
+        return {
+            "tenant_id": tenant_id,
+            "version": None,
+            "method": method,
+            "model": model.parameters,
+            "metrics": metrics,
+            "sample_count": len(samples),
+        }
+    return insert_confidence_calibration(
+        tenant_id=tenant_id,
+        method=method,
+        model=model.parameters,
+        metrics=metrics,
+        sample_count=len(samples),
+        fitted_by=fitted_by,
+    )
+
+
+def load_calibration(stored: Dict[str, Any]) -> CalibrationModel:
+    return CalibrationModel(method=stored["method"], parameters=stored["model"], version=stored["version"])
+
+
+def active_calibration(tenant_id: Optional[str]) -> Optional[CalibrationModel]:
+    """The calibration the tenant has selected, or None to report the raw score only."""
+    version = get_tenant_validation_settings(tenant_id).get(CALIBRATION_SETTING)
+    if version is None:
+        return None
+    stored = get_confidence_calibration(tenant_id, version)
+    return load_calibration(stored) if stored else None
@@ -4,22 +4,45 @@ Quantifies uncertainty instead of binary pass/fail
 """
 
 from dataclasses import dataclass
+from typing import TYPE_CHECKING, Any, Dict, Optional
+
+if TYPE_CHECKING:
+    from app.core.calibration import CalibrationModel
 
 
 @dataclass
 class ConfidenceScore:
-    score: float  # 0.0 to 1.0
+    score: float  # 0.0 to 1.0; calibrated probability when a calibration applied, else raw
     risk_level: str  # "GREEN", "YELLOW", "RED"
     explanation: str
+    raw_score: Optional[float] = None  # Heuristic score before calibration
+    calibrated_score: Optional[float] = None
+    calibration_version: Optional[int] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "score": self.score,
+            "risk_level": self.risk_level,
+            "explanation": self.explanation,
+            "raw_score": self.raw_score,
+            "calibrated_score": self.calibrated_score,
+            "calibration_version": self.calibration_version,
+        }
 
 
-def calculate_confidence(divergence: float, violations: list[str]) -> ConfidenceScore:
+def calculate_confidence(
+    divergence: float,
+    violations: list[str],
+    calibration: Optional["CalibrationModel"] = None,
+) -> ConfidenceScore:
     """
     Map divergence and violations to a confidence score.
     
     Logic:
     - Divergence alone: score = 1.0 - divergence
     - Each violation: subtract 0.15
+    - With a tenant calibration (app/core/calibration.py) the raw score is mapped to the
+      observed probability that the action was safe, and that drives the risk level
     - Risk levels:
         GREEN (0.8+): Safe to execute
         YELLOW (0.5-0.8): Caution advised, review recommended
@@ -31,6 +54,12 @@ def calculate_confidence(divergence: float, violations: list[str]) -> Confidence
     # Penalize for each violation
     violation_penalty = len(violations) * 0.15
     confidence = max(0.0, confidence - violation_penalty)
+    raw_score = round(confidence, 3)
+
+    calibrated_score = None
+    if calibration is not None:
+        calibrated_score = round(min(1.0, max(0.0, calibration.predict(raw_score))), 3)
+        confidence = calibrated_score
     
     # Determine risk level
     if confidence >= 0.8:
@@ -47,4 +76,7 @@ def calculate_confidence(divergence: float, violations: list[str]) -> Confidence
         score=round(confidence, 3),
         risk_level=risk_level,
         explanation=explanation,
+        raw_score=raw_score,
+        calibrated_score=calibrated_score,
+        calibration_version=calibration.version if calibration is not None else None,
     )
@@ -0,0 +1,169 @@
+"""
+Safe Constraint Expressions
+Parses and evaluates relational constraint conditions without eval().
+
+An expression is a single comparison (chains allowed) over numeric variables,
+named parameters and literals:
+
+    leverage_ratio * notional <= capital * k
+    abs(proposed_loss) <= var_99
+    0.2 <= energy_out / energy_in <= 0.95
+
+Only + - * /, unary minus, abs(x)/min(a, b, ...)/max(a, b, ...) and the six comparison
+operators are accepted; anything else (attribute access, subscripts, other calls, strings)
+is rejected at load time.
+"""
+
+from __future__ import annotations
+
+import ast
+import math
+import operator
+from dataclasses import dataclass
+from typing import Callable, Dict, FrozenSet, List, Optional, Tuple
+
+
+class ExpressionError(ValueError):
+    """Raised when an expression is not a supported comparison."""
+
+
+class EvaluationError(ArithmeticError):
+    """Raised when an expression cannot be evaluated for the given inputs (e.g. division by zero)."""
+
+
+_BINARY_OPERATORS: Dict[type, Callable[[float, float], float]] = {
+    ast.Add: operator.add,
+    ast.Sub: operator.sub,
+    ast.Mult: operator.mul,
+    ast.Div: operator.truediv,
+}
+_UNARY_OPERATORS: Dict[type, Callable[[float], float]] = {
+    ast.USub: operator.neg,
+    ast.UAdd: operator.pos,
+}
+_COMPARISONS: Dict[type, Tuple[str, Callable[[float, float], bool]]] = {
+    ast.Lt: ("<", operator.lt),
+    ast.LtE: ("<=", operator.le),
+    ast.Gt: (">", operator.gt),
+    ast.GtE: (">=", operator.ge),
+    ast.Eq: ("==", operator.eq),
+    ast.NotEq: ("!=", operator.ne),
+}
+_FUNCTIONS: Dict[str, Callable[..., float]] = {"abs": abs, "min": min, "max": max}
+MAX_EXPRESSION_LENGTH = 500
+
+
+@dataclass(frozen=True)
+class ComparisonFailure:
+    """The first comparison in a chain that did not hold."""
+    lhs_source: str
+    operator: str
+    rhs_source: str
+    lhs: float
+    rhs: float
+
+
+@dataclass(frozen=True)
+class CompiledExpression:
+    source: str
+    names: FrozenSet[str]          # every identifier except function names
+    _tree: ast.Expression
+
+    def evaluate(self, namespace: Dict[str, float]) -> Optional[ComparisonFailure]:
+        """None when the comparison holds; otherwise the first failing link of the chain."""
+        try:
+            return self._evaluate_chain(namespace)
+        except (TypeError, OverflowError) as e:
+            # e.g. a literal too large for a float; a bad bundle must not break check_logic
+            raise EvaluationError(str(e)) from e
+
+    def _evaluate_chain(self, namespace: Dict[str, float]) -> Optional[ComparisonFailure]:
+        compare = self._tree.body
+        left_node = compare.left
+        left = _evaluate(left_node, namespace)
+        for op, right_node in zip(compare.ops, compare.comparators):
+            right = _evaluate(right_node, namespace)
+            symbol, check = _COMPARISONS[type(op)]
+            if not check(left, right):
+                return ComparisonFailure(
+                    lhs_source=ast.unparse(left_node),
+                    operator=symbol,
+                    rhs_source=ast.unparse(right_node),
+                    lhs=left,
+                    rhs=right,
+                )
+            left_node, left = right_node, right
+        return None
+
+
+def _evaluate(node: ast.AST, namespace: Dict[str, float]) -> float:
+    if isinstance(node, ast.Constant):
+        return float(node.value)
+    if isinstance(node, ast.Name):
+        return float(namespace[node.id])
+    if isinstance(node, ast.UnaryOp):
+        return _UNARY_OPERATORS[type(node.op)](_evaluate(node.operand, namespace))
+    if isinstance(node, ast.BinOp):
+        left = _evaluate(node.left, namespace)
+        right = _evaluate(node.right, namespace)
+        try:
+            result = _BINARY_OPERATORS[type(node.op)](left, right)
+        except ZeroDivisionError as e:
+            raise EvaluationError("division by zero") from e
+        if not math.isfinite(result):
+            raise EvaluationError("non-finite intermediate value")
+        return result
+    if isinstance(node, ast.Call):
+        return float(_FUNCTIONS[node.func.id](*(_evaluate(arg, namespace) for arg in node.args)))
+    raise EvaluationError(f"unsupported node {type(node).__name__}")  # unreachable after validation
+
+
+def _validate(node: ast.AST, names: List[str]) -> None:
+    if isinstance(node, ast.Constant):
+        if isinstance(node.value, bool) or not isinstance(node.value, (int, float)):
+            raise ExpressionError(f"unsupported literal {node.value!r}")
+    elif isinstance(node, ast.Name):
+        if node.id in _FUNCTIONS:
+            raise ExpressionError(f"'{node.id}' is a function, not a value")
+        names.append(node.id)
+    elif isinstance(node, ast.UnaryOp):
+        if type(node.op) not in _UNARY_OPERATORS:
+            raise ExpressionError(f"unsupported operator {type(node.op).__name__}")
+        _validate(node.operand, names)
+    elif isinstance(node, ast.BinOp):
+        if type(node.op) not in _BINARY_OPERATORS:
+            raise ExpressionError(f"unsupported operator {type(node.op).__name__}")
+        _validate(node.left, names)
+        _validate(node.right, names)
+    elif isinstance(node, ast.Call):
+        if not isinstance(node.func, ast.Name) or node.func.id not in _FUNCTIONS:
+            raise ExpressionError(f"only {sorted(_FUNCTIONS)} may be called")
+        expected_ok = len(node.args) == 1 if node.func.id == "abs" else len(node.args) >= 2
+        if node.keywords or not expected_ok:
+            raise ExpressionError(f"invalid arguments to {node.func.id}()")
+        for arg in node.args:
+            _validate(arg, names)
+    else:
+        raise ExpressionError(f"unsupported syntax {type(node).__name__}")
+
+
+def compile_expression(source: str) -> CompiledExpression:
+    """Parse and whitelist-check a comparison expression."""
+    if not isinstance(source, str) or not source.strip():
+        raise ExpressionError("expression must be a non-empty string")
+    if len(source) > MAX_EXPRESSION_LENGTH:
+        raise ExpressionError(f"expression exceeds {MAX_EXPRESSION_LENGTH} characters")
+    try:
+        tree = ast.parse(source.strip(), mode="eval")
+    except SyntaxError as e:
+        raise ExpressionError(f"invalid syntax ({e.msg})") from e
+    if not isinstance(tree.body, ast.Compare):
+        raise ExpressionError("expression must be a comparison, e.g. 'a * b <= c'")
+
+    names: List[str] = []
+    _validate(tree.body.left, names)
+    for op, comparator in zip(tree.body.ops, tree.body.comparators):
+        if type(op) not in _COMPARISONS:
+            raise ExpressionError(f"unsupported comparison {type(op).__name__}")
+        _validate(comparator, names)
+    return CompiledExpression(source=source.strip(), names=frozenset(names), _tree=tree)
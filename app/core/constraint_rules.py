@@ -0,0 +1,574 @@
+"""
+Declarative Constraint Definitions
+Single source of truth for hard-constraint rules, severity weights and regulatory tags.
+
+Definitions live in constraint_rules.json (or a YAML/JSON file named by
+ORIPHIM_CONSTRAINTS_PATH). Each rule states the condition a value must satisfy:
+
+    {"id": "leverage_limit", "variables": ["leverage_ratio", "leverage"],
+     "operator": "<=", "limit": 10, "unit": "x", "severity_weight": 2.0,
+     "message": "Leverage limit breached: hard limit is {limit:g}x maximum",
+     "articles": ["EU-AIA-14-HumanOversight"]}
+
+`relations` relate several inputs through a safe comparison expression (see
+constraint_expressions); they only fire when every variable they name was supplied:
+
+    {"id": "leverage_capital", "expression": "leverage_ratio * notional <= capital * k",
+     "parameters": {"k": 10}, "severity_weight": 2.0,
+     "message": "Leverage exposure breached: {lhs:,.0f} exceeds {rhs:,.0f} capital limit",
+     "articles": ["EU-AIA-14-HumanOversight"]}
+
+`temporal` rules bound how a variable may change across an agent's requests (per
+tenant and agent_id, from the persisted metric history):
+
+    {"id": "leverage_step", "variables": ["leverage_ratio", "leverage"], "kind": "max_delta",
+     "limit": 3, "severity_weight": 4.0,
+     "message": "Leverage jumped {previous:g}x -> {current:g}x; max step is {limit:g}x"}
+
+kinds: `max_delta` (vs the previous request), `max_window_delta` (vs any value in the
+last `window_seconds`) and `monotonic` (`direction` "increasing" or "decreasing").
+
+`checks` tag violations raised by built-in validators (numeric claims, ...) by message
+prefix so compliance and severity can be driven from the same file.
+
+Tenants can replace the shipped rules with their own versioned constraint bundles
+(see storage.create_constraint_bundle); resolve_constraint_definitions() picks the
+tenant's active bundle, falling back to the process-wide definitions.
+
+Definitions are validated at load time; an invalid file raises ConstraintDefinitionError.
+"""
+
+from __future__ import annotations
+
+import json
+import math
+import operator
+import os
+import re
+import string
+import threading
+from dataclasses import dataclass, replace
+from pathlib import Path
+from typing import Any, Callable, Dict, FrozenSet, Iterable, List, Optional, Tuple, Union
+
+from app.core.constraint_expressions import (
+    CompiledExpression,
+    EvaluationError,
+    ExpressionError,
+    compile_expression,
+)
+from app.core.violations import ConstraintViolation
+
+
+DEFAULT_DEFINITIONS_PATH = Path(__file__).with_name("constraint_rules.json")
+
+OPERATORS: Dict[str, Callable[[float, float], bool]] = {
+    "<": operator.lt,
+    "<=": operator.le,
+    ">": operator.gt,
+    ">=": operator.ge,
+    "==": operator.eq,
+    "!=": operator.ne,
+}
+MIN_SEVERITY_WEIGHT = 1.0
+MAX_SEVERITY_WEIGHT = 4.0
+_ARTICLE_PATTERN = re.compile(r"^[A-Z][A-Za-z0-9]*(-[A-Za-z0-9]+)+$")
+_TEMPLATE_FIELDS = {"variable", "value", "limit", "unit"}
+
+
+class ConstraintDefinitionError(ValueError):
+    """Raised when a constraint definition file is malformed."""
+
+
+@dataclass(frozen=True)
+class ConstraintRule:
+    rule_id: str
+    variables: Tuple[str, ...]
+    operator: str       # condition the value must satisfy: value <operator> limit
+    limit: float
+    unit: Optional[str]
+    severity_weight: float
+    message: str        # str.format template over {variable}, {value}, {limit}, {unit}
+    articles: Tuple[str, ...]
+
+    @property
+    def message_prefix(self) -> str:
+        return self.message.split("{", 1)[0]
+
+    def is_satisfied(self, value: float) -> bool:
+        return OPERATORS[self.operator](value, self.limit)
+
+    def render(self, variable: str, value: float) -> str:
+        return self.message.format(variable=variable, value=value, limit=self.limit, unit=self.unit or "")
+
+    def evaluate(self, variable: str, value: float) -> Optional[str]:
+        """Violation message, or None when the value satisfies the rule."""
+        return None if self.is_satisfied(value) else self.render(variable, value)
+
+    def violation(self, variable: str, value: float) -> Optional[ConstraintViolation]:
+        """Violation record, or None when the value satisfies the rule."""
+        message = self.evaluate(variable, value)
+        if message is None:
+            return None
+        return ConstraintViolation(
+            rule_id=self.rule_id,
+            message=message,
+            variable=variable,
+            actual=float(value),
+            limit=self.limit,
+            unit=self.unit,
+        )
+
+
+@dataclass(frozen=True)
+class RelationalRule:
+    rule_id: str
+    expression: CompiledExpression
+    parameters: Dict[str, float]
+    severity_weight: float
+    message: str        # str.format template over variables, parameters, {lhs} and {rhs}
+    articles: Tuple[str, ...]
+
+    @property
+    def variables(self) -> FrozenSet[str]:
+        return self.expression.names - self.parameters.keys()
+
+    @property
+    def message_prefix(self) -> str:
+        return self.message.split("{", 1)[0]
+
+    def violation(self, inputs: Dict[str, float]) -> Optional[ConstraintViolation]:
+        """
+        Violation record reporting every participating variable, or None when the
+        relation holds, a variable is missing, or it cannot be evaluated (e.g. x / 0).
+        """
+        if not self.variables <= inputs.keys():
+            return None
+        values = {name: float(inputs[name]) for name in sorted(self.variables)}
+        try:
+            failure = self.expression.evaluate({**values, **self.parameters})
+        except EvaluationError:
+            return None
+        if failure is None:
+            return None
+        return ConstraintViolation(
+            rule_id=self.rule_id,
+            message=self.message.format(**values, **self.parameters, lhs=failure.lhs, rhs=failure.rhs),
+            variable=failure.lhs_source,
+            actual=failure.lhs,
+            limit=failure.rhs,
+            inputs=values,
+        )
+
+
+TEMPORAL_KINDS = ("max_delta", "max_window_delta", "monotonic")
+_TEMPORAL_TEMPLATE_FIELDS = {"variable", "previous", "current", "delta", "limit", "window"}
+
+
+@dataclass(frozen=True)
+class TemporalRule:
+    rule_id: str
+    variables: Tuple[str, ...]       # aliases; history is stored under variables[0]
+    kind: str                        # max_delta | max_window_delta | monotonic
+    limit: float                     # largest allowed |delta| (0 for monotonic)
+    window_seconds: Optional[float]  # max_window_delta only
+    direction: Optional[str]         # monotonic only: increasing | decreasing
+    severity_weight: float
+    message: str                     # template over {variable} {previous} {current} {delta} {limit} {window}
+    articles: Tuple[str, ...]
+
+    @property
+    def variable(self) -> str:
+        return self.variables[0]
+
+    @property
+    def message_prefix(self) -> str:
+        return self.message.split("{", 1)[0]
+
+    def _breach(self, previous: float, current: float) -> bool:
+        delta = current - previous
+        if self.kind == "monotonic":
+            return delta < 0 if self.direction == "increasing" else delta > 0
+        return abs(delta) > self.limit
+
+    def violation(self, current: float, history: List[float]) -> Optional[ConstraintViolation]:
+        """
+        Compare `current` with prior values (oldest first): the latest one for max_delta and
+        monotonic, every value inside the window for max_window_delta.
+        """
+        if not history:
+            return None
+        candidates = history if self.kind == "max_window_delta" else history[-1:]
+        breaches = [previous for previous in candidates if self._breach(previous, current)]
+        if not breaches:
+            return None
+        previous = max(breaches, key=lambda value: abs(current - value))
+        delta = current - previous
+        return ConstraintViolation(
+            rule_id=self.rule_id,
+            message=self.message.format(
+                variable=self.variable,
+                previous=previous,
+                current=current,
+                delta=delta,
+                limit=self.limit,
+                window=self.window_seconds or 0.0,
+            ),
+            variable=self.variable,
+            actual=abs(delta),
+            limit=self.limit,
+            previous=previous,
+            current=current,
+            window_seconds=self.window_seconds,
+        )
+
+
+@dataclass(frozen=True)
+class CheckDefinition:
+    check_id: str
+    prefix: str
+    severity_weight: float
+    articles: Tuple[str, ...]
+
+
+@dataclass(frozen=True)
+class ConstraintDefinitions:
+    version: str
+    rules: Tuple[ConstraintRule, ...]
+    checks: Tuple[CheckDefinition, ...]
+    relations: Tuple[RelationalRule, ...] = ()
+    temporal: Tuple[TemporalRule, ...] = ()
+
+    def rule(self, rule_id: str) -> ConstraintRule:
+        for rule in self.rules:
+            if rule.rule_id == rule_id:
+                return rule
+        raise KeyError(rule_id)
+
+    def rule_for(self, variable: str) -> Optional[ConstraintRule]:
+        name = variable.strip().lower()
+        for rule in self.rules:
+            if name in rule.variables:
+                return rule
+        return None
+
+    def _tags_for(self, violation: Union[str, ConstraintViolation]) -> List[Tuple[float, Tuple[str, ...]]]:
+        # Records match on rule/check id; bare strings (and unknown ids) on message prefix
+        if isinstance(violation, ConstraintViolation):
+            tagged = [(r.severity_weight, r.articles) for r in self.rules if r.rule_id == violation.rule_id]
+            tagged.extend((r.severity_weight, r.articles) for r in self.relations if r.rule_id == violation.rule_id)
+            tagged.extend((r.severity_weight, r.articles) for r in self.temporal if r.rule_id == violation.rule_id)
+            tagged.extend((c.severity_weight, c.articles) for c in self.checks if c.check_id == violation.rule_id)
+            if tagged:
+                return tagged
+            violation = violation.message
+        tags = [(r.severity_weight, r.articles) for r in self.rules if violation.startswith(r.message_prefix)]
+        tags.extend((r.severity_weight, r.articles) for r in self.relations if violation.startswith(r.message_prefix))
+        tags.extend((r.severity_weight, r.articles) for r in self.temporal if violation.startswith(r.message_prefix))
+        tags.extend((c.severity_weight, c.articles) for c in self.checks if violation.startswith(c.prefix))
+        return tags
+
+    def articles_for(self, violation: Union[str, ConstraintViolation]) -> List[str]:
+        return [article for _, articles in self._tags_for(violation) for article in articles]
+
+    def severity_weight_for(self, violation: Union[str, ConstraintViolation]) -> Optional[float]:
+        weights = [weight for weight, _ in self._tags_for(violation)]
+        return max(weights) if weights else None
+
+    def limits(self) -> Dict[str, float]:
+        return {variable: rule.limit for rule in self.rules for variable in rule.variables}
+
+
+def _require(condition: bool, message: str) -> None:
+    if not condition:
+        raise ConstraintDefinitionError(message)
+
+
+def _parse_weight(raw: Any, where: str) -> float:
+    _require(
+        isinstance(raw, (int, float)) and not isinstance(raw, bool)
+        and MIN_SEVERITY_WEIGHT <= raw <= MAX_SEVERITY_WEIGHT,
+        f"{where}: severity_weight must be a number in [{MIN_SEVERITY_WEIGHT}, {MAX_SEVERITY_WEIGHT}]",
+    )
+    return float(raw)
+
+
+def _parse_articles(raw: Any, where: str) -> Tuple[str, ...]:
+    _require(isinstance(raw, list), f"{where}: articles must be a list")
+    for article in raw:
+        _require(
+            isinstance(article, str) and bool(_ARTICLE_PATTERN.match(article)),
+            f"{where}: invalid article tag {article!r}",
+        )
+    return tuple(raw)
+
+
+def _parse_template(raw: Any, where: str, allowed: Iterable[str] = _TEMPLATE_FIELDS) -> str:
+    _require(isinstance(raw, str) and raw.strip() != "", f"{where}: message must be a non-empty string")
+    try:
+        fields = {name for _, name, _, _ in string.Formatter().parse(raw) if name is not None}
+    except ValueError as e:
+        raise ConstraintDefinitionError(f"{where}: malformed message template ({e})") from e
+    unknown = fields - set(allowed)
+    _require(not unknown, f"{where}: unknown message placeholders {sorted(unknown)}")
+    return raw
+
+
+def _parse_rule(raw: Any, index: int) -> ConstraintRule:
+    where = f"rules[{index}]"
+    _require(isinstance(raw, dict), f"{where}: must be an object")
+    rule_id = raw.get("id")
+    _require(isinstance(rule_id, str) and rule_id != "", f"{where}: id is required")
+    where = f"rule '{rule_id}'"
+
+    variables = raw.get("variables")
+    _require(
+        isinstance(variables, list) and bool(variables) and all(isinstance(v, str) and v.strip() for v in variables),
+        f"{where}: variables must be a non-empty list of names",
+    )
+    op = raw.get("operator")
+    _require(op in OPERATORS, f"{where}: operator must be one of {sorted(OPERATORS)}")
+    limit = raw.get("limit")
+    _require(
+        isinstance(limit, (int, float)) and not isinstance(limit, bool) and math.isfinite(limit),
+        f"{where}: limit must be a finite number",
+    )
+    unit = raw.get("unit")
+    _require(unit is None or isinstance(unit, str), f"{where}: unit must be a string")
+
+    rule = ConstraintRule(
+        rule_id=rule_id,
+        variables=tuple(v.strip().lower() for v in variables),
+        operator=op,
+        limit=float(limit),
+        unit=unit,
+        severity_weight=_parse_weight(raw.get("severity_weight"), where),
+        message=_parse_template(raw.get("message"), where),
+        articles=_parse_articles(raw.get("articles", []), where),
+    )
+    # Compliance and severity match violations on the literal text before the first placeholder
+    _require(rule.message_prefix.strip() != "", f"{where}: message must start with literal text")
+    try:
+        rule.render(rule.variables[0], rule.limit)
+    except (ValueError, IndexError, KeyError) as e:
+        raise ConstraintDefinitionError(f"{where}: message template cannot be rendered ({e})") from e
+    return rule
+
+
+def _parse_relation(raw: Any, index: int) -> RelationalRule:
+    where = f"relations[{index}]"
+    _require(isinstance(raw, dict), f"{where}: must be an object")
+    rule_id = raw.get("id")
+    _require(isinstance(rule_id, str) and rule_id != "", f"{where}: id is required")
+    where = f"relation '{rule_id}'"
+
+    try:
+        expression = compile_expression(raw.get("expression"))
+    except ExpressionError as e:
+        raise ConstraintDefinitionError(f"{where}: {e}") from e
+    parameters = raw.get("parameters", {})
+    _require(isinstance(parameters, dict), f"{where}: parameters must be an object")
+    for name, value in parameters.items():
+        _require(
+            isinstance(value, (int, float)) and not isinstance(value, bool) and math.isfinite(value),
+            f"{where}: parameter '{name}' must be a finite number",
+        )
+
+    relation = RelationalRule(
+        rule_id=rule_id,
+        expression=expression,
+        parameters={name: float(value) for name, value in parameters.items()},
+        severity_weight=_parse_weight(raw.get("severity_weight"), where),
+        message="",
+        articles=_parse_articles(raw.get("articles", []), where),
+    )
+    _require(bool(relation.variables), f"{where}: expression must reference at least one input variable")
+    # Inputs are matched on lower-cased metric names
+    _require(
+        all(name == name.lower() for name in relation.variables),
+        f"{where}: variable names must be lower case",
+    )
+    template_fields = set(relation.variables) | set(relation.parameters) | {"lhs", "rhs"}
+    relation = replace(relation, message=_parse_template(raw.get("message"), where, template_fields))
+    _require(relation.message_prefix.strip() != "", f"{where}: message must start with literal text")
+    try:
+        relation.message.format(**{name: 1.0 for name in template_fields})
+    except (ValueError, IndexError, KeyError) as e:
+        raise ConstraintDefinitionError(f"{where}: message template cannot be rendered ({e})") from e
+    return relation
+
+
+def _parse_temporal(raw: Any, index: int) -> TemporalRule:
+    where = f"temporal[{index}]"
+    _require(isinstance(raw, dict), f"{where}: must be an object")
+    rule_id = raw.get("id")
+    _require(isinstance(rule_id, str) and rule_id != "", f"{where}: id is required")
+    where = f"temporal rule '{rule_id}'"
+
+    variables = raw.get("variables")
+    _require(
+        isinstance(variables, list) and bool(variables) and all(isinstance(v, str) and v.strip() for v in variables),
+        f"{where}: variables must be a non-empty list of names",
+    )
+    kind = raw.get("kind")
+    _require(kind in TEMPORAL_KINDS, f"{where}: kind must be one of {list(TEMPORAL_KINDS)}")
+
+    limit = raw.get("limit", 0.0 if kind == "monotonic" else None)
+    _require(
+        isinstance(limit, (int, float)) and not isinstance(limit, bool) and math.isfinite(limit) and limit >= 0,
+        f"{where}: limit must be a finite, non-negative number",
+    )
+    window = raw.get("window_seconds")
+    if kind == "max_window_delta":
+        _require(
+            isinstance(window, (int, float)) and not isinstance(window, bool) and math.isfinite(window) and window > 0,
+            f"{where}: window_seconds must be a positive number",
+        )
+    else:
+        _require(window is None, f"{where}: window_seconds only applies to max_window_delta")
+    direction = raw.get("direction")
+    if kind == "monotonic":
+        _require(direction in ("increasing", "decreasing"), f"{where}: direction must be increasing or decreasing")
+    else:
+        _require(direction is None, f"{where}: direction only applies to monotonic rules")
+
+    allowed = _TEMPORAL_TEMPLATE_FIELDS if kind == "max_window_delta" else _TEMPORAL_TEMPLATE_FIELDS - {"window"}
+    rule = TemporalRule(
+        rule_id=rule_id,
+        variables=tuple(v.strip().lower() for v in variables),
+        kind=kind,
+        limit=float(limit),
+        window_seconds=float(window) if window is not None else None,
+        direction=direction,
+        severity_weight=_parse_weight(raw.get("severity_weight"), where),
+        message=_parse_template(raw.get("message"), where, allowed),
+        articles=_parse_articles(raw.get("articles", []), where),
+    )
+    _require(rule.message_prefix.strip() != "", f"{where}: message must start with literal text")
+    try:
+        # One of the two probes breaches every kind and direction, rendering the template
+        rule.violation(rule.limit + 2.0, [0.0])
+        rule.violation(0.0, [rule.limit + 2.0])
+    except (ValueError, IndexError, KeyError) as e:
+        raise ConstraintDefinitionError(f"{where}: message template cannot be rendered ({e})") from e
+    return rule
+
+
+def _parse_check(raw: Any, index: int) -> CheckDefinition:
+    where = f"checks[{index}]"
+    _require(isinstance(raw, dict), f"{where}: must be an object")
+    check_id = raw.get("id")
+    _require(isinstance(check_id, str) and check_id != "", f"{where}: id is required")
+    prefix = raw.get("prefix")
+    _require(isinstance(prefix, str) and prefix.strip() != "", f"check '{check_id}': prefix is required")
+    return CheckDefinition(
+        check_id=check_id,
+        prefix=prefix,
+        severity_weight=_parse_weight(raw.get("severity_weight", MIN_SEVERITY_WEIGHT), f"check '{check_id}'"),
+        articles=_parse_articles(raw.get("articles", []), f"check '{check_id}'"),
+    )
+
+
+def parse_constraint_definitions(document: Any) -> ConstraintDefinitions:
+    """Validate a decoded definitions document."""
+    _require(isinstance(document, dict), "definitions must be an object")
+    version = document.get("version")
+    _require(isinstance(version, str) and version != "", "version is required")
+    raw_rules = document.get("rules")
+    _require(isinstance(raw_rules, list) and bool(raw_rules), "rules must be a non-empty list")
+
+    rules = tuple(_parse_rule(raw, index) for index, raw in enumerate(raw_rules))
+    raw_relations = document.get("relations", [])
+    _require(isinstance(raw_relations, list), "relations must be a list")
+    relations = tuple(_parse_relation(raw, index) for index, raw in enumerate(raw_relations))
+    raw_temporal = document.get("temporal", [])
+    _require(isinstance(raw_temporal, list), "temporal must be a list")
+    temporal = tuple(_parse_temporal(raw, index) for index, raw in enumerate(raw_temporal))
+    checks = tuple(_parse_check(raw, index) for index, raw in enumerate(document.get("checks", [])))
+
+    ids = (
+        [rule.rule_id for rule in rules]
+        + [relation.rule_id for relation in relations]
+        + [rule.rule_id for rule in temporal]
+        + [check.check_id for check in checks]
+    )
+    _require(len(ids) == len(set(ids)), "rule, relation, temporal and check ids must be unique")
+    aliases = [variable for rule in rules for variable in rule.variables]
+    duplicates = sorted({alias for alias in aliases if aliases.count(alias) > 1})
+    _require(not duplicates, f"variables claimed by more than one rule: {duplicates}")
+
+    return ConstraintDefinitions(
+        version=version, rules=rules, checks=checks, relations=relations, temporal=temporal
+    )
+
+
+def load_constraint_definitions(path: str | Path) -> ConstraintDefinitions:
+    """Load and validate a JSON or YAML (.yaml/.yml, requires PyYAML) definitions file."""
+    path = Path(path)
+    text = path.read_text(encoding="utf-8")
+    if path.suffix.lower() in {".yaml", ".yml"}:
+        try:
+            import yaml
+        except ImportError as e:
+            raise ConstraintDefinitionError("PyYAML is required to load YAML constraint definitions") from e
+        document = yaml.safe_load(text)
+    else:
+        try:
+            document = json.loads(text)
+        except json.JSONDecodeError as e:
+            raise ConstraintDefinitionError(f"{path.name}: invalid JSON ({e.msg})") from e
+    return parse_constraint_definitions(document)
+
+
+_DEFINITIONS: Optional[ConstraintDefinitions] = None
+_DEFINITIONS_LOCK = threading.Lock()
+
+
+def get_constraint_definitions() -> ConstraintDefinitions:
+    """Process-wide definitions (ORIPHIM_CONSTRAINTS_PATH or the shipped defaults)."""
+    global _DEFINITIONS
+    with _DEFINITIONS_LOCK:
+        if _DEFINITIONS is None:
+            _DEFINITIONS = load_constraint_definitions(
+                os.getenv("ORIPHIM_CONSTRAINTS_PATH") or DEFAULT_DEFINITIONS_PATH
+            )
+        return _DEFINITIONS
+
+
+def parse_bundle_definitions(document: Any, version: str) -> ConstraintDefinitions:
+    """
+    Validate a tenant constraint bundle ({"rules": [...], "relations": [...], "temporal": [...],
+    "checks": [...]}).
+
+    The version is assigned server-side; bundles without `relations`, `temporal` or
+    `checks` inherit the process-wide ones so invariants such as energy balance stay
+    enforced and built-in validators stay tagged.
+    """
+    _require(isinstance(document, dict), "bundle must be an object")
+    definitions = parse_constraint_definitions({**document, "version": version})
+    defaults = get_constraint_definitions()
+    if "relations" not in document:
+        definitions = replace(definitions, relations=defaults.relations)
+    if "temporal" not in document:
+        definitions = replace(definitions, temporal=defaults.temporal)
+    if "checks" not in document:
+        definitions = replace(definitions, checks=defaults.checks)
+    return definitions
+
+
+_BUNDLE_CACHE: Dict[Tuple[str, int, str], ConstraintDefinitions] = {}
+
+
+def resolve_constraint_definitions(bundle: Optional[Dict[str, Any]]) -> ConstraintDefinitions:
+    """Definitions for a tenant's active bundle (as stored), or the process-wide defaults."""
+    if bundle is None:
+        return get_constraint_definitions()
+    # Bundle versions are immutable; the checksum guards against a re-created database
+    key = (bundle["tenant_id"], bundle["version"], bundle.get("checksum", ""))
+    with _DEFINITIONS_LOCK:
+        cached = _BUNDLE_CACHE.get(key)
+    if cached is None:
+        cached = parse_bundle_definitions(bundle["definitions"], str(bundle["version"]))
+        with _DEFINITIONS_LOCK:
+            _BUNDLE_CACHE[key] = cached
+    return cached
@@ -1,4 +1,4 @@
-from typing import List, Protocol
+from typing import Dict, List, Optional, Protocol
 from app.models import ValidationRequest
 
 
@@ -7,37 +7,70 @@ class _RequestLike(Protocol):
     financial: object | None
     metrics: object | None
 from app.core.physical_validator import PhysicalValidator
+from app.core.constraint_rules import ConstraintDefinitions, get_constraint_definitions
+from app.core.numeric_claims import check_numeric_consistency
+from app.core.structured_divergence import check_structured_consistency
+from app.core.violations import ConstraintViolation
 
 
-LOSS_THRESHOLD = 10_000.0
+MAX_METRICS = 100
 
 
-def check_logic(request: ValidationRequest | _RequestLike) -> List[str]:
-    violations: List[str] = []
-    validator = PhysicalValidator()
-
+def constraint_inputs(request: ValidationRequest | _RequestLike) -> Dict[str, float]:
+    """Named numeric inputs for relational/temporal rules; payload fields win over same-named metrics."""
+    inputs: Dict[str, float] = {}
+    if request.metrics and len(request.metrics) <= MAX_METRICS:
+        inputs.update({name.strip().lower(): value for name, value in request.metrics.items()})
+    if request.financial is not None:
+        inputs["proposed_loss"] = request.financial.proposed_loss
     if request.physics is not None:
-        if request.physics.energy_out > request.physics.energy_in:
-            violations.append("Balance invariant breached")
-        ok, reason = validator.validate("temperature", request.physics.energy_in)
-        if not ok and reason:
-            violations.append(reason)
+        inputs["energy_in"] = request.physics.energy_in
+        inputs["energy_out"] = request.physics.energy_out
+    return inputs
+
+
+def check_logic(
+    request: ValidationRequest | _RequestLike,
+    definitions: Optional[ConstraintDefinitions] = None,
+) -> List[ConstraintViolation]:
+    """Hard-constraint violations. `definitions` is a tenant bundle; defaults to the shipped rules."""
+    violations: List[ConstraintViolation] = []
+    validator = PhysicalValidator(definitions)
 
     if request.financial is not None:
-        if request.financial.proposed_loss < -LOSS_THRESHOLD:
-            violations.append("VaR loss limit breached")
-        ok, reason = validator.validate("proposed_loss", request.financial.proposed_loss)
-        if not ok and reason:
-            violations.append(reason)
+        violation = validator.check("proposed_loss", request.financial.proposed_loss)
+        if violation is not None:
+            violations.append(violation)
 
     if request.metrics:
         # Prevent DoS via excessive metrics keys
-        if len(request.metrics) > 100:
-            violations.append(f"Metrics dict exceeds 100 keys limit (got {len(request.metrics)})")
+        if len(request.metrics) > MAX_METRICS:
+            violations.append(
+                ConstraintViolation(
+                    rule_id="metrics_limit",
+                    message=f"Metrics dict exceeds {MAX_METRICS} keys limit (got {len(request.metrics)})",
+                    variable="metrics",
+                    actual=float(len(request.metrics)),
+                    limit=float(MAX_METRICS),
+                )
+            )
         else:
             for name, value in request.metrics.items():
-                ok, reason = validator.validate(name, value)
-                if not ok and reason:
-                    violations.append(reason)
+                violation = validator.check(name, value)
+                if violation is not None:
+                    violations.append(violation)
+
+    # Relational rules (energy balance, leverage vs capital, ...) over every supplied input
+    inputs = constraint_inputs(request)
+    for relation in (definitions or get_constraint_definitions()).relations:
+        violation = relation.violation(inputs)
+        if violation is not None:
+            violations.append(violation)
+
+    # Samples that disagree on quantities/prices/tickers are a violation even at low divergence
+    if getattr(request, "sample_format", "text") == "json":
+        violations.extend(check_structured_consistency(request.samples, request.decision_fields))
+    else:
+        violations.extend(check_numeric_consistency(getattr(request, "samples", None)))
 
     return violations
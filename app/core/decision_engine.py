@@ -0,0 +1,113 @@
+"""
+Decision Engine
+Single, versioned decision table shared by /v1/validate, /v2/validate and /v3/intent.
+
+Rules are evaluated top to bottom; the first match wins:
+
+| rule            | condition                         | action  | status | context_reset |
+|-----------------|-----------------------------------|---------|--------|---------------|
+| hard_violation  | any violation                     | BLOCK   | 424    | yes           |
+| high_divergence | divergence_score > 0.4            | REVIEW  | 400    | no            |
+| latency_guard   | validation exceeded latency guard | CAUTION | 202    | no            |
+| default         | -                                 | ALLOW   | 200    | no            |
+
+Bump DECISION_TABLE_VERSION whenever a rule, threshold or ordering changes; the
+version is returned with every verdict and stored with every validation result.
+"""
+
+from __future__ import annotations
+
+import logging
+from dataclasses import dataclass
+from typing import Optional, Sequence, Tuple
+
+from app.core.violations import ConstraintViolation
+
+
+logger = logging.getLogger(__name__)
+
+DECISION_TABLE_VERSION = "1.0"
+DIVERGENCE_REVIEW_THRESHOLD = 0.4
+
+
+@dataclass(frozen=True)
+class DecisionRule:
+    name: str
+    action: str
+    status_code: int
+    context_reset: bool
+    description: str
+
+
+DECISION_TABLE: Tuple[DecisionRule, ...] = (
+    DecisionRule("hard_violation", "BLOCK", 424, True, "Any hard constraint violation"),
+    DecisionRule("high_divergence", "REVIEW", 400, False, f"Divergence score above {DIVERGENCE_REVIEW_THRESHOLD}"),
+    DecisionRule("latency_guard", "CAUTION", 202, False, "Validation exceeded the latency guard"),
+    DecisionRule("default", "ALLOW", 200, False, "No rule matched"),
+)
+_RULES = {rule.name: rule for rule in DECISION_TABLE}
+
+
+@dataclass(frozen=True)
+class Decision:
+    action: str
+    status_code: int
+    context_reset: bool
+    rule: str
+    table_version: str = DECISION_TABLE_VERSION
+
+
+def decide(
+    divergence_score: float,
+    violations: Sequence[ConstraintViolation | str],
+    latency_exceeded: bool = False,
+) -> Decision:
+    """Apply the decision table. Latency is only known to the async path (v3)."""
+    if violations:
+        rule = _RULES["hard_violation"]
+    elif divergence_score > DIVERGENCE_REVIEW_THRESHOLD:
+        rule = _RULES["high_divergence"]
+    elif latency_exceeded:
+        rule = _RULES["latency_guard"]
+    else:
+        rule = _RULES["default"]
+    return Decision(
+        action=rule.action,
+        status_code=rule.status_code,
+        context_reset=rule.context_reset,
+        rule=rule.name,
+    )
+
+
+def recommendation_for(
+    decision: Decision,
+    risk_level: str,
+    top_impact: Optional[str] = None,
+) -> str:
+    if decision.action == "BLOCK":
+        return top_impact or "Constraint violation detected"
+    if decision.action == "REVIEW":
+        return f"Manual review recommended. Confidence: {risk_level}"
+    if decision.action == "CAUTION":
+        return "Latency guard triggered; caution flagged for downstream review."
+    return "Safe to execute"
+
+
+def action_label_and_reason(
+    decision: Decision,
+    confidence_score: float,
+    violations: Sequence[ConstraintViolation | str],
+) -> Tuple[str, str]:
+    """CRO-friendly single-line label and reason for a decision."""
+    if decision.action == "BLOCK":
+        return "BLOCK", f"Execution blocked (424 Sentinel): {'; '.join(str(v) for v in violations)}"
+    if decision.action == "REVIEW":
+        risk = "high" if confidence_score < 0.5 else "moderate"
+        return "REVIEW", f"Requires manual review due to {risk} confidence ({confidence_score:.2f})"
+    if decision.action == "CAUTION":
+        return "CAUTION", "Latency guard triggered; process continues with flag"
+    if decision.action == "ALLOW":
+        return "ALLOW", f"Safe to execute (confidence={confidence_score:.2f})"
+    # Unknown action - default to BLOCK (safe default)
+    logger.error("Unknown action '%s' detected, defaulting to BLOCK", decision.action)
+    return "BLOCK", f"Unknown validation action '{decision.action}'; defaulting to safe BLOCK"
@@ -0,0 +1,319 @@
+"""
+Divergence Backend Registry
+Named, versioned similarity scorers used to build meaning clusters for semantic entropy.
+
+Built-in backends:
+- lexical-js:     Token distribution Jensen-Shannon similarity (no model, always available)
+- minilm:         all-MiniLM-L6-v2 SentenceTransformer cosine similarity
+- onnx-minilm:    all-MiniLM-L6-v2 exported to ONNX, CPU-only onnxruntime inference
+- numeric-claims: Agreement of the numbers each sample asserts
+
+Custom backends are discovered from the "oriphim.divergence_backends" entry point
+group. Each entry point must resolve to a zero-argument factory (or class) returning
+an object with `name`, `version`, `cluster_threshold` and `similarity_matrix()`.
+
+Tenants and requests can select a single backend or a weighted ensemble. The
+resolved backend name and version are stored with every validation result.
+"""
+
+from __future__ import annotations
+
+import hashlib
+import logging
+import os
+import threading
+from typing import Callable, Dict, List, Optional, Protocol
+
+import numpy as np
+
+from app.core.entropy import (
+    _embeddings_disabled,
+    _get_cached_embeddings,
+    _get_embedding_model,
+    _js_divergence,
+    _to_distribution,
+    _tokenize,
+)
+from app.core.numeric_claims import CLAIM_TOLERANCES, NumericClaim, extract_claims
+
+logger = logging.getLogger(__name__)
+
+ENTRY_POINT_GROUP = "oriphim.divergence_backends"
+MAX_ENSEMBLE_MEMBERS = 5
+
+
+class DivergenceBackend(Protocol):
+    name: str
+    version: str
+    cluster_threshold: float  # Minimum pairwise similarity for two samples to share a cluster
+
+    def similarity_matrix(self, responses: List[str]) -> List[List[float]]:
+        """Symmetric N x N similarity matrix in [0.0, 1.0] with 1.0 on the diagonal."""
+        ...
+
+
+class UnknownBackendError(ValueError):
+    """Raised when a request or tenant setting names a backend that is not registered."""
+
+
+def _matrix_from_pairs(size: int, pair_similarity: Callable[[int, int], float]) -> List[List[float]]:
+    matrix = [[1.0] * size for _ in range(size)]
+    for i in range(size):
+        for j in range(i + 1, size):
+            similarity = float(min(max(pair_similarity(i, j), 0.0), 1.0))
+            matrix[i][j] = similarity
+            matrix[j][i] = similarity
+    return matrix
+
+
+class LexicalJSBackend:
+    name = "lexical-js"
+    version = "1.0"
+    cluster_threshold = 0.6  # 1 - Jensen-Shannon divergence (bits)
+
+    def similarity_matrix(self, responses: List[str]) -> List[List[float]]:
+        tokenized = [_tokenize(response) for response in responses]
+        vocab = sorted({token for tokens in tokenized for token in tokens})
+        if not vocab:
+            return _matrix_from_pairs(len(responses), lambda i, j: 1.0)
+
+        distributions = [_to_distribution(tokens, vocab) for tokens in tokenized]
+        return _matrix_from_pairs(
+            len(responses),
+            lambda i, j: 1.0 - _js_divergence(distributions[i], distributions[j]),
+        )
+
+
+class MiniLMBackend:
+    name = "minilm"
+    version = "all-MiniLM-L6-v2"
+    cluster_threshold = 0.8  # cosine similarity on normalized embeddings
+
+    def similarity_matrix(self, responses: List[str]) -> List[List[float]]:
+        if _embeddings_disabled():
+            raise RuntimeError("Embeddings disabled via ORIPHIM_DISABLE_EMBEDDINGS")
+        model = _get_embedding_model()
+        embeddings = _get_cached_embeddings(responses, model)
+        return _matrix_from_pairs(len(responses), lambda i, j: float(np.dot(embeddings[i], embeddings[j])))
+
+
+class OnnxMiniLMBackend:
+    """all-MiniLM-L6-v2 on onnxruntime's CPU provider (no torch dependency).
+
+    Expects ORIPHIM_ONNX_MODEL_DIR to contain model.onnx and tokenizer.json.
+    The version string pins the exact model file so stored verdicts stay traceable.
+    """
+
+    name = "onnx-minilm"
+    cluster_threshold = 0.8
+
+    def __init__(self) -> None:
+        self._session = None
+        self._tokenizer = None
+        self._model_digest: Optional[str] = None
+        self._lock = threading.Lock()
+
+    @property
+    def version(self) -> str:
+        self._load()
+        return f"all-MiniLM-L6-v2-onnx@{self._model_digest}"
+
+    def _load(self) -> None:
+        with self._lock:
+            if self._session is not None:
+                return
+            import onnxruntime as ort
+            from tokenizers import Tokenizer
+
+            model_dir = os.getenv("ORIPHIM_ONNX_MODEL_DIR")
+            if not model_dir:
+                raise RuntimeError("ORIPHIM_ONNX_MODEL_DIR is not set")
+            model_path = os.path.join(model_dir, "model.onnx")
+            with open(model_path, "rb") as handle:
+                self._model_digest = hashlib.sha256(handle.read()).hexdigest()[:12]
+
+            tokenizer = Tokenizer.from_file(os.path.join(model_dir, "tokenizer.json"))
+            tokenizer.enable_padding()
+            tokenizer.enable_truncation(max_length=256)
+            self._tokenizer = tokenizer
+            self._session = ort.InferenceSession(model_path, providers=["CPUExecutionProvider"])
+
+    def _embed(self, responses: List[str]) -> np.ndarray:
+        self._load()
+        encodings = self._tokenizer.encode_batch([response.strip() for response in responses])
+        input_ids = np.array([e.ids for e in encodings], dtype=np.int64)
+        attention_mask = np.array([e.attention_mask for e in encodings], dtype=np.int64)
+        feeds = {"input_ids": input_ids, "attention_mask": attention_mask}
+        input_names = {i.name for i in self._session.get_inputs()}
+        if "token_type_ids" in input_names:
+            feeds["token_type_ids"] = np.zeros_like(input_ids)
+
+        token_embeddings = self._session.run(None, feeds)[0]
+        # Mean pooling over non-padding tokens, then L2 normalize (matches sentence-transformers)
+        mask = attention_mask[..., None].astype(np.float32)
+        pooled = (token_embeddings * mask).sum(axis=1) / np.clip(mask.sum(axis=1), 1e-9, None)
+        norms = np.clip(np.linalg.norm(pooled, axis=1, keepdims=True), 1e-12, None)
+        return pooled / norms
+
+    def similarity_matrix(self, responses: List[str]) -> List[List[float]]:
+        embeddings = self._embed(responses)
+        return _matrix_from_pairs(len(responses), lambda i, j: float(np.dot(embeddings[i], embeddings[j])))
+
+
+class NumericClaimBackend:
+    """Similarity = share of asserted numeric claims that agree between two samples.
+
+    Complements semantic scorers, which treat "buy 100" and "buy 1000" as near-identical.
+    Claims come from app.core.numeric_claims and only match claims of the same kind
+    within that kind's tolerance. Samples that assert no numbers are treated as agreeing.
+    """
+
+    name = "numeric-claims"
+    version = "1.1"
+    cluster_threshold = 0.99
+
+    def _agreement(self, left: List[NumericClaim], right: List[NumericClaim]) -> float:
+        if not left and not right:
+            return 1.0
+        if not left or not right:
+            return 0.0
+        remaining = list(right)
+        matched = 0
+        for claim in left:
+            tolerance = CLAIM_TOLERANCES.get(claim.kind, 0.0)
+            for index, candidate in enumerate(remaining):
+                if candidate.kind != claim.kind or candidate.unit != claim.unit:
+                    continue
+                scale = max(min(abs(claim.value), abs(candidate.value)), 1e-9)
+                if abs(claim.value - candidate.value) / scale <= tolerance:
+                    matched += 1
+                    del remaining[index]
+                    break
+        return matched / max(len(left), len(right))
+
+    def similarity_matrix(self, responses: List[str]) -> List[List[float]]:
+        claims = [extract_claims(response) for response in responses]
+        return _matrix_from_pairs(len(responses), lambda i, j: self._agreement(claims[i], claims[j]))
+
+
+class EnsembleBackend:
+    """Weighted average of member similarity matrices and cluster thresholds."""
+
+    name = "ensemble"
+
+    def __init__(self, members: List[tuple[DivergenceBackend, float]]) -> None:
+        total = sum(weight for _, weight in members)
+        if not members or total <= 0:
+            raise UnknownBackendError("Ensemble requires at least one member with a positive weight")
+        self.members = [(backend, weight / total) for backend, weight in members]
+        self.cluster_threshold = sum(backend.cluster_threshold * weight for backend, weight in self.members)
+
+    @property
+    def version(self) -> str:
+        return "+".join(
+            f"{backend.name}@{backend.version}:{weight:.3f}" for backend, weight in self.members
+        )
+
+    def similarity_matrix(self, responses: List[str]) -> List[List[float]]:
+        size = len(responses)
+        combined = [[0.0] * size for _ in range(size)]
+        for backend, weight in self.members:
+            matrix = backend.similarity_matrix(responses)
+            for i in range(size):
+                for j in range(size):
+                    combined[i][j] += weight * matrix[i][j]
+        return combined
+
+
+_FACTORIES: Dict[str, Callable[[], DivergenceBackend]] = {
+    LexicalJSBackend.name: LexicalJSBackend,
+    MiniLMBackend.name: MiniLMBackend,
+    OnnxMiniLMBackend.name: OnnxMiniLMBackend,
+    NumericClaimBackend.name: NumericClaimBackend,
+}
+_INSTANCES: Dict[str, DivergenceBackend] = {}
+_REGISTRY_LOCK = threading.Lock()
+_ENTRY_POINTS_LOADED = False
+
+
+def register_backend(name: str, factory: Callable[[], DivergenceBackend]) -> None:
+    """Register (or replace) a backend factory under `name`."""
+    with _REGISTRY_LOCK:
+        _FACTORIES[name] = factory
+        _INSTANCES.pop(name, None)
+
+
+def _load_entry_point_backends() -> None:
+    global _ENTRY_POINTS_LOADED
+    if _ENTRY_POINTS_LOADED:
+        return
+    _ENTRY_POINTS_LOADED = True
+
+    from importlib.metadata import entry_points
+
+    for entry_point in entry_points(group=ENTRY_POINT_GROUP):
+        if entry_point.name in _FACTORIES:
+            logger.warning("Ignoring entry point backend '%s': name already registered", entry_point.name)
+            continue
+        try:
+            _FACTORIES[entry_point.name] = entry_point.load()
+        except Exception:
+            logger.warning("Failed to load divergence backend entry point '%s'", entry_point.name, exc_info=True)
+
+
+def list_backends() -> List[str]:
+    with _REGISTRY_LOCK:
+        _load_entry_point_backends()
+        return sorted(_FACTORIES)
+
+
+def get_backend(name: str) -> DivergenceBackend:
+    with _REGISTRY_LOCK:
+        _load_entry_point_backends()
+        if name not in _INSTANCES:
+            factory = _FACTORIES.get(name)
+            if factory is None:
+                raise UnknownBackendError(f"Unknown divergence backend '{name}'")
+            _INSTANCES[name] = factory()
+        return _INSTANCES[name]
+
+
+def default_backend_name() -> str:
+    return LexicalJSBackend.name if _embeddings_disabled() else MiniLMBackend.name
+
+
+def resolve_backend(
+    name: Optional[str] = None,
+    ensemble: Optional[Dict[str, float]] = None,
+    tenant_settings: Optional[Dict[str, object]] = None,
+) -> DivergenceBackend:
+    """
+    Pick the backend for a validation: request choice, then tenant default, then global default.
+
+    A tenant default that no longer resolves (for example a plugin that was uninstalled)
+    falls back to the global default instead of failing every validation.
+    """
+    if name is not None and ensemble is not None:
+        raise ValueError("Specify either divergence_backend or divergence_ensemble, not both")
+
+    if name is None and ensemble is None and tenant_settings:
+        try:
+            return resolve_backend(
+                name=tenant_settings.get("divergence_backend"),  # type: ignore[arg-type]
+                ensemble=tenant_settings.get("divergence_ensemble"),  # type: ignore[arg-type]
+            )
+        except ValueError:
+            logger.warning("Tenant divergence backend setting %s does not resolve; using the default", tenant_settings)
+            return get_backend(default_backend_name())
+
+    if ensemble:
+        if len(ensemble) > MAX_ENSEMBLE_MEMBERS:
+            raise UnknownBackendError(f"Ensemble supports at most {MAX_ENSEMBLE_MEMBERS} members")
+        return EnsembleBackend([(get_backend(member), float(weight)) for member, weight in ensemble.items()])
+
+    return get_backend(name or default_backend_name())
+
+
+def fallback_backend() -> DivergenceBackend:
+    """Backend used when the selected one fails at runtime (model missing, ONNX not installed)."""
+    return get_backend(LexicalJSBackend.name)
@@ -4,13 +4,54 @@ Detects when LLM behavior degrades or changes significantly
 
 LATENCY OPTIMIZATION: Uses Welford's algorithm for O(1) incremental mean/variance
 instead of storing rolling window for O(n) recomputation each request.
+
+Baselines are kept per (tenant, agent) so one noisy agent cannot shift anyone else's
+z-scores. Tracker state is persisted through storage.py on every update and reloaded
+on the next, so it survives restarts. The read-score-save cycle is guarded by a
+process-local lock only: like the account ledger, this assumes one worker per database.
+
+The z-score is one of several detectors (see drift_detectors.py); the tenant setting
+`drift_detectors` chooses which of them can raise an alert.
+
+Alerts (detections and HEALTHY/DEGRADED/CRITICAL transitions of a scope) are written to
+the chained runtime audit log by record_drift_audit_events and served by /v3/alerts.
 """
 
+import threading
 from collections import deque
-from dataclasses import dataclass
-from typing import Optional
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Sequence
 import statistics
 
+from app.core.drift_detectors import (
+    DETECTOR_TYPES,
+    DETECTORS_SETTING,
+    DetectorConfig,
+    DetectorResult,
+    parse_detector_config,
+)
+from app.core.storage import (
+    get_drift_state,
+    get_tenant_validation_settings,
+    insert_audit_event,
+    list_drift_states,
+    save_drift_state,
+)
+
+ZSCORE_THRESHOLD = 2.5
+ZSCORE_MIN_HISTORY = 5
+
+# Audit event per detector signal; divergence detectors share one event per result
+AUDITED_DETECTORS = {
+    "divergence": "DRIFT_DETECTED",
+    "embeddings": "SEMANTIC_DRIFT_DETECTED",
+    "violations": "VIOLATION_RATE_DRIFT_DETECTED",
+}
+HEALTH_EVENT = "HEALTH_STATUS_CHANGED"
+ALERT_EVENT_TYPES = (*AUDITED_DETECTORS.values(), HEALTH_EVENT)
+# Blocked executions reported as /v2/health last_critical_violation (/v3 and /v2 markers)
+CRITICAL_VIOLATION_EVENT_TYPES = ("EXECUTION_BLOCKED_424", "EXECUTION_BLOCKED")
+
 
 @dataclass
 class DriftAlert:
@@ -19,6 +60,10 @@ class DriftAlert:
     historical_mean: float
     current_value: float
     explanation: str
+    fired_detectors: List[str] = field(default_factory=list)  # Enabled detectors that fired
+    detectors: Dict[str, Dict[str, Any]] = field(default_factory=dict)  # name -> statistic, threshold, ...
+    health_status: Optional[str] = None  # Scope status after this result (set by DriftRegistry.record)
+    previous_health_status: Optional[str] = None  # Differs from health_status on a transition
 
 
 class IncrementalStats:
@@ -66,14 +111,31 @@ class IncrementalStats:
         instance.M2 = float(state.get("M2", 0.0))
         return instance
 
+    @classmethod
+    def combine(cls, parts: Iterable['IncrementalStats']) -> 'IncrementalStats':
+        """Pool several streams (Chan et al. parallel update), e.g. all agents of a tenant."""
+        pooled = cls()
+        for part in parts:
+            if part.count == 0:
+                continue
+            count = pooled.count + part.count
+            delta = part.mean - pooled.mean
+            pooled.M2 += part.M2 + delta * delta * pooled.count * part.count / count
+            pooled.mean += delta * part.count / count
+            pooled.count = count
+        return pooled
+
 
 class RequestHistory:
-    """In-memory drift tracker using Welford's algorithm for O(1) updates."""
+    """Drift tracker for one scope using Welford's algorithm for O(1) updates."""
 
     def __init__(self, window_size: int = 100):
         self.window_size = window_size
         self.stats = IncrementalStats()
         self.violation_counts = deque(maxlen=window_size)
+        self.drift_detected = False  # Outcome of the latest observation
+        self.health_status = "HEALTHY"  # Status after the latest observation
+        self.detector_states: Dict[str, Dict[str, Any]] = {}  # Change-point detector name -> state
 
     def record(self, divergence: float, violation_count: int):
         """Record a validation result. O(1) time."""
@@ -84,6 +146,100 @@ class RequestHistory:
         """Return mean and std dev of all history."""
         return self.stats.get_stats()
 
+    def violation_rate(self) -> float:
+        if not self.violation_counts:
+            return 0.0
+        return sum(self.violation_counts) / len(self.violation_counts)
+
+    def compute_health_status(self) -> str:
+        """HEALTHY / DEGRADED / CRITICAL from mean divergence and the recent violation rate."""
+        mean = self.get_stats()["mean"]
+        violation_rate = self.violation_rate()
+        if mean > 0.5 or violation_rate > 0.5:
+            return "CRITICAL"
+        if mean > 0.35 or violation_rate > 0.3:
+            return "DEGRADED"
+        return "HEALTHY"
+
+    def to_dict(self) -> dict:
+        return {
+            "window_size": self.window_size,
+            "stats": self.stats.to_dict(),
+            "violation_counts": list(self.violation_counts),
+            "drift_detected": self.drift_detected,
+            "health_status": self.health_status,
+            "detectors": self.detector_states,
+        }
+
+    @classmethod
+    def from_dict(cls, state: dict) -> 'RequestHistory':
+        instance = cls(window_size=int(state.get("window_size", 100)))
+        instance.stats = IncrementalStats.from_dict(state.get("stats", {}))
+        instance.violation_counts.extend(state.get("violation_counts", []))
+        instance.drift_detected = bool(state.get("drift_detected", False))
+        instance.health_status = state.get("health_status", "HEALTHY")
+        instance.detector_states = dict(state.get("detectors", {}))
+        return instance
+
+    @classmethod
+    def combine(cls, parts: Iterable['RequestHistory'], window_size: int = 100) -> 'RequestHistory':
+        """Read-only view over several trackers (rates use each tracker's recent window)."""
+        parts = list(parts)
+        pooled = cls(window_size=window_size * max(len(parts), 1))
+        pooled.stats = IncrementalStats.combine(part.stats for part in parts)
+        for part in parts:
+            pooled.violation_counts.extend(part.violation_counts)
+        pooled.drift_detected = any(part.drift_detected for part in parts)
+        pooled.health_status = pooled.compute_health_status()
+        return pooled
+
+    def observe(
+        self,
+        divergence: float,
+        violation_count: int,
+        config: Optional[DetectorConfig] = None,
+        embeddings: Optional[Sequence[Sequence[float]]] = None,
+    ) -> DriftAlert:
+        """
+        Score a result with the z-score (against the baseline before it) and every
+        other detector, then record it. Only `config.enabled` detectors can set
+        `detected`; all of them report their statistics. Without `embeddings` the
+        semantic detector reports ready=False and keeps its state.
+        """
+        config = config or DetectorConfig()
+        stats = self.get_stats()
+        alert = self.detect_drift(divergence)
+        results = {
+            "zscore": DetectorResult(
+                "zscore",
+                alert.detected,
+                abs(alert.z_score),
+                ZSCORE_THRESHOLD,
+                ready=stats["count"] >= ZSCORE_MIN_HISTORY and stats["std_dev"] > 0,
+            )
+        }
+        signals = {"divergence": divergence, "violations": violation_count, "embeddings": embeddings}
+        for name, detector in config.build().items():
+            detector.load(self.detector_states.get(name, {}))
+            results[name] = detector.update(signals[detector.signal])
+            self.detector_states[name] = detector.state()
+        self.record(divergence, violation_count)
+
+        fired = [name for name in config.enabled if results[name].fired]
+        if fired and fired != ["zscore"]:
+            alert.explanation = f"Drift detected by {', '.join(fired)} (z-score={alert.z_score:.2f})."
+        elif not fired and alert.detected:
+            alert.explanation = (
+                f"Z-score={alert.z_score:.2f} exceeds {ZSCORE_THRESHOLD} but the z-score detector is disabled."
+            )
+        alert.detected = bool(fired)
+        alert.fired_detectors = fired
+        alert.detectors = {
+            name: {**result.to_dict(), "enabled": name in config.enabled} for name, result in results.items()
+        }
+        self.drift_detected = alert.detected
+        return alert
+
     def detect_drift(self, current_divergence: float) -> DriftAlert:
         """
         Detect if current divergence is anomalous.
@@ -95,7 +251,7 @@ class RequestHistory:
         """
         stats = self.get_stats()
 
-        if stats["count"] < 5:  # Not enough history
+        if stats["count"] < ZSCORE_MIN_HISTORY:  # Not enough history
             return DriftAlert(
                 detected=False,
                 z_score=0.0,
@@ -117,7 +273,8 @@ class RequestHistory:
             )
 
         z_score = (current_divergence - mean) / std_dev  # Protected by std_dev > 0 check above
-        detected = abs(z_score) > 2.5
+        detected = abs(z_score) > ZSCORE_THRESHOLD
+        self.drift_detected = detected
 
         if detected:
             explanation = (
@@ -139,5 +296,103 @@ class RequestHistory:
         )
 
 
-# Global history tracker
-request_history = RequestHistory(window_size=100)
+class DriftRegistry:
+    """Drift trackers keyed by (tenant_id, agent_id); None means "no tenant"/"no agent"."""
+
+    def __init__(self, window_size: int = 100):
+        self.window_size = window_size
+        self._lock = threading.Lock()
+
+    def get(self, tenant_id: Optional[str], agent_id: Optional[str]) -> RequestHistory:
+        state = get_drift_state(tenant_id, agent_id)
+        return RequestHistory.from_dict(state) if state else RequestHistory(self.window_size)
+
+    def record(
+        self,
+        tenant_id: Optional[str],
+        agent_id: Optional[str],
+        divergence: float,
+        violation_count: int,
+        embeddings: Optional[Sequence[Sequence[float]]] = None,
+    ) -> DriftAlert:
+        """Score this result against the scope's baseline, then add it to the baseline."""
+        config = parse_detector_config(get_tenant_validation_settings(tenant_id).get(DETECTORS_SETTING))
+        with self._lock:
+            history = self.get(tenant_id, agent_id)
+            # Scoring first keeps an outlier from inflating the std_dev it is judged by
+            alert = history.observe(divergence, violation_count, config, embeddings)
+            alert.previous_health_status = history.health_status
+            alert.health_status = history.health_status = history.compute_health_status()
+            save_drift_state(tenant_id, agent_id, history.to_dict())
+        return alert
+
+    def scope(self, tenant_id: Optional[str], agent_id: Optional[str] = None) -> RequestHistory:
+        """One agent's tracker, or every agent of the tenant pooled when agent_id is None."""
+        if agent_id is not None:
+            return self.get(tenant_id, agent_id)
+        return RequestHistory.combine(
+            (RequestHistory.from_dict(entry["state"]) for entry in list_drift_states(tenant_id)),
+            window_size=self.window_size,
+        )
+
+
+def _detector_summary(name: str, result: Dict[str, Any]) -> str:
+    detail = ", ".join(
+        f"{key}={value}"
+        for key, value in sorted(result.items())
+        if key not in ("fired", "statistic", "threshold", "ready", "enabled")
+    )
+    summary = f"{name} statistic {result['statistic']:.2f} exceeds {result['threshold']:.2f}"
+    return f"{summary} ({detail})" if detail else summary
+
+
+def record_drift_audit_events(
+    request_id: str,
+    tenant_id: Optional[str],
+    agent_id: Optional[str],
+    alert: DriftAlert,
+) -> List[int]:
+    """
+    Write the alert's detections and health transition to the runtime audit log:
+    one event per fired signal (see AUDITED_DETECTORS) plus HEALTH_STATUS_CHANGED.
+    Returns the audit ids.
+    """
+    by_signal: Dict[str, List[str]] = {}
+    for name in alert.fired_detectors:
+        signal = DETECTOR_TYPES[name].signal if name in DETECTOR_TYPES else "divergence"
+        by_signal.setdefault(signal, []).append(name)
+
+    agent = agent_id or "unknown"
+    events = [
+        (
+            AUDITED_DETECTORS[signal],
+            f"Drift detected for agent {agent} by {', '.join(names)}: "
+            + "; ".join(_detector_summary(name, alert.detectors[name]) for name in names)
+            + ".",
+        )
+        for signal, names in by_signal.items()
+    ]
+    if alert.previous_health_status and alert.health_status != alert.previous_health_status:
+        events.append(
+            (
+                HEALTH_EVENT,
+                f"Health of agent {agent} changed from {alert.previous_health_status} to {alert.health_status} "
+                f"(divergence={alert.current_value:.3f}).",
+            )
+        )
+    return [
+        insert_audit_event(
+            request_id=request_id,
+            tenant_id=tenant_id,
+            agent_id=agent_id,
+            event_type=event_type,
+            violations=[],
+            regulatory_articles=["EU-AIA-12-RecordKeeping"],
+            message=message,
+        )
+        for event_type, message in events
+    ]
+
+
+# Global registry; state lives in storage, not in the process
+drift_registry = DriftRegistry(window_size=100)
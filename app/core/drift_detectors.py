@@ -0,0 +1,491 @@
+"""
+Change-Point Detectors
+Sequential drift detectors that complement the all-time z-score in drift.py.
+
+- cusum:        two-sided standardized CUSUM against a warm-up baseline (sustained shifts)
+- page_hinkley: two-sided Page-Hinkley test against the running mean (gradual shifts)
+- ewma:         EWMA control chart with time-varying limits (small persistent shifts)
+- ks:           two-sample Kolmogorov-Smirnov test, reference vs recent sliding window
+
+Two detectors watch other signals than the divergence score:
+
+- semantic:       distance of a request's mean sample embedding from the agent's running
+                  centroid, and the spread of its samples, against their own history
+- violation_rate: share of recent results with violations vs the share before them
+                  (one-sided two-proportion z-test; only increases alert)
+
+Every detector sees every result so enabling one later does not start it cold; the tenant
+setting `drift_detectors` picks which of them count towards `DriftAlert.detected` and
+overrides their parameters. Detector state is serialized with the tracker (storage.drift_states).
+"""
+
+from __future__ import annotations
+
+import math
+from abc import ABC, abstractmethod
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, ClassVar, Dict, List, Optional, Sequence, Tuple, Type
+
+DEFAULT_ENABLED_DETECTORS = ("zscore", "semantic", "violation_rate")
+DETECTORS_SETTING = "drift_detectors"
+
+
+@dataclass
+class DetectorResult:
+    name: str
+    fired: bool
+    statistic: float
+    threshold: float
+    ready: bool = True  # False while the detector is still collecting its baseline
+    detail: Dict[str, Any] = field(default_factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "fired": self.fired,
+            "statistic": round(self.statistic, 6),
+            "threshold": self.threshold,
+            "ready": self.ready,
+            **self.detail,
+        }
+
+
+class DriftDetector(ABC):
+    name: ClassVar[str]
+    # What update() receives: the divergence score, the violation count or the sample embeddings
+    signal: ClassVar[str] = "divergence"
+    # name -> (default, minimum, maximum); bounds are inclusive
+    PARAMETERS: ClassVar[Dict[str, Tuple[float, float, float]]]
+
+    def __init__(self, **params: float):
+        unknown = set(params) - set(self.PARAMETERS)
+        if unknown:
+            raise ValueError(f"{self.name}: unknown parameters {sorted(unknown)}")
+        self.params: Dict[str, float] = {}
+        for key, (default, low, high) in self.PARAMETERS.items():
+            value = params.get(key, default)
+            if isinstance(value, bool) or not isinstance(value, (int, float)) or not low <= value <= high:
+                raise ValueError(f"{self.name}.{key} must be a number in [{low}, {high}]")
+            self.params[key] = value
+
+    @abstractmethod
+    def update(self, value: float) -> DetectorResult:
+        """Score `value` against the current state, then absorb it."""
+
+    @abstractmethod
+    def state(self) -> Dict[str, Any]:
+        ...
+
+    @abstractmethod
+    def load(self, state: Dict[str, Any]) -> None:
+        ...
+
+
+class _Baseline:
+    """Welford mean/std; warm-up baselines stop adding once full, running ones never do."""
+
+    def __init__(self):
+        self.count = 0
+        self.mean = 0.0
+        self.M2 = 0.0
+
+    def add(self, value: float) -> None:
+        self.count += 1
+        delta = value - self.mean
+        self.mean += delta / self.count
+        self.M2 += delta * (value - self.mean)
+
+    def std(self, floor: float) -> float:
+        if self.count < 2:
+            return floor
+        return max((self.M2 / (self.count - 1)) ** 0.5, floor)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"count": self.count, "mean": self.mean, "M2": self.M2}
+
+    def load(self, state: Dict[str, Any]) -> None:
+        self.count = int(state.get("count", 0))
+        self.mean = float(state.get("mean", 0.0))
+        self.M2 = float(state.get("M2", 0.0))
+
+
+class CusumDetector(DriftDetector):
+    """Tabular CUSUM in baseline standard deviations: fires when S+ or S- exceeds h."""
+
+    name = "cusum"
+    PARAMETERS = {
+        "warmup": (20, 5, 1000),
+        "k": (0.5, 0.0, 5.0),  # Slack, in std units
+        "h": (5.0, 0.5, 50.0),  # Decision interval, in std units
+        "min_std": (0.01, 1e-6, 1.0),
+    }
+
+    def __init__(self, **params: float):
+        super().__init__(**params)
+        self.baseline = _Baseline()
+        self.upper = 0.0
+        self.lower = 0.0
+
+    def update(self, value: float) -> DetectorResult:
+        h = self.params["h"]
+        if self.baseline.count < self.params["warmup"]:
+            self.baseline.add(value)
+            return DetectorResult(self.name, False, 0.0, h, ready=False)
+        z = (value - self.baseline.mean) / self.baseline.std(self.params["min_std"])
+        self.upper = max(0.0, self.upper + z - self.params["k"])
+        self.lower = max(0.0, self.lower - z - self.params["k"])
+        statistic = max(self.upper, self.lower)
+        direction = "increase" if self.upper >= self.lower else "decrease"
+        fired = statistic > h
+        if fired:
+            # Restart accumulation so one shift raises one alarm run, not a permanent one
+            self.upper = self.lower = 0.0
+        return DetectorResult(self.name, fired, statistic, h, detail={"direction": direction})
+
+    def state(self) -> Dict[str, Any]:
+        return {"baseline": self.baseline.to_dict(), "upper": self.upper, "lower": self.lower}
+
+    def load(self, state: Dict[str, Any]) -> None:
+        self.baseline.load(state.get("baseline", {}))
+        self.upper = float(state.get("upper", 0.0))
+        self.lower = float(state.get("lower", 0.0))
+
+
+class PageHinkleyDetector(DriftDetector):
+    """Cumulative deviation from the running mean minus its extreme; fires above `threshold`."""
+
+    name = "page_hinkley"
+    PARAMETERS = {
+        "delta": (0.01, 0.0, 1.0),  # Tolerated deviation per observation
+        "threshold": (0.5, 0.01, 100.0),
+        "min_samples": (20, 1, 1000),
+    }
+
+    def __init__(self, **params: float):
+        super().__init__(**params)
+        self._reset()
+
+    def _reset(self) -> None:
+        self.count = 0
+        self.mean = 0.0
+        self.cumulative_up = 0.0
+        self.minimum_up = 0.0
+        self.cumulative_down = 0.0
+        self.maximum_down = 0.0
+
+    def update(self, value: float) -> DetectorResult:
+        threshold = self.params["threshold"]
+        self.count += 1
+        self.mean += (value - self.mean) / self.count
+        self.cumulative_up += value - self.mean - self.params["delta"]
+        self.minimum_up = min(self.minimum_up, self.cumulative_up)
+        self.cumulative_down += value - self.mean + self.params["delta"]
+        self.maximum_down = max(self.maximum_down, self.cumulative_down)
+        up = self.cumulative_up - self.minimum_up
+        down = self.maximum_down - self.cumulative_down
+        statistic = max(up, down)
+        ready = self.count >= self.params["min_samples"]
+        fired = ready and statistic > threshold
+        result = DetectorResult(
+            self.name, fired, statistic, threshold, ready=ready,
+            detail={"direction": "increase" if up >= down else "decrease"},
+        )
+        if fired:
+            # The post-change regime becomes the new reference
+            self._reset()
+        return result
+
+    def state(self) -> Dict[str, Any]:
+        return {
+            "count": self.count,
+            "mean": self.mean,
+            "cumulative_up": self.cumulative_up,
+            "minimum_up": self.minimum_up,
+            "cumulative_down": self.cumulative_down,
+            "maximum_down": self.maximum_down,
+        }
+
+    def load(self, state: Dict[str, Any]) -> None:
+        self.count = int(state.get("count", 0))
+        for key in ("mean", "cumulative_up", "minimum_up", "cumulative_down", "maximum_down"):
+            setattr(self, key, float(state.get(key, 0.0)))
+
+
+class EwmaDetector(DriftDetector):
+    """EWMA chart: fires when the smoothed value leaves mean +/- L * sigma_ewma(t)."""
+
+    name = "ewma"
+    PARAMETERS = {
+        "lambda": (0.2, 0.01, 1.0),
+        "L": (3.0, 0.5, 10.0),
+        "warmup": (20, 5, 1000),
+        "min_std": (0.01, 1e-6, 1.0),
+    }
+
+    def __init__(self, **params: float):
+        super().__init__(**params)
+        self.baseline = _Baseline()
+        self.smoothed: Optional[float] = None
+        self.steps = 0
+
+    def update(self, value: float) -> DetectorResult:
+        lam = self.params["lambda"]
+        if self.baseline.count < self.params["warmup"]:
+            self.baseline.add(value)
+            return DetectorResult(self.name, False, 0.0, self.params["L"], ready=False)
+        if self.smoothed is None:
+            self.smoothed = self.baseline.mean
+        self.steps += 1
+        self.smoothed = lam * value + (1 - lam) * self.smoothed
+        sigma = self.baseline.std(self.params["min_std"]) * math.sqrt(
+            lam / (2 - lam) * (1 - (1 - lam) ** (2 * self.steps))
+        )
+        statistic = abs(self.smoothed - self.baseline.mean) / sigma
+        return DetectorResult(
+            self.name,
+            statistic > self.params["L"],
+            statistic,
+            self.params["L"],
+            detail={"smoothed": round(self.smoothed, 6), "center": round(self.baseline.mean, 6)},
+        )
+
+    def state(self) -> Dict[str, Any]:
+        return {"baseline": self.baseline.to_dict(), "smoothed": self.smoothed, "steps": self.steps}
+
+    def load(self, state: Dict[str, Any]) -> None:
+        self.baseline.load(state.get("baseline", {}))
+        self.smoothed = state.get("smoothed")
+        self.steps = int(state.get("steps", 0))
+
+
+def ks_statistic(reference: List[float], current: List[float]) -> float:
+    """Two-sample Kolmogorov-Smirnov D: largest gap between the empirical CDFs."""
+    a, b = sorted(reference), sorted(current)
+    i = j = 0
+    d = 0.0
+    while i < len(a) and j < len(b):
+        x = min(a[i], b[j])
+        while i < len(a) and a[i] == x:
+            i += 1
+        while j < len(b) and b[j] == x:
+            j += 1
+        d = max(d, abs(i / len(a) - j / len(b)))
+    return d
+
+
+def ks_p_value(d: float, n: int, m: int) -> float:
+    """Asymptotic two-sided p-value (Kolmogorov distribution with Stephens' correction)."""
+    ne = n * m / (n + m)
+    lam = (math.sqrt(ne) + 0.12 + 0.11 / math.sqrt(ne)) * d
+    if lam < 1e-3:
+        return 1.0
+    total = sum((-1) ** (j - 1) * math.exp(-2 * j * j * lam * lam) for j in range(1, 101))
+    return min(1.0, max(0.0, 2 * total))
+
+
+class KsWindowDetector(DriftDetector):
+    """Compares the latest `window` results with the `window` before them."""
+
+    name = "ks"
+    PARAMETERS = {
+        "window": (30, 5, 500),
+        "alpha": (0.01, 1e-6, 0.2),
+    }
+
+    def __init__(self, **params: float):
+        super().__init__(**params)
+        self.values: deque = deque(maxlen=2 * int(self.params["window"]))
+
+    def update(self, value: float) -> DetectorResult:
+        self.values.append(value)
+        alpha = self.params["alpha"]
+        window = int(self.params["window"])
+        if len(self.values) < 2 * window:
+            return DetectorResult(self.name, False, 0.0, alpha, ready=False)
+        values = list(self.values)
+        d = ks_statistic(values[:window], values[window:])
+        p_value = ks_p_value(d, window, window)
+        return DetectorResult(self.name, p_value < alpha, d, alpha, detail={"p_value": round(p_value, 6)})
+
+    def state(self) -> Dict[str, Any]:
+        return {"values": list(self.values)}
+
+    def load(self, state: Dict[str, Any]) -> None:
+        # A changed window keeps the most recent values that still fit
+        self.values.extend(state.get("values", []))
+
+
+class ViolationRateDetector(DriftDetector):
+    """Recent violation share vs the preceding baseline window (one-sided z-test)."""
+
+    name = "violation_rate"
+    signal = "violations"
+    PARAMETERS = {
+        "recent": (20, 5, 500),
+        "baseline": (80, 10, 2000),
+        "z": (3.0, 1.0, 10.0),
+    }
+
+    def __init__(self, **params: float):
+        super().__init__(**params)
+        self.flags: deque = deque(maxlen=int(self.params["recent"] + self.params["baseline"]))
+
+    def update(self, value: float) -> DetectorResult:
+        self.flags.append(1 if value > 0 else 0)
+        threshold = self.params["z"]
+        recent_size = int(self.params["recent"])
+        flags = list(self.flags)
+        recent, earlier = flags[-recent_size:], flags[:-recent_size]
+        # Half the baseline window is enough history to estimate the reference rate
+        if len(earlier) < self.params["baseline"] / 2:
+            return DetectorResult(self.name, False, 0.0, threshold, ready=False)
+        recent_rate = sum(recent) / len(recent)
+        earlier_rate = sum(earlier) / len(earlier)
+        pooled = sum(flags) / len(flags)
+        se = math.sqrt(max(pooled * (1 - pooled), 1e-9) * (1 / len(recent) + 1 / len(earlier)))
+        statistic = (recent_rate - earlier_rate) / se
+        return DetectorResult(
+            self.name,
+            statistic > threshold,
+            statistic,
+            threshold,
+            detail={"recent_rate": round(recent_rate, 4), "baseline_rate": round(earlier_rate, 4)},
+        )
+
+    def state(self) -> Dict[str, Any]:
+        return {"flags": list(self.flags)}
+
+    def load(self, state: Dict[str, Any]) -> None:
+        self.flags.extend(state.get("flags", []))
+
+
+def _normalize(vector: List[float]) -> List[float]:
+    norm = math.sqrt(sum(x * x for x in vector))
+    return [x / norm for x in vector] if norm > 0 else vector
+
+
+def _cosine_distance(a: List[float], b: List[float]) -> float:
+    return 1.0 - sum(x * y for x, y in zip(_normalize(a), _normalize(b)))
+
+
+class SemanticCentroidDetector(DriftDetector):
+    """
+    Topic/behaviour shift even when samples agree with each other: the request's mean
+    embedding is compared with an exponentially weighted centroid of the agent's earlier
+    requests, and its within-request spread with earlier spreads. Fires when either
+    z-score exceeds `z`.
+    """
+
+    name = "semantic"
+    signal = "embeddings"
+    PARAMETERS = {
+        "decay": (0.05, 0.001, 1.0),  # Weight of each new request in the centroid
+        "min_history": (10, 2, 1000),
+        "z": (3.0, 1.0, 10.0),
+        "min_std": (0.01, 1e-6, 1.0),
+    }
+
+    def __init__(self, **params: float):
+        super().__init__(**params)
+        self.centroid: Optional[List[float]] = None
+        self.distance = _Baseline()
+        self.dispersion = _Baseline()
+
+    def update(self, value: Optional[Sequence[Sequence[float]]]) -> DetectorResult:
+        threshold = self.params["z"]
+        if not value:
+            # No embeddings for this request (model disabled or unavailable)
+            return DetectorResult(self.name, False, 0.0, threshold, ready=False)
+        samples = [_normalize([float(x) for x in row]) for row in value]
+        mean = [sum(column) / len(samples) for column in zip(*samples)]
+        dispersion = sum(_cosine_distance(sample, mean) for sample in samples) / len(samples)
+        if self.centroid is None or len(self.centroid) != len(mean):
+            # First request, or the embedding model changed dimension: start a new baseline
+            self.centroid = mean
+            self.distance = _Baseline()
+            self.dispersion = _Baseline()
+            self.dispersion.add(dispersion)
+            return DetectorResult(self.name, False, 0.0, threshold, ready=False)
+
+        distance = _cosine_distance(mean, self.centroid)
+        ready = self.distance.count >= self.params["min_history"]
+        distance_z = (distance - self.distance.mean) / self.distance.std(self.params["min_std"]) if ready else 0.0
+        dispersion_z = (
+            (dispersion - self.dispersion.mean) / self.dispersion.std(self.params["min_std"]) if ready else 0.0
+        )
+        statistic = max(distance_z, abs(dispersion_z))
+
+        decay = self.params["decay"]
+        self.centroid = [(1 - decay) * c + decay * m for c, m in zip(self.centroid, mean)]
+        self.distance.add(distance)
+        self.dispersion.add(dispersion)
+        return DetectorResult(
+            self.name,
+            ready and statistic > threshold,
+            statistic,
+            threshold,
+            ready=ready,
+            detail={
+                "centroid_distance": round(distance, 6),
+                "dispersion": round(dispersion, 6),
+                "distance_z": round(distance_z, 4),
+                "dispersion_z": round(dispersion_z, 4),
+            },
+        )
+
+    def state(self) -> Dict[str, Any]:
+        return {
+            "centroid": self.centroid,
+            "distance": self.distance.to_dict(),
+            "dispersion": self.dispersion.to_dict(),
+        }
+
+    def load(self, state: Dict[str, Any]) -> None:
+        self.centroid = state.get("centroid")
+        self.distance.load(state.get("distance", {}))
+        self.dispersion.load(state.get("dispersion", {}))
+
+
+DETECTOR_TYPES: Dict[str, Type[DriftDetector]] = {
+    detector.name: detector
+    for detector in (
+        CusumDetector,
+        PageHinkleyDetector,
+        EwmaDetector,
+        KsWindowDetector,
+        SemanticCentroidDetector,
+        ViolationRateDetector,
+    )
+}
+DETECTOR_NAMES = ("zscore", *DETECTOR_TYPES)
+
+
+@dataclass(frozen=True)
+class DetectorConfig:
+    enabled: Tuple[str, ...] = DEFAULT_ENABLED_DETECTORS
+    parameters: Dict[str, Dict[str, float]] = field(default_factory=dict)
+
+    def build(self) -> Dict[str, DriftDetector]:
+        return {name: cls(**self.parameters.get(name, {})) for name, cls in DETECTOR_TYPES.items()}
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"enabled": list(self.enabled), "parameters": self.parameters}
+
+
+def parse_detector_config(raw: Optional[Dict[str, Any]]) -> DetectorConfig:
+    """Validate a `drift_detectors` setting. Raises ValueError on unknown names or bad values."""
+    if not raw:
+        return DetectorConfig()
+    enabled = raw.get("enabled", list(DEFAULT_ENABLED_DETECTORS))
+    if not isinstance(enabled, list) or any(name not in DETECTOR_NAMES for name in enabled):
+        raise ValueError(f"enabled must be a list drawn from {list(DETECTOR_NAMES)}")
+    parameters = raw.get("parameters") or {}
+    if not isinstance(parameters, dict):
+        raise ValueError("parameters must map detector names to parameter objects")
+    for name, params in parameters.items():
+        if name not in DETECTOR_TYPES:
+            raise ValueError(f"Unknown detector '{name}' (configurable: {list(DETECTOR_TYPES)})")
+        if not isinstance(params, dict):
+            raise ValueError(f"parameters for '{name}' must be an object")
+        DETECTOR_TYPES[name](**params)  # Raises on unknown or out-of-range values
+    return DetectorConfig(enabled=tuple(dict.fromkeys(enabled)), parameters=parameters)
@@ -1,107 +1,208 @@
-from typing import List, Dict
+from typing import TYPE_CHECKING, List, Dict
 import math
 import os
 import re
 import threading
+import time
 from collections import OrderedDict
+from dataclasses import dataclass
 import numpy as np
 from sentence_transformers import SentenceTransformer
 
+if TYPE_CHECKING:
+    from app.core.divergence_backends import DivergenceBackend
 
-_EMBEDDING_LOCK = threading.Lock()  # Thread-safe cache access
-
-
-def semantic_entropy(samples: List[str]) -> float:
-    if not samples:
-        return 0.0
-
-    counts = {}
-    for sample in samples:
-        key = sample.strip().lower()
-        counts[key] = counts.get(key, 0) + 1
-
-    total = len(samples)
-    entropy = 0.0
-    for count in counts.values():
-        p = count / total
-        entropy -= p * math.log(p, 2)
 
-    max_entropy = math.log(total, 2) if total > 1 else 1.0
-    return min(entropy / max_entropy, 1.0)
+_EMBEDDING_LOCK = threading.Lock()  # Thread-safe cache access
 
 
 _EMBEDDING_MODEL: SentenceTransformer | None = None
 _EMBEDDING_CACHE: OrderedDict = OrderedDict()  # LRU cache: text -> embedding
 _CACHE_MAX_SIZE = 1000
 
+# Reported by the readiness probe (app/core/health.py)
+_EMBEDDING_LOAD_ERROR: str | None = None
+_LAST_LEXICAL_FALLBACK: float | None = None  # time.time() of the latest runtime fallback
+
 
 def _embeddings_disabled() -> bool:
     return os.getenv("ORIPHIM_DISABLE_EMBEDDINGS", "false").lower() == "true"
 
 
-def _lexical_divergence(responses: List[str]) -> float:
-    vocab = sorted({token for response in responses for token in _tokenize(response)})
-    if not vocab:
+def _note_lexical_fallback() -> None:
+    global _LAST_LEXICAL_FALLBACK
+    _LAST_LEXICAL_FALLBACK = time.time()
+
+
+# Entailment-style contradiction markers. Two samples share a meaning cluster only when
+# the selected divergence backend scores them as similar AND neither contradicts the other.
+_NEGATIONS = {"not", "no", "never", "none", "cannot", "can't", "don't", "doesn't", "won't", "isn't", "without"}
+_NEGATION_SCOPE = 3  # Content tokens after a negation that it applies to
+_FUNCTION_WORDS = {
+    "a", "an", "the", "is", "are", "was", "be", "to", "of", "in", "on", "at", "for", "and", "or",
+    "it", "this", "that", "we", "you", "i", "should", "will", "would", "do", "does",
+}
+_OPPOSITES = [
+    ("buy", "sell"),
+    ("long", "short"),
+    ("increase", "decrease"),
+    ("raise", "lower"),
+    ("up", "down"),
+    ("above", "below"),
+    ("call", "put"),
+    ("approve", "reject"),
+    ("open", "close"),
+]
+
+
+@dataclass
+class SemanticEntropyResult:
+    score: float  # Normalized entropy over meaning clusters, 0.0 to 1.0
+    clusters: List[int]  # Cluster id per sample, in input order
+    cluster_count: int
+    backend: str  # Divergence backend that produced the similarity scores
+    backend_version: str
+
+
+def _negated_terms(tokens: List[str]) -> set:
+    """Content tokens within _NEGATION_SCOPE content tokens after a negation."""
+    negated: set = set()
+    remaining = 0
+    for token in tokens:
+        if token in _NEGATIONS:
+            remaining = _NEGATION_SCOPE
+        elif remaining and token not in _FUNCTION_WORDS:
+            negated.add(token)
+            remaining -= 1
+    return negated
+
+
+def _contradicts(a: str, b: str) -> bool:
+    """Entailment-style check: flags pairs that embed closely but flip meaning.
+
+    Catches negation mismatches ("is safe" vs "is not safe") and opposing
+    directives ("buy AAPL" vs "sell AAPL") that cosine similarity scores as near-identical.
+    A negation only counts when it flips a content word both samples use, so
+    "buy without delay" vs "buy now" is not a contradiction.
+    """
+    words_a, words_b = _tokenize(a), _tokenize(b)
+    tokens_a, tokens_b = set(words_a), set(words_b)
+    shared = (tokens_a & tokens_b) - _NEGATIONS - _FUNCTION_WORDS
+    negated_a, negated_b = _negated_terms(words_a), _negated_terms(words_b)
+    if any((term in negated_a) != (term in negated_b) for term in shared):
+        return True
+    return _opposite_directive(tokens_a, tokens_b) is not None
+
+
+def _opposite_directive(tokens_a: set, tokens_b: set) -> tuple[str, str] | None:
+    """Return the (a-side, b-side) directive pair when the two token sets point opposite ways."""
+    for left, right in _OPPOSITES:
+        if left in tokens_a and right in tokens_b and left not in tokens_b and right not in tokens_a:
+            return left, right
+        if right in tokens_a and left in tokens_b and right not in tokens_b and left not in tokens_a:
+            return right, left
+    return None
+
+
+def cluster_samples(responses: List[str], similarity: List[List[float]], threshold: float) -> List[int]:
+    """Greedy meaning clustering: each sample joins the first cluster whose
+    representative is similar enough and mutually non-contradictory."""
+    clusters: List[int] = []
+    representatives: List[int] = []
+    for index, response in enumerate(responses):
+        assigned = None
+        for cluster_id, rep in enumerate(representatives):
+            if similarity[index][rep] >= threshold and not _contradicts(response, responses[rep]):
+                assigned = cluster_id
+                break
+        if assigned is None:
+            assigned = len(representatives)
+            representatives.append(index)
+        clusters.append(assigned)
+    return clusters
+
+
+def _cluster_entropy(clusters: List[int]) -> float:
+    total = len(clusters)
+    if total <= 1:
         return 0.0
 
-    distributions = [_to_distribution(_tokenize(response), vocab) for response in responses]
-    divergences = [
-        _js_divergence(distributions[0], distributions[1]),
-        _js_divergence(distributions[0], distributions[2]),
-        _js_divergence(distributions[1], distributions[2]),
-    ]
-    return float(min(max(sum(divergences) / len(divergences), 0.0), 1.0))
+    counts: Dict[int, int] = {}
+    for cluster_id in clusters:
+        counts[cluster_id] = counts.get(cluster_id, 0) + 1
+
+    entropy = 0.0
+    for count in counts.values():
+        p = count / total
+        entropy -= p * math.log(p, 2)
+    return float(min(max(entropy / math.log(total, 2), 0.0), 1.0))
 
 
-def hallucination_divergence(responses: List[str]) -> float:
+def semantic_entropy(responses: List[str], backend: "DivergenceBackend | None" = None) -> SemanticEntropyResult:
     """
-    Measures mathematical divergence between three responses using
-    semantic embeddings (all-MiniLM-L6-v2) and pairwise cosine distance.
-    Returns a score in [0.0, 1.0].
-    
-    LATENCY OPTIMIZATIONS:
-    1. Pre-warm model at startup (load_embedding_model)
-    2. Cache embeddings per request_id to avoid re-computing
-    3. Cap sample count at 3 (input validation)
+    Semantic entropy over N samples (MIN_SAMPLES..MAX_SAMPLES).
+
+    Samples are grouped into meaning clusters using the selected divergence backend
+    (see app.core.divergence_backends; defaults to all-MiniLM-L6-v2, or lexical JS
+    when embeddings are disabled) plus an entailment-style contradiction check.
+    The score is the entropy of the cluster distribution normalized by log2(N),
+    so 0.0 means every sample agrees and 1.0 means every sample says something different.
+
+    With few samples the score is coarsely quantized: for N=3 it can only be 0.0, 0.579
+    (one dissenting sample) or 1.0, so a single dissent already crosses the default 0.4
+    block threshold. Callers that need to tolerate one outlier should send more samples
+    (with N=10 one dissent scores 0.141).
     """
-    if len(responses) != 3:
-        raise ValueError(f"hallucination_divergence expects exactly 3 responses, got {len(responses)}")
+    from app.core.divergence_backends import default_backend_name, fallback_backend, resolve_backend
 
-    if all(not r.strip() for r in responses):
-        return 0.0
+    if len(responses) < 2:
+        raise ValueError(f"semantic_entropy expects at least 2 responses, got {len(responses)}")
 
-    if any(not r.strip() for r in responses) and any(r.strip() for r in responses):
-        return 1.0
-
-    if _embeddings_disabled():
-        return _lexical_divergence(responses)
+    if backend is None:
+        backend = resolve_backend()
 
     try:
-        model = _get_embedding_model()
-        embeddings = _get_cached_embeddings(responses, model)
+        similarity = backend.similarity_matrix(responses)
+        backend_version = backend.version
     except Exception:
-        import logging
-        logger = logging.getLogger(__name__)
-        logger.warning("Falling back to lexical divergence because embedding model is unavailable", exc_info=True)
-        return _lexical_divergence(responses)
-
-    cos_01 = float(np.dot(embeddings[0], embeddings[1]))
-    cos_02 = float(np.dot(embeddings[0], embeddings[2]))
-    cos_12 = float(np.dot(embeddings[1], embeddings[2]))
-
-    avg_cos = (cos_01 + cos_02 + cos_12) / 3.0
-    score = (1.0 - avg_cos) / 2.0
-    
-    # Clamp to [0, 1] and log if clamping occurs (indicates calculation issue)
-    clamped_score = float(min(max(score, 0.0), 1.0))
-    if clamped_score != score:
         import logging
         logger = logging.getLogger(__name__)
         logger.warning(
-            f"Entropy score clamped from {score:.4f} to {clamped_score:.4f}. "
-            f"Indicates potential calculation issue. cos_01={cos_01:.4f}, cos_02={cos_02:.4f}, cos_12={cos_12:.4f}"
+            "Falling back to lexical similarity because divergence backend '%s' is unavailable",
+            backend.name,
+            exc_info=True,
         )
-    return clamped_score
+        if backend.name == default_backend_name():
+            _note_lexical_fallback()  # A tenant's own backend failing does not make the process unready
+        backend = fallback_backend()
+        similarity = backend.similarity_matrix(responses)
+        backend_version = backend.version
+
+    clusters = cluster_samples(responses, similarity, backend.cluster_threshold)
+    score = _cluster_entropy(clusters)
+
+    # Empty and non-empty answers to the same prompt are a hallucination signal on their own.
+    if any(not r.strip() for r in responses) and any(r.strip() for r in responses):
+        score = 1.0
+
+    return SemanticEntropyResult(
+        score=score,
+        clusters=clusters,
+        cluster_count=len(set(clusters)),
+        backend=backend.name,
+        backend_version=backend_version,
+    )
+
+
+def hallucination_divergence(responses: List[str]) -> float:
+    """Semantic entropy score in [0.0, 1.0] for N responses (see semantic_entropy).
+
+    LATENCY OPTIMIZATIONS:
+    1. Pre-warm model at startup (load_embedding_model)
+    2. Cache embeddings per sample text to avoid re-computing
+    3. Cap sample count at MAX_SAMPLES (input validation)
+    """
+    return semantic_entropy(responses).score
 
 
 def _tokenize(text: str) -> List[str]:
@@ -143,9 +244,14 @@ def _kl_divergence(p: Dict[str, float], m: Dict[str, float]) -> float:
 
 def _get_embedding_model() -> SentenceTransformer:
     """Get or load the embedding model (lazy init or pre-warmed)."""
-    global _EMBEDDING_MODEL
+    global _EMBEDDING_MODEL, _EMBEDDING_LOAD_ERROR
     if _EMBEDDING_MODEL is None:
-        _EMBEDDING_MODEL = SentenceTransformer("all-MiniLM-L6-v2")
+        try:
+            _EMBEDDING_MODEL = SentenceTransformer("all-MiniLM-L6-v2")
+        except Exception as e:
+            _EMBEDDING_LOAD_ERROR = str(e)
+            raise
+        _EMBEDDING_LOAD_ERROR = None
     return _EMBEDDING_MODEL
 
 
@@ -155,17 +261,33 @@ def load_embedding_model() -> None:
     Call this during application startup to download and cache the model.
     Prevents first request timeout when model is downloaded on-demand.
     """
-    global _EMBEDDING_MODEL
     if _embeddings_disabled():
         return
     if _EMBEDDING_MODEL is None:
         import logging
         logger = logging.getLogger(__name__)
         logger.info("Pre-warming embedding model (all-MiniLM-L6-v2)...")
-        _EMBEDDING_MODEL = SentenceTransformer("all-MiniLM-L6-v2")
+        _get_embedding_model()
         logger.info("Embedding model loaded successfully")
 
 
+def embedding_model_status() -> Dict[str, object]:
+    """Load state of the embedding model: disabled, loaded, not_loaded or failed."""
+    if _embeddings_disabled():
+        state = "disabled"
+    elif _EMBEDDING_MODEL is not None:
+        state = "loaded"
+    elif _EMBEDDING_LOAD_ERROR is not None:
+        state = "failed"
+    else:
+        state = "not_loaded"
+    return {
+        "state": state,
+        "error": _EMBEDDING_LOAD_ERROR,
+        "last_lexical_fallback": _LAST_LEXICAL_FALLBACK,
+    }
+
+
 def _get_cached_embeddings(responses: List[str], model: SentenceTransformer) -> np.ndarray:
     """Retrieve embeddings from cache or compute and cache them.
     
@@ -205,3 +327,17 @@ def _get_cached_embeddings(responses: List[str], model: SentenceTransformer) ->
                 _EMBEDDING_CACHE.move_to_end(text)
     
     return np.array(embeddings_list)
+
+
+def sample_embeddings(responses: List[str]) -> List[List[float]] | None:
+    """Normalized sample embeddings for drift tracking; None when the model is off or unavailable."""
+    if _embeddings_disabled() or not responses:
+        return None
+    try:
+        embeddings = _get_cached_embeddings([str(response) for response in responses], _get_embedding_model())
+    except Exception:
+        import logging
+        logger = logging.getLogger(__name__)
+        logger.warning("Embedding model unavailable; skipping semantic drift for this request", exc_info=True)
+        return None
+    return [[float(x) for x in row] for row in embeddings]
@@ -0,0 +1,177 @@
+"""
+Validation Feedback
+Reviewer-labelled outcomes for /v3 validation results and /v4 pre-trade decisions, and the
+precision/recall metrics computed from them.
+
+A verdict counts as a positive ("flagged") when it stopped or escalated the action
+(BLOCK, REVIEW, MODIFY); a label of "unsafe" means the reviewer judged the action harmful.
+Per-rule metrics treat "the rule fired" as the prediction, and the divergence sweep
+re-scores labelled /v3 results at alternative cutoffs so DIVERGENCE_REVIEW_THRESHOLD can
+be compared against what reviewers actually saw.
+"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+from typing import Any, Dict, Iterable, List, Optional, Sequence
+
+from app.core.decision_engine import DIVERGENCE_REVIEW_THRESHOLD
+from app.core.storage import get_execution_decision, get_validation_result
+
+OUTCOME_SAFE = "safe"
+OUTCOME_UNSAFE = "unsafe"
+OUTCOMES = (OUTCOME_SAFE, OUTCOME_UNSAFE)
+
+REFERENCE_VALIDATION = "validation"
+REFERENCE_PRE_TRADE = "pre_trade"
+
+FLAGGED_ACTIONS = frozenset({"BLOCK", "REVIEW", "MODIFY"})
+
+DEFAULT_DIVERGENCE_THRESHOLDS = (0.2, 0.3, DIVERGENCE_REVIEW_THRESHOLD, 0.5, 0.6)
+
+
+@dataclass
+class ConfusionMatrix:
+    true_positive: int = 0
+    false_positive: int = 0
+    false_negative: int = 0
+    true_negative: int = 0
+
+    def add(self, predicted_unsafe: bool, outcome: str) -> None:
+        actual_unsafe = outcome == OUTCOME_UNSAFE
+        if predicted_unsafe and actual_unsafe:
+            self.true_positive += 1
+        elif predicted_unsafe:
+            self.false_positive += 1
+        elif actual_unsafe:
+            self.false_negative += 1
+        else:
+            self.true_negative += 1
+
+    @property
+    def total(self) -> int:
+        return self.true_positive + self.false_positive + self.false_negative + self.true_negative
+
+    @property
+    def precision(self) -> Optional[float]:
+        return _ratio(self.true_positive, self.true_positive + self.false_positive)
+
+    @property
+    def recall(self) -> Optional[float]:
+        return _ratio(self.true_positive, self.true_positive + self.false_negative)
+
+    @property
+    def f1(self) -> Optional[float]:
+        if self.precision is None or self.recall is None or self.precision + self.recall == 0:
+            return None
+        return round(2 * self.precision * self.recall / (self.precision + self.recall), 4)
+
+    @property
+    def accuracy(self) -> Optional[float]:
+        return _ratio(self.true_positive + self.true_negative, self.total)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "labelled": self.total,
+            "true_positive": self.true_positive,
+            "false_positive": self.false_positive,
+            "false_negative": self.false_negative,
+            "true_negative": self.true_negative,
+            "precision": self.precision,
+            "recall": self.recall,
+            "f1": self.f1,
+            "accuracy": self.accuracy,
+        }
+
+
+def _ratio(numerator: int, denominator: int) -> Optional[float]:
+    # None rather than 0 so "no data" is not mistaken for "always wrong"
+    if denominator == 0:
+        return None
+    return round(numerator / denominator, 4)
+
+
+def feedback_subject(
+    tenant_id: str,
+    request_id: Optional[str] = None,
+    decision_id: Optional[str] = None,
+) -> Optional[Dict[str, Any]]:
+    """
+    Snapshot of the labelled verdict (action, rules that fired, divergence score, agent).
+    Stored alongside the label so metrics do not depend on later schema changes.
+    Returns None when the reference does not exist for the tenant.
+    """
+    if request_id is not None:
+        result = get_validation_result(request_id, tenant_id=tenant_id)
+        if result is None:
+            return None
+        return {
+            "reference_type": REFERENCE_VALIDATION,
+            "reference_id": request_id,
+            "agent_id": result["agent_id"],
+            "action": result["action"],
+            "rule_ids": [record["rule_id"] for record in result["violation_records"] or []],
+            "divergence_score": result["divergence_score"],
+        }
+    if decision_id is not None:
+        decision = get_execution_decision(decision_id, tenant_id)
+        if decision is None:
+            return None
+        return {
+            "reference_type": REFERENCE_PRE_TRADE,
+            "reference_id": decision_id,
+            "agent_id": decision["agent_id"],
+            "action": decision["decision"],
+            "rule_ids": decision["triggered_controls"],
+            "divergence_score": None,
+        }
+    return None
+
+
+def compute_feedback_metrics(
+    entries: Iterable[Dict[str, Any]],
+    divergence_thresholds: Sequence[float] = DEFAULT_DIVERGENCE_THRESHOLDS,
+) -> Dict[str, Any]:
+    """Confusion matrices overall, per agent, per rule and per divergence cutoff."""
+    entries = list(entries)
+    overall = ConfusionMatrix()
+    by_reference: Dict[str, ConfusionMatrix] = {}
+    by_agent: Dict[str, ConfusionMatrix] = {}
+    for entry in entries:
+        flagged = entry["action"] in FLAGGED_ACTIONS
+        overall.add(flagged, entry["outcome"])
+        by_reference.setdefault(entry["reference_type"], ConfusionMatrix()).add(flagged, entry["outcome"])
+        if entry["agent_id"] is not None:
+            by_agent.setdefault(entry["agent_id"], ConfusionMatrix()).add(flagged, entry["outcome"])
+
+    # A rule is only judged against labels of the path it runs on: /v3 constraint rules
+    # say nothing about pre-trade decisions and vice versa.
+    by_rule: Dict[str, ConfusionMatrix] = {}
+    rule_paths = {
+        rule_id: entry["reference_type"] for entry in entries for rule_id in entry["rule_ids"]
+    }
+    for rule_id, reference_type in sorted(rule_paths.items()):
+        matrix = by_rule.setdefault(rule_id, ConfusionMatrix())
+        for entry in entries:
+            if entry["reference_type"] == reference_type:
+                matrix.add(rule_id in entry["rule_ids"], entry["outcome"])
+
+    scored = [e for e in entries if e["reference_type"] == REFERENCE_VALIDATION and e["divergence_score"] is not None]
+    sweep: List[Dict[str, Any]] = []
+    for threshold in sorted(set(divergence_thresholds)):
+        matrix = ConfusionMatrix()
+        for entry in scored:
+            matrix.add(entry["divergence_score"] > threshold, entry["outcome"])
+        sweep.append({
+            "threshold": threshold,
+            "current": threshold == DIVERGENCE_REVIEW_THRESHOLD,
+            **matrix.to_dict(),
+        })
+
+    return {
+        "overall": overall.to_dict(),
+        "by_reference_type": {key: matrix.to_dict() for key, matrix in sorted(by_reference.items())},
+        "by_agent": {key: matrix.to_dict() for key, matrix in sorted(by_agent.items())},
+        "by_rule": {key: matrix.to_dict() for key, matrix in by_rule.items()},
+        "divergence_thresholds": sweep,
+    }
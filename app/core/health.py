@@ -0,0 +1,185 @@
+"""
+Dependency health for the liveness / readiness split
+
+Liveness only says the process is serving requests. Readiness probes what validations
+depend on and fails when any of these checks fail:
+
+- database:     a write transaction commits (locked, read-only or full databases fail)
+- audit_chain:  every tenant's runtime audit chain verified intact within the max age;
+                the oldest stale chains are re-verified here (a few per probe), so probes
+                keep them fresh without re-walking every tenant at once
+- embeddings:   the embedding model is loaded (or disabled on purpose) and no validation
+                fell back to lexical similarity within the fallback window
+- queue:        background /v3 validations waiting in this process
+- disk:         free space on the database volume
+
+Thresholds are read from the environment on every call (HEALTH_* variables). The probe is
+unauthenticated, so readiness() can reuse a recent report and public_report() strips it to
+booleans and counts; failing details go to the server log instead.
+"""
+
+import logging
+import os
+import shutil
+import threading
+import time
+from dataclasses import dataclass, field
+from datetime import datetime, timedelta
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+from app.core.entropy import embedding_model_status
+from app.core.parallel_validation import validation_queue_depth
+from app.core.storage import (
+    database_path,
+    list_audit_chain_verifications,
+    probe_database_write,
+    verify_runtime_audit_chain,
+)
+
+logger = logging.getLogger(__name__)
+
+_REPORT_LOCK = threading.Lock()
+_LAST_REPORT: Optional[Dict[str, Any]] = None
+_LAST_REPORT_AT = 0.0  # time.monotonic() of _LAST_REPORT
+
+
+def _env_float(name: str, default: float) -> float:
+    return float(os.getenv(name, str(default)))
+
+
+@dataclass
+class CheckResult:
+    name: str
+    ok: bool
+    detail: Dict[str, Any] = field(default_factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"ok": self.ok, **self.detail}
+
+
+def check_database() -> CheckResult:
+    start = time.perf_counter()
+    try:
+        probe_database_write(timeout_seconds=_env_float("HEALTH_DB_TIMEOUT_SECONDS", 1.0))
+    except Exception as e:
+        return CheckResult("database", False, {"error": str(e)})
+    return CheckResult("database", True, {"write_latency_ms": round((time.perf_counter() - start) * 1000, 3)})
+
+
+def check_audit_chain() -> CheckResult:
+    max_age = timedelta(seconds=_env_float("HEALTH_AUDIT_MAX_AGE_SECONDS", 3600))
+    max_reverify = int(_env_float("HEALTH_AUDIT_MAX_REVERIFY", 5))
+    now = datetime.utcnow()
+    broken: List[str] = []
+    reverified = 0
+    stale = 0
+    oldest = None
+    entries = []
+    for entry in list_audit_chain_verifications():
+        verified_at = datetime.fromisoformat(entry["verified_at"]) if entry["verified_at"] else None
+        entries.append((verified_at, entry))
+    # Never-verified chains first, then oldest; the rest wait for a later probe
+    entries.sort(key=lambda item: (item[0] is not None, item[0] or now))
+    for verified_at, entry in entries:
+        intact = entry["intact"]
+        if verified_at is None or now - verified_at > max_age:
+            if reverified < max_reverify:
+                intact = verify_runtime_audit_chain(entry["tenant_id"])
+                verified_at = now
+                reverified += 1
+            else:
+                stale += 1
+        if intact is False:  # None: never verified and still waiting for its turn
+            broken.append(entry["tenant_id"])
+        if verified_at is not None:
+            oldest = verified_at if oldest is None else min(oldest, verified_at)
+    return CheckResult(
+        "audit_chain",
+        not broken,
+        {
+            "broken_tenants": broken,
+            "broken_count": len(broken),
+            "reverified": reverified,
+            "stale": stale,
+            "oldest_verification_age_seconds": round((now - oldest).total_seconds(), 3) if oldest else None,
+        },
+    )
+
+
+def check_embeddings() -> CheckResult:
+    status = embedding_model_status()
+    window = _env_float("HEALTH_EMBEDDING_FALLBACK_WINDOW_SECONDS", 300)
+    last_fallback = status["last_lexical_fallback"]
+    recent_fallback = last_fallback is not None and time.time() - last_fallback < window
+    if status["state"] == "disabled":
+        ok = True  # Lexical mode by configuration, not degradation
+    else:
+        ok = status["state"] == "loaded" and not recent_fallback
+    return CheckResult(
+        "embeddings",
+        ok,
+        {
+            "state": status["state"],
+            "error": status["error"],
+            "lexical_fallback_seconds_ago": round(time.time() - last_fallback, 3) if last_fallback else None,
+        },
+    )
+
+
+def check_queue() -> CheckResult:
+    depth = validation_queue_depth()
+    max_depth = int(_env_float("HEALTH_MAX_QUEUE_DEPTH", 100))
+    return CheckResult("queue", depth <= max_depth, {"depth": depth, "max_depth": max_depth})
+
+
+def check_disk() -> CheckResult:
+    path = database_path()
+    directory = Path(path).parent if path != ":memory:" else Path.cwd()
+    min_free_mb = _env_float("HEALTH_MIN_FREE_DISK_MB", 500)
+    try:
+        free_mb = shutil.disk_usage(directory).free / (1024 * 1024)
+    except OSError as e:
+        return CheckResult("disk", False, {"error": str(e)})
+    return CheckResult("disk", free_mb >= min_free_mb, {"free_mb": round(free_mb, 1), "min_free_mb": min_free_mb})
+
+
+READINESS_CHECKS = (check_database, check_audit_chain, check_embeddings, check_queue, check_disk)
+
+
+def _run(check) -> CheckResult:
+    try:
+        return check()
+    except Exception as e:  # A probe that cannot run counts as failed, never as a 500
+        return CheckResult(check.__name__[len("check_"):], False, {"error": str(e)})
+
+
+def readiness(max_age_seconds: float = 0.0) -> Dict[str, Any]:
+    """Run every check, or return the last report if it is younger than max_age_seconds."""
+    global _LAST_REPORT, _LAST_REPORT_AT
+    with _REPORT_LOCK:  # Concurrent probes wait for one run instead of each writing to the DB
+        if _LAST_REPORT is not None and time.monotonic() - _LAST_REPORT_AT < max_age_seconds:
+            return _LAST_REPORT
+        results = [_run(check) for check in READINESS_CHECKS]
+        report = {
+            "ready": all(result.ok for result in results),
+            "checks": {result.name: result.to_dict() for result in results},
+            "checked_at": datetime.utcnow().isoformat(),
+        }
+        failed = {result.name: result.detail for result in results if not result.ok}
+        if failed:
+            logger.warning("Readiness checks failed: %s", failed)
+        _LAST_REPORT, _LAST_REPORT_AT = report, time.monotonic()
+        return report
+
+
+def public_report(report: Dict[str, Any]) -> Dict[str, Any]:
+    """Readiness without tenant ids, paths or exception text: only booleans and counts."""
+    return {
+        "ready": report["ready"],
+        "checks": {
+            name: {key: value for key, value in check.items() if isinstance(value, (bool, int, float))}
+            for name, check in report["checks"].items()
+        },
+        "checked_at": report["checked_at"],
+    }
@@ -0,0 +1,188 @@
+"""
+Server-side account ledger for /v4/execution/pre-trade
+
+The ledger (app/core/storage.py) tracks, per tenant and account, the approved quantity of
+ALLOW/MODIFY decisions as pending exposure until execution reports fill or cancel it, filled
+positions at average cost and today's realized PnL. Pre-trade checks use the worse of the
+ledger and the caller's AccountSnapshot for each of:
+
+- daily_pnl:               the lower of the two
+- open_positions:          per symbol, the larger absolute quantity (ledger on ties)
+- pending_orders:          per symbol, the larger absolute quantity (ledger on ties)
+- current_gross_exposure:  the higher of the two
+
+Marks for sector, asset-class and concentration limits combine the snapshot's mark_prices
+with the ledger's average cost per symbol, taking the stricter value for each limit: the
+higher mark for sector / asset-class exposure, the lower for the concentration denominator
+(concentration_mark_prices). A symbol with only one of the two uses that one.
+
+Every field where the two disagree is written to the runtime audit log as LEDGER_DISCREPANCY.
+
+Callers hold account_lock() from reconcile_account() until the decision is stored, so two
+concurrent orders on one account cannot both be approved against the same pending exposure.
+"""
+
+import os
+import threading
+from contextlib import contextmanager
+from typing import Any, Dict, Iterator, List, Optional, Tuple
+
+from app.core.storage import get_account_ledger, insert_audit_event
+from app.models import AccountSnapshot
+
+DISCREPANCY_EVENT = "LEDGER_DISCREPANCY"
+
+_ACCOUNT_LOCKS: Dict[Tuple[str, str], threading.Lock] = {}
+_ACCOUNT_LOCKS_GUARD = threading.Lock()
+
+
+def _tolerance() -> float:
+    return float(os.getenv("LEDGER_DISCREPANCY_TOLERANCE", "1e-6"))
+
+
+def _worse_quantities(
+    field: str,
+    snapshot: Dict[str, float],
+    ledger: Dict[str, float],
+    discrepancies: List[Dict[str, Any]],
+) -> Dict[str, float]:
+    client = {symbol.upper(): float(quantity) for symbol, quantity in snapshot.items()}
+    merged: Dict[str, float] = {}
+    for symbol in sorted(set(client) | set(ledger)):
+        reported, tracked = client.get(symbol, 0.0), ledger.get(symbol, 0.0)
+        merged[symbol] = tracked if abs(tracked) >= abs(reported) else reported
+        if abs(reported - tracked) > _tolerance():
+            discrepancies.append(
+                {
+                    "field": f"{field}.{symbol}",
+                    "snapshot": reported,
+                    "ledger": tracked,
+                    "used": "ledger" if merged[symbol] == tracked else "snapshot",
+                }
+            )
+    return {symbol: quantity for symbol, quantity in merged.items() if quantity != 0}
+
+
+@contextmanager
+def account_lock(tenant_id: str, account_id: Optional[str]) -> Iterator[None]:
+    """
+    Serialize read-evaluate-insert per (tenant, account). Process-local: the service runs one
+    worker against one SQLite file. account_id None is the tenant's default ("") account.
+    """
+    with _ACCOUNT_LOCKS_GUARD:
+        lock = _ACCOUNT_LOCKS.setdefault((tenant_id, account_id or ""), threading.Lock())
+    with lock:
+        yield
+
+
+def _conservative_marks(
+    snapshot: Dict[str, float],
+    ledger: Dict[str, float],
+    discrepancies: List[Dict[str, Any]],
+) -> Tuple[Dict[str, float], Dict[str, float]]:
+    """(higher, lower) mark per symbol; a client mark cannot shrink exposure below ledger cost."""
+    client = {symbol.upper(): float(price) for symbol, price in snapshot.items()}
+    high: Dict[str, float] = {}
+    low: Dict[str, float] = {}
+    for symbol in sorted(set(client) | set(ledger)):
+        marks = [mark for mark in (client.get(symbol), ledger.get(symbol)) if mark is not None]
+        high[symbol], low[symbol] = max(marks), min(marks)
+        if len(marks) == 2 and abs(marks[0] - marks[1]) > _tolerance():
+            discrepancies.append(
+                {
+                    "field": f"mark_prices.{symbol}",
+                    "snapshot": marks[0],
+                    "ledger": marks[1],
+                    "used": "per_limit",
+                }
+            )
+    return high, low
+
+
+def reconcile_account(
+    tenant_id: str,
+    account_id: Optional[str],
+    snapshot: AccountSnapshot,
+) -> Tuple[AccountSnapshot, Dict[str, Any]]:
+    """
+    The account to evaluate an order with (worse of ledger and snapshot) and a record of the
+    ledger view and discrepancies for the decision row.
+    """
+    ledger = get_account_ledger(tenant_id, account_id)
+    discrepancies: List[Dict[str, Any]] = []
+
+    open_positions = _worse_quantities(
+        "open_positions",
+        snapshot.open_positions,
+        {symbol: position["quantity"] for symbol, position in ledger["positions"].items()},
+        discrepancies,
+    )
+    pending_orders = _worse_quantities("pending_orders", snapshot.pending_orders, ledger["pending_orders"], discrepancies)
+
+    high_marks, low_marks = _conservative_marks(
+        snapshot.mark_prices,
+        {symbol: position["avg_price"] for symbol, position in ledger["positions"].items() if position["avg_price"] > 0},
+        discrepancies,
+    )
+
+    daily_pnl = min(snapshot.daily_pnl, ledger["daily_pnl"])
+    gross_exposure = max(snapshot.current_gross_exposure, ledger["gross_exposure"])
+    for field, reported, tracked, used in (
+        ("daily_pnl", snapshot.daily_pnl, ledger["daily_pnl"], daily_pnl),
+        ("current_gross_exposure", snapshot.current_gross_exposure, ledger["gross_exposure"], gross_exposure),
+    ):
+        if abs(reported - tracked) > _tolerance():
+            discrepancies.append(
+                {
+                    "field": field,
+                    "snapshot": reported,
+                    "ledger": tracked,
+                    "used": "ledger" if used == tracked else "snapshot",
+                }
+            )
+
+    effective = snapshot.model_copy(
+        update={
+            "daily_pnl": daily_pnl,
+            "open_positions": open_positions,
+            "pending_orders": pending_orders,
+            "current_gross_exposure": gross_exposure,
+            "mark_prices": high_marks,
+            "concentration_mark_prices": low_marks,
+        }
+    )
+    record = {
+        "account_id": account_id,
+        "positions": ledger["positions"],
+        "pending_orders": ledger["pending_orders"],
+        "daily_pnl": ledger["daily_pnl"],
+        "gross_exposure": ledger["gross_exposure"],
+        "discrepancies": discrepancies,
+    }
+    return effective, record
+
+
+def record_ledger_discrepancies(
+    decision_id: str,
+    tenant_id: str,
+    agent_id: Optional[str],
+    account_id: Optional[str],
+    discrepancies: List[Dict[str, Any]],
+) -> Optional[int]:
+    """One LEDGER_DISCREPANCY audit event listing every disagreeing field; None when they agree."""
+    if not discrepancies:
+        return None
+    understated = [d["field"] for d in discrepancies if d["used"] == "ledger"]
+    summary = "; ".join(f"{d['field']} snapshot={d['snapshot']:g} ledger={d['ledger']:g}" for d in discrepancies)
+    return insert_audit_event(
+        request_id=decision_id,
+        tenant_id=tenant_id,
+        agent_id=agent_id,
+        event_type=DISCREPANCY_EVENT,
+        violations=[d["field"] for d in discrepancies],
+        regulatory_articles=[],
+        message=(
+            f"Account {account_id or 'default'} snapshot disagrees with the ledger on {len(discrepancies)} field(s), "
+            f"{len(understated)} understated: {summary}."
+        ),
+    )
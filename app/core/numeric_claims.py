@@ -0,0 +1,275 @@
+"""
+Numeric Claim Consistency
+Extracts the quantities, prices, percentages, measurements and tickers each sample
+asserts, aligns them across samples and flags disagreements beyond a per-kind tolerance.
+
+Semantic scorers treat "buy 100 AAPL" and "buy 1000 AAPL" as near-identical; this
+check turns that 10x disagreement into a hard violation.
+"""
+
+from __future__ import annotations
+
+import math
+import re
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Tuple
+
+from app.core.violations import ConstraintViolation
+
+
+VIOLATION_PREFIX = "Numeric claim disagreement"
+CHECK_ID = "numeric_claims"  # check id in the constraint definitions
+
+# Maximum relative spread ((max - min) / smallest non-zero magnitude) tolerated per claim kind
+CLAIM_TOLERANCES: Dict[str, float] = {
+    "quantity": 0.0,      # share/contract counts must match exactly
+    "price": 0.01,
+    "percentage": 0.05,
+    "measurement": 0.01,
+    "number": 0.01,
+    "ticker": 0.0,
+}
+
+_SCALE_WORDS = {
+    "k": 1e3,
+    "thousand": 1e3,
+    "m": 1e6,  # only as an uppercase suffix ("5M"); "5 m" is metres
+    "mm": 1e6,
+    "mn": 1e6,
+    "million": 1e6,
+    "b": 1e9,
+    "bn": 1e9,
+    "billion": 1e9,
+}
+_PERCENT_WORDS = {"%", "percent", "pct"}
+_QUANTITY_WORDS = {"shares", "share", "contracts", "contract", "lots", "lot", "units", "unit", "options"}
+_PRICE_WORDS = {"usd", "eur", "gbp", "dollars", "dollar"}
+_PRICE_CUES = {"at", "@", "price", "limit", "stop", "target"}
+_MEASUREMENT_UNITS = {
+    "j", "kj", "mj", "w", "kw", "mw", "gw", "wh", "kwh", "mwh",
+    "kg", "g", "t", "m", "km", "s", "ms", "m/s", "km/h", "c", "f", "k",
+    "pa", "kpa", "mpa", "bar", "v", "a", "hz",
+}
+_TICKER_STOPWORDS = {
+    "USD", "EUR", "GBP", "JPY", "CHF", "OK", "AM", "PM", "EOD", "ASAP", "VAR", "CEO", "CFO",
+    "AI", "API", "ETF", "IPO", "EU", "US", "UK", "NAV", "PNL", "KW", "MW", "GW", "KWH", "MWH",
+    "BUY", "SELL", "HOLD", "NOT", "NO", "YES", "NOW", "THE", "AND", "FOR", "AT",
+}
+
+_CLAIM_PATTERN = re.compile(
+    r"(?P<currency>[$€£])?"
+    r"(?<![\w.])(?P<number>-?\d{1,3}(?:,\d{3})+(?:\.\d+)?|-?\d+(?:\.\d+)?)"
+    r"(?P<suffix>%|(?:[kK]|MM|M|[bB]n|BN|B)\b)?"
+    r"(?:\s*(?P<next>[A-Za-z%][A-Za-z/]*|%))?"
+)
+_TICKER_PATTERN = re.compile(r"(?<![\w$])\$?([A-Z]{2,5})\b|\$([A-Z]{1,5})\b")
+_PREVIOUS_WORD = re.compile(r"([\w@]+)\s*$")
+
+
+@dataclass(frozen=True)
+class NumericClaim:
+    kind: str               # quantity | price | percentage | measurement | number
+    value: float
+    unit: Optional[str]     # measurement unit, when present
+    subject: Optional[str]  # nearest ticker, when present
+    cue: Optional[str] = None  # lowercased word preceding the number ("buy", "at", ...)
+
+
+@dataclass(frozen=True)
+class NumericDisagreement:
+    kind: str
+    label: str
+    values: Tuple[str, ...]  # distinct asserted values in first-seen order
+    spread: float            # relative spread across samples
+    tolerance: float
+
+    @property
+    def message(self) -> str:
+        return (
+            f"{VIOLATION_PREFIX}: {self.label} ({' vs '.join(self.values)}; "
+            f"spread {self.spread:.1%} exceeds {self.tolerance:.1%} tolerance)"
+        )
+
+    def to_violation(self) -> ConstraintViolation:
+        return ConstraintViolation(
+            rule_id=CHECK_ID,
+            message=self.message,
+            variable=self.label,
+            actual=self.spread,
+            limit=self.tolerance,
+        )
+
+
+def extract_tickers(text: str) -> List[str]:
+    tickers: List[str] = []
+    for match in _TICKER_PATTERN.finditer(text):
+        symbol = match.group(1) or match.group(2)
+        if match.group(2) is None and symbol in _TICKER_STOPWORDS:
+            continue
+        if symbol not in tickers:
+            tickers.append(symbol)
+    return tickers
+
+
+def _classify(
+    currency: Optional[str],
+    suffix: Optional[str],
+    next_word: Optional[str],
+    previous_word: Optional[str],
+) -> Tuple[str, Optional[str], float]:
+    """Return (kind, unit, multiplier) for a number given its surrounding tokens."""
+    suffix_lower = (suffix or "").lower()
+    next_lower = (next_word or "").lower()
+    multiplier = 1.0
+    consumed = False
+
+    if suffix_lower == "%":
+        return "percentage", None, 1.0
+    if suffix_lower in _SCALE_WORDS:
+        multiplier = _SCALE_WORDS[suffix_lower]
+    elif next_lower in {"thousand", "million", "billion"}:
+        multiplier = _SCALE_WORDS[next_lower]
+        consumed = True
+
+    if not consumed and next_lower in _PERCENT_WORDS:
+        return "percentage", None, 1.0
+    if not consumed and next_lower == "bps":
+        return "percentage", None, 0.01
+    if currency or (not consumed and next_lower in _PRICE_WORDS) or (previous_word or "").lower() in _PRICE_CUES:
+        return "price", None, multiplier
+    if not consumed and next_lower in _QUANTITY_WORDS:
+        return "quantity", None, multiplier
+    if not consumed and next_word and next_word.isupper() and 2 <= len(next_word) <= 5 and next_word not in _TICKER_STOPWORDS:
+        return "quantity", None, multiplier
+    if not consumed and not suffix and next_lower in _MEASUREMENT_UNITS:
+        return "measurement", next_lower, 1.0
+    return "number", None, multiplier
+
+
+def extract_claims(text: str) -> List[NumericClaim]:
+    """Extract numeric claims in order of appearance."""
+    tickers = extract_tickers(text)
+    subject = tickers[0] if tickers else None
+    claims: List[NumericClaim] = []
+
+    for match in _CLAIM_PATTERN.finditer(text):
+        previous = _PREVIOUS_WORD.search(text[: match.start()])
+        kind, unit, multiplier = _classify(
+            match.group("currency"),
+            match.group("suffix"),
+            match.group("next"),
+            previous.group(1) if previous else None,
+        )
+        value = float(match.group("number").replace(",", "")) * multiplier
+        cue = previous.group(1).lower() if previous else None
+        claims.append(NumericClaim(kind=kind, value=value, unit=unit, subject=subject, cue=cue))
+
+    return claims
+
+
+def _relative_spread(values: List[float]) -> float:
+    magnitudes = [abs(v) for v in values if v != 0]
+    scale = min(magnitudes) if magnitudes else 1.0
+    return (max(values) - min(values)) / scale
+
+
+def _format_value(value: float) -> str:
+    return f"{value:,.0f}" if value == int(value) else f"{value:,.4g}"
+
+
+def _match_cost(claim: NumericClaim, anchor: NumericClaim) -> Tuple[int, float]:
+    """Same preceding word first, then closest value (log ratio)."""
+    distance = abs(math.log((abs(claim.value) + 1e-9) / (abs(anchor.value) + 1e-9)))
+    return (0 if claim.cue == anchor.cue else 1), distance
+
+
+def _align_claims(samples: List[str]) -> List[List[NumericClaim]]:
+    """
+    Group claims that refer to the same thing across samples.
+
+    Each group is anchored on the first claim that opened it. A sample's claims of a given
+    (kind, unit) are matched to groups greedily by cue, then value, with at most one claim
+    per sample per group, so one extra number in a sample opens its own group instead of
+    shifting every later pairing.
+    """
+    groups: Dict[Tuple[str, Optional[str]], List[List[NumericClaim]]] = {}
+    for sample in samples:
+        by_key: Dict[Tuple[str, Optional[str]], List[NumericClaim]] = {}
+        for claim in extract_claims(sample):
+            by_key.setdefault((claim.kind, claim.unit), []).append(claim)
+        for key, claims in by_key.items():
+            existing = groups.setdefault(key, [])
+            candidates = sorted(
+                (_match_cost(claim, group[0]), claim_index, group_index)
+                for claim_index, claim in enumerate(claims)
+                for group_index, group in enumerate(existing)
+            )
+            used_claims, used_groups = set(), set()
+            for _, claim_index, group_index in candidates:
+                if claim_index in used_claims or group_index in used_groups:
+                    continue
+                existing[group_index].append(claims[claim_index])
+                used_claims.add(claim_index)
+                used_groups.add(group_index)
+            existing.extend([claim] for index, claim in enumerate(claims) if index not in used_claims)
+    return [group for key_groups in groups.values() for group in key_groups]
+
+
+def find_numeric_disagreements(samples: List[str]) -> List[NumericDisagreement]:
+    """
+    Align claims across samples (see _align_claims) and report every aligned claim
+    whose relative spread exceeds CLAIM_TOLERANCES for its kind.
+
+    A claim is only compared across the samples that assert it; a sample that omits
+    a number is not treated as disagreeing with it.
+    """
+    primary_tickers: List[str] = []
+    for sample in samples:
+        tickers = extract_tickers(sample)
+        if tickers:
+            primary_tickers.append(tickers[0])
+
+    disagreements: List[NumericDisagreement] = []
+
+    # The first ticker a sample names is the instrument it is acting on
+    distinct_tickers = tuple(dict.fromkeys(primary_tickers))
+    if len(distinct_tickers) > 1:
+        disagreements.append(
+            NumericDisagreement(
+                kind="ticker",
+                label="ticker",
+                values=distinct_tickers,
+                spread=1.0,
+                tolerance=CLAIM_TOLERANCES["ticker"],
+            )
+        )
+
+    for claims in _align_claims(samples):
+        if len(claims) < 2:
+            continue
+        kind, unit = claims[0].kind, claims[0].unit
+        values = [claim.value for claim in claims]
+        spread = _relative_spread(values)
+        tolerance = CLAIM_TOLERANCES[kind]
+        if spread <= tolerance:
+            continue
+        subject = next((claim.subject for claim in claims if claim.subject), None)
+        label = " ".join(part for part in (kind, subject, unit and f"[{unit}]") if part)
+        disagreements.append(
+            NumericDisagreement(
+                kind=kind,
+                label=label,
+                values=tuple(dict.fromkeys(_format_value(v) for v in values)),
+                spread=spread,
+                tolerance=tolerance,
+            )
+        )
+
+    return disagreements
+
+
+def check_numeric_consistency(samples: Optional[List[str]]) -> List[ConstraintViolation]:
+    """check_logic violation records for cross-sample numeric disagreements."""
+    if not samples or len(samples) < 2:
+        return []
+    return [d.to_violation() for d in find_numeric_disagreements(samples)]
@@ -100,6 +100,7 @@ class APIKeyScope(str, Enum):
     VALIDATE_ONLY = "validate-only"  # Can only call /v2/validate
     ADMIN = "admin"  # Full access
     READ_METRICS = "read-metrics"  # Read-only audit trail + health
+    EXECUTION_REPORTS = "execution-reports"  # Broker / OMS drop copy: can only post execution reports
 
 
 def _get_encryption_key() -> Optional[str]:
@@ -318,10 +319,12 @@ def init_onboarding_db() -> None:
             ("admin", "manage_config", "all"),
             ("admin", "manage_users", "all"),
             ("admin", "view_audit", "all"),
+            ("admin", "report_executions", "all"),
             ("risk-officer", "validate", "all"),
             ("risk-officer", "read_results", "all"),
             ("risk-officer", "manage_config", "own"),
             ("risk-officer", "view_audit", "all"),
+            ("risk-officer", "report_executions", "all"),
             ("analyst", "validate", "all"),
             ("analyst", "read_results", "all"),
             ("analyst", "view_audit", "all"),
@@ -604,7 +607,7 @@ def generate_api_key(
     Args:
         tenant_id: Tenant that owns this key
         user_id: User that created/owns this key
-        scope: Permission scope (validate-only, admin, read-metrics)
+        scope: Permission scope (validate-only, admin, read-metrics, execution-reports)
         expires_in_days: TTL in days (default 90)
     
     Returns:
@@ -906,6 +909,33 @@ def _insert_audit_log(
     )
 
 
+def record_config_change(
+    tenant_id: str,
+    event_type: str,
+    target: str,
+    details: Dict[str, Any],
+    actor_id: Optional[str] = None
+) -> None:
+    """
+    Record a tenant configuration change in the identity audit chain.
+    
+    Used by configuration endpoints whose state lives outside the onboarding tables
+    (e.g., validation settings) so every change still has an auditable actor.
+    """
+    with _LOCK:
+        conn = _connect()
+        _insert_audit_log(
+            conn,
+            tenant_id=tenant_id,
+            actor_id=actor_id,
+            event_type=event_type,
+            target=target,
+            details=details
+        )
+        conn.commit()
+        conn.close()
+
+
 def list_audit_log(
     tenant_id: str,
     event_type: Optional[str] = None,
@@ -1,47 +1,67 @@
 from __future__ import annotations
 
+import threading
 import time
 from typing import Dict, Any, List
 
-from app.core.constraints import check_logic
-from app.core.entropy import hallucination_divergence
+from app.core.constraints import check_logic, constraint_inputs
+from app.core.reasoning_audit import check_reasoning
+from app.core.shadow import shadow_validation
+from app.core.temporal_constraints import agent_lock, check_temporal_constraints, record_temporal_values
+from app.core.structured_divergence import field_divergence_details, score_sample_divergence
+from app.core.divergence_backends import fallback_backend, resolve_backend
+from app.core.calibration import active_calibration
 from app.core.confidence import calculate_confidence
-from app.core.severity import calculate_violation_severity, calculate_overall_severity_score
-from app.core.drift import request_history
+from app.core.severity import calculate_violation_severities, calculate_overall_severity_score
+from app.core.drift import drift_registry, record_drift_audit_events
+from app.core.entropy import sample_embeddings
 from app.core.compliance import map_violations_to_articles
-from app.core.storage import insert_validation_result, insert_audit_event, insert_state_snapshot
+from app.core.violations import ConstraintViolation, violation_messages, violation_records
+from app.core.constraint_rules import ConstraintDefinitions, resolve_constraint_definitions
+from app.core.decision_engine import action_label_and_reason, decide, recommendation_for
+from app.core.storage import (
+    insert_validation_result,
+    insert_audit_event,
+    insert_state_snapshot,
+    get_active_constraint_bundle,
+    get_tenant_validation_settings,
+)
 from app.models import AgentIntentRequest
 
 
 LATENCY_GUARD_SECONDS = 0.2
 
+# Submitted /v3 validations not yet finished in this process (readiness probe queue depth)
+_QUEUE_LOCK = threading.Lock()
+_QUEUED = 0
 
-def _compute_action_label(status_code: int, action: str, severity_overall: float, confidence_score: float) -> tuple[str, str]:
-    """Compute CRO-friendly action_label and action_reason for single-line decisions.
-    
-    Returns (action_label: str, action_reason: str)
-    """
-    if status_code == 424:
-        return ("BLOCK", "Constraint violation prevents execution (424 Sentinel)")
-    elif action == "BLOCK":
-        return ("BLOCK", "Critical violation detected; manual review required")
-    elif action == "REVIEW":
-        risk = "high" if confidence_score < 0.5 else "moderate"
-        return ("REVIEW", f"Requires manual review due to {risk} confidence ({confidence_score:.2f})")
-    elif action == "CAUTION":
-        return ("CAUTION", f"Latency guard triggered ({LATENCY_GUARD_SECONDS*1000:.0f}ms); process continues with flag")
-    elif action == "ALLOW":
-        return ("ALLOW", f"Safe to execute (confidence={confidence_score:.2f})")
-    else:
-        # Unknown action - default to BLOCK (safe default)
-        import logging
-        logger = logging.getLogger(__name__)
-        logger.error(f"Unknown action '{action}' detected, defaulting to BLOCK")
-        return ("BLOCK", f"Unknown validation action '{action}'; defaulting to safe BLOCK")
-
-
-def _build_severity(violations: List[str]) -> Dict[str, Any]:
-    details = [calculate_violation_severity(v, 0.0) for v in violations]
+
+def mark_validation_queued() -> None:
+    global _QUEUED
+    with _QUEUE_LOCK:
+        _QUEUED += 1
+
+
+def run_queued_validation(request_id: str, request: AgentIntentRequest) -> Dict[str, Any]:
+    """Background-task entry point: run_parallel_validation for a mark_validation_queued() submission."""
+    global _QUEUED
+    try:
+        return run_parallel_validation(request_id, request)
+    finally:
+        with _QUEUE_LOCK:
+            _QUEUED -= 1
+
+
+def validation_queue_depth() -> int:
+    with _QUEUE_LOCK:
+        return _QUEUED
+
+
+def _build_severity(
+    records: List[ConstraintViolation],
+    definitions: ConstraintDefinitions,
+) -> Dict[str, Any]:
+    details = calculate_violation_severities(records, definitions)
     overall = calculate_overall_severity_score(details)
     return {
         "details": [
@@ -65,43 +85,64 @@ def run_parallel_validation(request_id: str, request: AgentIntentRequest) -> Dic
     2. Rewind snapshot capture is ATOMIC with validation
     3. Compliance Forge entry written synchronously
     4. Action label + reason computed server-side for CRO clarity
+
+    One request per (tenant, agent) at a time: temporal rules read the metric history that
+    the previous request recorded.
     """
+    with agent_lock(request.tenant_id, request.agent_id):
+        return _validate(request_id, request)
+
+
+def _validate(request_id: str, request: AgentIntentRequest) -> Dict[str, Any]:
     start = time.perf_counter()
 
-    entropy_score = hallucination_divergence(request.samples)
-    violations = check_logic(request)
+    try:
+        backend = resolve_backend(
+            name=request.divergence_backend,
+            ensemble=request.divergence_ensemble,
+            tenant_settings=get_tenant_validation_settings(request.tenant_id),
+        )
+    except ValueError:
+        # Tenant setting changed after submission; never drop the validation
+        backend = fallback_backend()
+    entropy = score_sample_divergence(request, backend)
+    field_divergences = field_divergence_details(entropy)
+    entropy_score = entropy.score
+    # Limits live at validation time: the tenant's active bundle, else the shipped defaults
+    bundle = get_active_constraint_bundle(request.tenant_id)
+    constraint_bundle_version = bundle["version"] if bundle else None
+    definitions = resolve_constraint_definitions(bundle)
+    records = check_logic(request, definitions)
+    inputs = constraint_inputs(request)
+    records.extend(check_temporal_constraints(request.tenant_id, request.agent_id, inputs, definitions))
+    reasoning_violations, reasoning = check_reasoning(
+        request.chain_of_thought, request.intent, request.desired_state
+    )
+    records.extend(reasoning_violations)
+    violations = violation_messages(records)
+    reasoning_evidence = reasoning.to_dict() if request.chain_of_thought else None
 
-    confidence = calculate_confidence(entropy_score, violations)
-    severity = _build_severity(violations)
+    confidence = calculate_confidence(entropy_score, violations, active_calibration(request.tenant_id))
+    severity = _build_severity(records, definitions)
 
-    request_history.record(entropy_score, len(violations))
-    drift = request_history.detect_drift(entropy_score)
+    drift = drift_registry.record(
+        request.tenant_id, request.agent_id, entropy_score, len(violations), sample_embeddings(request.samples)
+    )
+    record_drift_audit_events(request_id, request.tenant_id, request.agent_id, drift)
 
     elapsed = time.perf_counter() - start
     latency_ms = elapsed * 1000.0
 
-    context_reset = False
-    if elapsed > LATENCY_GUARD_SECONDS:
-        action = "CAUTION"
-        status_code = 202
-        recommendation = "Latency guard triggered; caution flagged for downstream review."
-    elif violations:
-        action = "BLOCK"
-        status_code = 424
-        context_reset = True
-        recommendation = (
-            severity["details"][0]["impact_description"]
-            if severity["details"]
-            else "Constraint violation detected"
-        )
-    elif entropy_score > 0.4:
-        action = "REVIEW"
-        status_code = 400
-        recommendation = f"Manual review recommended. Confidence: {confidence.risk_level}"
-    else:
-        action = "ALLOW"
-        status_code = 200
-        recommendation = "Safe to execute"
+    latency_exceeded = elapsed > LATENCY_GUARD_SECONDS
+    decision = decide(entropy_score, records, latency_exceeded=latency_exceeded)
+    action = decision.action
+    status_code = decision.status_code
+    context_reset = decision.context_reset
+    recommendation = recommendation_for(
+        decision,
+        confidence.risk_level,
+        severity["details"][0]["impact_description"] if severity["details"] else None,
+    )
 
     # ====== DETERMINISTIC ENFORCEMENT: 424 = IMMEDIATE SYNC CONTROL PATH ======
     # On 424: capture snapshot + log audit event BEFORE returning
@@ -119,17 +160,29 @@ def run_parallel_validation(request_id: str, request: AgentIntentRequest) -> Dic
             )
         
         # 2. Compliance Forge entry (regulatory articles + chain-of-thought)
-        articles = map_violations_to_articles(violations)
+        articles = map_violations_to_articles(records, definitions)
+        reasoning_note = (
+            f" First divergent reasoning step: {reasoning.first_divergent_step}."
+            if reasoning.first_divergent_step is not None
+            else ""
+        )
+        bundle_note = (
+            f" Constraint bundle: v{constraint_bundle_version}."
+            if constraint_bundle_version is not None
+            else f" Constraint bundle: defaults {definitions.version}."
+        )
         insert_audit_event(
             request_id=request_id,
             tenant_id=request.tenant_id,
             agent_id=request.agent_id,
             event_type="EXECUTION_BLOCKED_424",  # Explicit 424 marker
             violations=violations,
+            violation_records=violation_records(records),
             regulatory_articles=articles,
             message=(
                 f"Execution BLOCKED (424 Sentinel). Constraint violations: {'; '.join(violations)}. "
-                f"Context reset flag: {context_reset}. Regulatory articles: {', '.join(articles)}"
+                f"Context reset flag: {context_reset}. Regulatory articles: {', '.join(articles)}."
+                f"{reasoning_note}{bundle_note}"
             ),
         )
     # On ALLOW: capture valid snapshot for rewind capability
@@ -144,23 +197,42 @@ def run_parallel_validation(request_id: str, request: AgentIntentRequest) -> Dic
             valid=True,
         )
 
-    # Compute action_label and action_reason for CRO UI
-    action_label, action_reason = _compute_action_label(
-        status_code, action, severity["overall"], confidence.score
+    # Candidate bundle in shadow mode: judged on the same evidence, never enforced.
+    # Runs before this request's values join the agent's metric history.
+    shadow_validation(
+        request_id,
+        request,
+        entropy_score,
+        reasoning_violations,
+        latency_exceeded,
+        live_action=action,
+        live_violations=violations,
     )
 
+    # Blocked values never become the baseline for the agent's next request
+    if action != "BLOCK":
+        record_temporal_values(request.tenant_id, request.agent_id, request_id, inputs, definitions)
+
+    # Compute action_label and action_reason for CRO UI
+    action_label, action_reason = action_label_and_reason(decision, confidence.score, violations)
+
     insert_validation_result(
         request_id=request_id,
         tenant_id=request.tenant_id,
+        agent_id=request.agent_id,
         status_code=status_code,
         action=action,
         divergence_score=entropy_score,
         violations=violations,
-        confidence={
-            "score": confidence.score,
-            "risk_level": confidence.risk_level,
-            "explanation": confidence.explanation,
-        },
+        violation_records=violation_records(records),
+        semantic_clusters=entropy.clusters,
+        divergence_backend=entropy.backend,
+        divergence_backend_version=entropy.backend_version,
+        field_divergences=field_divergences or None,
+        reasoning_audit=reasoning_evidence,
+        decision_table_version=decision.table_version,
+        constraint_bundle_version=constraint_bundle_version,
+        confidence=confidence.to_dict(),
         severity=severity,
         drift={
             "detected": drift.detected,
@@ -168,6 +240,8 @@ def run_parallel_validation(request_id: str, request: AgentIntentRequest) -> Dic
             "historical_mean": drift.historical_mean,
             "current_value": drift.current_value,
             "explanation": drift.explanation,
+            "fired_detectors": drift.fired_detectors,
+            "detectors": drift.detectors,
         },
         recommendation=recommendation,
         context_reset=context_reset,
@@ -180,13 +254,17 @@ def run_parallel_validation(request_id: str, request: AgentIntentRequest) -> Dic
         "action": action,
         "action_label": action_label,
         "action_reason": action_reason,
+        "decision_table_version": decision.table_version,
+        "constraint_bundle_version": constraint_bundle_version,
         "divergence_score": entropy_score,
         "violations": violations,
-        "confidence": {
-            "score": confidence.score,
-            "risk_level": confidence.risk_level,
-            "explanation": confidence.explanation,
-        },
+        "violation_records": violation_records(records),
+        "semantic_clusters": entropy.clusters,
+        "divergence_backend": entropy.backend,
+        "divergence_backend_version": entropy.backend_version,
+        "field_divergences": field_divergences,
+        "reasoning_audit": reasoning_evidence,
+        "confidence": confidence.to_dict(),
         "severity": severity,
         "drift": {
             "detected": drift.detected,
@@ -194,6 +272,8 @@ def run_parallel_validation(request_id: str, request: AgentIntentRequest) -> Dic
             "historical_mean": drift.historical_mean,
             "current_value": drift.current_value,
             "explanation": drift.explanation,
+            "fired_detectors": drift.fired_detectors,
+            "detectors": drift.detectors,
         },
         "recommendation": recommendation,
         "context_reset": context_reset,
@@ -1,30 +1,29 @@
 from typing import Optional, Tuple
 
+from app.core.constraint_rules import ConstraintDefinitions, get_constraint_definitions
+from app.core.violations import ConstraintViolation
+
 
 class PhysicalValidator:
     """
     Hard-constraint validator for physics/finance variables.
 
+    Rules (aliases, limits, messages) come from the declarative constraint
+    definitions in app/core/constraint_rules.json.
+
     Returns (ok, reason). ok=False means the value violates a hard rule.
     """
 
-    def validate(self, variable: str, value: float) -> Tuple[bool, Optional[str]]:
-        var = variable.strip().lower()
-
-        if var in {"temperature", "temp", "kelvin"}:
-            if value < 0:
-                return False, "Model parameter below minimum bound (absolute zero constraint violated)"
+    def __init__(self, definitions: Optional[ConstraintDefinitions] = None) -> None:
+        self.definitions = definitions or get_constraint_definitions()
 
-        if var in {"pressure", "pascal", "pa"}:
-            if value < 0:
-                return False, "Model parameter invalid: negative pressure not allowed"
-
-        if var in {"leverage_ratio", "leverage", "debt_to_equity"}:
-            if value > 10:
-                return False, "Leverage limit breached: hard limit is 10x maximum"
-
-        if var in {"var", "value_at_risk", "proposed_loss"}:
-            if value < -10_000:
-                return False, "VaR loss limit breached: cannot exceed -$10k maximum loss"
-
-        return True, None
+    def validate(self, variable: str, value: float) -> Tuple[bool, Optional[str]]:
+        violation = self.check(variable, value)
+        return violation is None, violation.message if violation else None
+
+    def check(self, variable: str, value: float) -> Optional[ConstraintViolation]:
+        """Typed violation record for check_logic, or None when the value passes."""
+        rule = self.definitions.rule_for(variable)
+        if rule is None:
+            return None
+        return rule.violation(variable.strip().lower(), value)
@@ -0,0 +1,531 @@
+"""
+Constraint & Policy Analyzer
+Static checks over a constraint bundle and/or a TradePolicyConfig before they go live.
+
+Load-time validation (constraint_rules) rejects malformed definitions; this module looks
+for definitions that are well-formed but wrong:
+
+| code                       | severity | example                                                  |
+|----------------------------|----------|----------------------------------------------------------|
+| contradiction              | error    | `x <= 5` and `x >= 10`; max_position_size < min_lot_size |
+| unreachable_rule           | warning  | rule implied by a stricter bound; restricted " AAPL"     |
+| overlapping_aliases        | warning  | `var_99` vs `var99`; temporal rules splitting history    |
+| missing_regulatory_mapping | warning  | rule without `articles` (falls back to default articles) |
+| unit_mismatch              | warning  | `leverage_ratio + proposed_loss`; "{limit}%" on unit "x" |
+
+Bounds are read from single-variable rules and from relation links that compare a bare
+variable with constants/parameters (e.g. `0.2 <= efficiency <= 0.95`); multi-variable
+relations are only checked for units.
+"""
+
+from __future__ import annotations
+
+import ast
+import math
+import re
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, List, Optional, Tuple
+
+from app.core.constraint_rules import ConstraintDefinitions, RelationalRule
+from app.models import TradePolicyConfig
+
+
+ERROR = "error"
+WARNING = "warning"
+
+# Order-side variables whose unit is fixed by the pre-trade models
+_POLICY_UNITS = {"leverage_ratio": "x"}
+_FLIPPED = {"<": ">", "<=": ">=", ">": "<", ">=": "<=", "==": "==", "!=": "!="}
+_COMPARE_SYMBOLS = {ast.Lt: "<", ast.LtE: "<=", ast.Gt: ">", ast.GtE: ">=", ast.Eq: "==", ast.NotEq: "!="}
+_PLACEHOLDER_SUFFIX = re.compile(r"\{(?:value|limit)(?::[^}]*)?\}([A-Za-z%]+)")
+_SHARE_CLASS_SEPARATORS = re.compile(r"[./-]")
+
+
+@dataclass(frozen=True)
+class AnalysisFinding:
+    severity: str       # error | warning
+    code: str
+    target: str         # rule/relation/temporal/check id, or policy field
+    message: str
+
+
+@dataclass(frozen=True)
+class AnalysisReport:
+    findings: Tuple[AnalysisFinding, ...]
+
+    @property
+    def errors(self) -> List[AnalysisFinding]:
+        return [f for f in self.findings if f.severity == ERROR]
+
+    @property
+    def warnings(self) -> List[AnalysisFinding]:
+        return [f for f in self.findings if f.severity == WARNING]
+
+    @property
+    def ok(self) -> bool:
+        return not self.errors
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "ok": self.ok,
+            "errors": len(self.errors),
+            "warnings": len(self.warnings),
+            "findings": [asdict(f) for f in self.findings],
+        }
+
+
+# ---------------------------------------------------------------------------
+# Bounds
+# ---------------------------------------------------------------------------
+
+@dataclass(frozen=True)
+class _Bound:
+    source: str         # rule / relation id
+    operator: str       # the condition `variable <operator> value` must hold
+    value: float
+
+
+@dataclass(frozen=True)
+class _Interval:
+    lo: float = -math.inf
+    lo_inclusive: bool = False
+    hi: float = math.inf
+    hi_inclusive: bool = False
+
+    @property
+    def empty(self) -> bool:
+        return self.lo > self.hi or (self.lo == self.hi and not (self.lo_inclusive and self.hi_inclusive))
+
+    def intersect(self, other: "_Interval") -> "_Interval":
+        if (other.lo, not other.lo_inclusive) > (self.lo, not self.lo_inclusive):
+            lo, lo_inclusive = other.lo, other.lo_inclusive
+        else:
+            lo, lo_inclusive = self.lo, self.lo_inclusive
+        if (other.hi, other.hi_inclusive) < (self.hi, self.hi_inclusive):
+            hi, hi_inclusive = other.hi, other.hi_inclusive
+        else:
+            hi, hi_inclusive = self.hi, self.hi_inclusive
+        return _Interval(lo, lo_inclusive, hi, hi_inclusive)
+
+    def within(self, other: "_Interval") -> bool:
+        lo_ok = self.lo > other.lo or (self.lo == other.lo and (other.lo_inclusive or not self.lo_inclusive))
+        hi_ok = self.hi < other.hi or (self.hi == other.hi and (other.hi_inclusive or not self.hi_inclusive))
+        return lo_ok and hi_ok
+
+    def contains(self, value: float) -> bool:
+        return not _Interval(value, True, value, True).intersect(self).empty
+
+
+def _satisfying(bound: _Bound) -> Optional[_Interval]:
+    """Values satisfying the bound, or None for `!=` (not an interval)."""
+    op, value = bound.operator, bound.value
+    if op == "<":
+        return _Interval(hi=value)
+    if op == "<=":
+        return _Interval(hi=value, hi_inclusive=True)
+    if op == ">":
+        return _Interval(lo=value)
+    if op == ">=":
+        return _Interval(lo=value, lo_inclusive=True)
+    if op == "==":
+        return _Interval(value, True, value, True)
+    return None
+
+
+def _constant(node: ast.AST, parameters: Dict[str, float]) -> Optional[float]:
+    """Numeric value of a constants-and-parameters subexpression, else None."""
+    if isinstance(node, ast.Constant):
+        return float(node.value)
+    if isinstance(node, ast.Name):
+        return parameters.get(node.id)
+    if isinstance(node, ast.UnaryOp):
+        operand = _constant(node.operand, parameters)
+        if operand is None:
+            return None
+        return -operand if isinstance(node.op, ast.USub) else operand
+    if isinstance(node, ast.BinOp):
+        left, right = _constant(node.left, parameters), _constant(node.right, parameters)
+        if left is None or right is None:
+            return None
+        if isinstance(node.op, ast.Add):
+            return left + right
+        if isinstance(node.op, ast.Sub):
+            return left - right
+        if isinstance(node.op, ast.Mult):
+            return left * right
+        return left / right if right != 0 else None
+    return None
+
+
+def _relation_bounds(relation: RelationalRule) -> List[Tuple[str, _Bound]]:
+    compare = ast.parse(relation.expression.source, mode="eval").body
+    operands = [compare.left, *compare.comparators]
+    bounds: List[Tuple[str, _Bound]] = []
+    for op, left, right in zip(compare.ops, operands, operands[1:]):
+        symbol = _COMPARE_SYMBOLS[type(op)]
+        for variable_node, other, operator_symbol in ((left, right, symbol), (right, left, _FLIPPED[symbol])):
+            if isinstance(variable_node, ast.Name) and variable_node.id in relation.variables:
+                value = _constant(other, relation.parameters)
+                if value is not None:
+                    bounds.append((variable_node.id, _Bound(relation.rule_id, operator_symbol, value)))
+    return bounds
+
+
+def _bounds_by_variable(definitions: ConstraintDefinitions) -> Dict[str, List[_Bound]]:
+    bounds: Dict[str, List[_Bound]] = {}
+    for rule in definitions.rules:
+        for variable in rule.variables:
+            bounds.setdefault(variable, []).append(_Bound(rule.rule_id, rule.operator, rule.limit))
+    for relation in definitions.relations:
+        for variable, bound in _relation_bounds(relation):
+            bounds.setdefault(variable, []).append(bound)
+    return bounds
+
+
+def _describe(bound: _Bound, variable: str) -> str:
+    return f"'{bound.source}' ({variable} {bound.operator} {bound.value:g})"
+
+
+def _implied_by(bound: _Bound, bounds: List[_Bound]) -> Optional[_Bound]:
+    """Another source's bound that already fails every value this one fails, if any."""
+    own = _satisfying(bound)
+    for other in bounds:
+        if other.source == bound.source:
+            continue
+        other_satisfying = _satisfying(other)
+        if other_satisfying is None or other_satisfying.empty:
+            continue
+        implied = other_satisfying.within(own) if own is not None else not other_satisfying.contains(bound.value)
+        # Two identical bounds imply each other; report only the later source
+        if implied and (other_satisfying != own or other.source < bound.source):
+            return other
+    return None
+
+
+def _check_bounds(definitions: ConstraintDefinitions) -> List[AnalysisFinding]:
+    findings: List[AnalysisFinding] = []
+    by_variable = _bounds_by_variable(definitions)
+    seen_pairs = set()
+    feasible: Dict[str, _Interval] = {}
+    for variable, bounds in sorted(by_variable.items()):
+        interval = _Interval()
+        for bound in bounds:
+            satisfying = _satisfying(bound)
+            if satisfying is not None:
+                interval = interval.intersect(satisfying)
+        feasible[variable] = interval
+
+        for i, first in enumerate(bounds):
+            for second in bounds[i + 1:]:
+                if first.source == second.source:
+                    continue
+                a, b = _satisfying(first), _satisfying(second)
+                contradictory = (
+                    (a is not None and b is not None and a.intersect(b).empty)
+                    or (a is None and b == _Interval(first.value, True, first.value, True))
+                    or (b is None and a == _Interval(second.value, True, second.value, True))
+                )
+                pair = (first.source, second.source)
+                if contradictory and pair not in seen_pairs:
+                    seen_pairs.add(pair)
+                    findings.append(AnalysisFinding(
+                        ERROR, "contradiction", first.source,
+                        f"No value of {variable} satisfies both {_describe(first, variable)} "
+                        f"and {_describe(second, variable)}; every request carrying it is blocked",
+                    ))
+
+    # A rule (every alias) or single-variable relation (every link) implied by stricter
+    # bounds from other sources never changes a verdict
+    owned: Dict[str, List[Tuple[str, _Bound]]] = {}
+    for variable, bounds in by_variable.items():
+        for bound in bounds:
+            owned.setdefault(bound.source, []).append((variable, bound))
+    expected = {rule.rule_id: len(rule.variables) for rule in definitions.rules}
+    expected.update({
+        relation.rule_id: len(ast.parse(relation.expression.source, mode="eval").body.ops)
+        for relation in definitions.relations
+    })
+    for source, entries in sorted(owned.items()):
+        implied = [(variable, bound, _implied_by(bound, by_variable[variable])) for variable, bound in entries]
+        if len(entries) == expected.get(source) and all(other is not None for _, _, other in implied):
+            variable, bound, other = implied[0]
+            findings.append(AnalysisFinding(
+                WARNING, "unreachable_rule", source,
+                f"{_describe(bound, variable)} never fires alone: {_describe(other, variable)} "
+                f"already blocks every value it blocks",
+            ))
+
+    for rule in definitions.temporal:
+        if rule.kind == "monotonic":
+            continue
+        interval = feasible.get(rule.variable)
+        if interval is not None and math.isfinite(interval.lo) and math.isfinite(interval.hi):
+            width = interval.hi - interval.lo
+            if rule.limit >= width:
+                findings.append(AnalysisFinding(
+                    WARNING, "unreachable_rule", rule.rule_id,
+                    f"Change limit {rule.limit:g} is at least the allowed range of {rule.variable} "
+                    f"({interval.lo:g} to {interval.hi:g}); recorded values can never breach it",
+                ))
+    return findings
+
+
+# ---------------------------------------------------------------------------
+# Aliases, articles and units
+# ---------------------------------------------------------------------------
+
+def _normalized(name: str) -> str:
+    return re.sub(r"[^a-z0-9]", "", name.lower())
+
+
+def _check_aliases(definitions: ConstraintDefinitions) -> List[AnalysisFinding]:
+    findings: List[AnalysisFinding] = []
+    names: Dict[str, str] = {}
+    for rule in definitions.rules:
+        names.update({variable: rule.rule_id for variable in rule.variables})
+    for relation in definitions.relations:
+        for variable in sorted(relation.variables):
+            names.setdefault(variable, relation.rule_id)
+    for rule in definitions.temporal:
+        for variable in rule.variables:
+            names.setdefault(variable, rule.rule_id)
+
+    by_normalized: Dict[str, List[str]] = {}
+    for name in sorted(names):
+        by_normalized.setdefault(_normalized(name), []).append(name)
+    for variants in by_normalized.values():
+        if len(variants) > 1:
+            findings.append(AnalysisFinding(
+                WARNING, "overlapping_aliases", names[variants[1]],
+                f"Inputs {', '.join(repr(v) for v in variants)} look like the same variable but are "
+                f"matched separately ({', '.join(sorted({names[v] for v in variants}))})",
+            ))
+
+    for i, first in enumerate(definitions.temporal):
+        for second in definitions.temporal[i + 1:]:
+            if set(first.variables) & set(second.variables) and first.variable != second.variable:
+                findings.append(AnalysisFinding(
+                    WARNING, "overlapping_aliases", second.rule_id,
+                    f"Temporal rules '{first.rule_id}' and '{second.rule_id}' share aliases but keep "
+                    f"history under different names ('{first.variable}' vs '{second.variable}')",
+                ))
+
+    rule_aliases = {rule.rule_id: set(rule.variables) for rule in definitions.rules}
+    for temporal in definitions.temporal:
+        for rule_id, aliases in rule_aliases.items():
+            shared = aliases & set(temporal.variables)
+            if shared and aliases != set(temporal.variables):
+                findings.append(AnalysisFinding(
+                    WARNING, "overlapping_aliases", temporal.rule_id,
+                    f"Temporal rule '{temporal.rule_id}' and rule '{rule_id}' share {sorted(shared)} "
+                    f"but not every alias, so some inputs are limited without being rate-checked (or vice versa)",
+                ))
+    return findings
+
+
+def _check_articles(definitions: ConstraintDefinitions) -> List[AnalysisFinding]:
+    unmapped = [
+        (kind, item_id)
+        for kind, items in (
+            ("Rule", [(r.rule_id, r.articles) for r in definitions.rules]),
+            ("Relation", [(r.rule_id, r.articles) for r in definitions.relations]),
+            ("Temporal rule", [(r.rule_id, r.articles) for r in definitions.temporal]),
+            ("Check", [(c.check_id, c.articles) for c in definitions.checks]),
+        )
+        for item_id, articles in items
+        if not articles
+    ]
+    return [
+        AnalysisFinding(
+            WARNING, "missing_regulatory_mapping", item_id,
+            f"{kind} '{item_id}' has no regulatory articles; its violations fall back to the default mapping",
+        )
+        for kind, item_id in unmapped
+    ]
+
+
+def _unit(node: ast.AST, units: Dict[str, Optional[str]], conflicts: List[str]) -> Optional[str]:
+    """
+    Unit of a subexpression: "" for plain numbers, None when unknown. Adding, subtracting,
+    comparing or min/max-ing two different known units is recorded as a conflict.
+    """
+    def unify(left: Optional[str], right: Optional[str], where: ast.AST) -> Optional[str]:
+        if left is None or right is None:
+            return None
+        if left and right and left != right:
+            conflicts.append(f"{ast.unparse(where)} mixes {left} and {right}")
+            return None
+        return left or right
+
+    if isinstance(node, ast.Constant):
+        return ""
+    if isinstance(node, ast.Name):
+        return units.get(node.id)
+    if isinstance(node, ast.UnaryOp):
+        return _unit(node.operand, units, conflicts)
+    if isinstance(node, ast.Call):
+        args = [_unit(arg, units, conflicts) for arg in node.args]
+        result = args[0]
+        for arg in args[1:]:
+            result = unify(result, arg, node)
+        return result
+    if isinstance(node, ast.BinOp):
+        left, right = _unit(node.left, units, conflicts), _unit(node.right, units, conflicts)
+        if isinstance(node.op, (ast.Add, ast.Sub)):
+            return unify(left, right, node)
+        if left is None or right is None:
+            return None
+        if isinstance(node.op, ast.Mult):
+            return "*".join(u for u in (left, right) if u)
+        if left == right:
+            return ""
+        return f"{left}/{right}" if right else left
+    if isinstance(node, ast.Compare):
+        operands = [node.left, *node.comparators]
+        resolved = [_unit(operand, units, conflicts) for operand in operands]
+        for left, right in zip(resolved, resolved[1:]):
+            unify(left, right, node)
+        return ""
+    return None
+
+
+def _check_units(
+    definitions: ConstraintDefinitions, policy: Optional[TradePolicyConfig]
+) -> List[AnalysisFinding]:
+    findings: List[AnalysisFinding] = []
+    units: Dict[str, Optional[str]] = {}
+    for rule in definitions.rules:
+        for variable in rule.variables:
+            units[variable] = rule.unit
+
+    for relation in definitions.relations:
+        conflicts: List[str] = []
+        tree = ast.parse(relation.expression.source, mode="eval").body
+        _unit(tree, {**units, **{name: "" for name in relation.parameters}}, conflicts)
+        for conflict in conflicts:
+            findings.append(AnalysisFinding(WARNING, "unit_mismatch", relation.rule_id, f"Expression {conflict}"))
+
+    for rule in definitions.rules:
+        if not rule.unit:
+            continue
+        for suffix in _PLACEHOLDER_SUFFIX.findall(rule.message):
+            if suffix != rule.unit:
+                findings.append(AnalysisFinding(
+                    WARNING, "unit_mismatch", rule.rule_id,
+                    f"Message renders values in '{suffix}' but the rule's unit is '{rule.unit}'",
+                ))
+
+    if policy is not None:
+        for variable, unit in _POLICY_UNITS.items():
+            rule = definitions.rule_for(variable)
+            if rule is not None and rule.unit is not None and rule.unit != unit:
+                findings.append(AnalysisFinding(
+                    WARNING, "unit_mismatch", rule.rule_id,
+                    f"Rule measures {variable} in '{rule.unit}' but the trade policy uses '{unit}'",
+                ))
+    return findings
+
+
+# ---------------------------------------------------------------------------
+# Trade policy
+# ---------------------------------------------------------------------------
+
+def _check_policy(policy: TradePolicyConfig) -> List[AnalysisFinding]:
+    findings: List[AnalysisFinding] = []
+    if policy.max_position_size < policy.min_lot_size:
+        findings.append(AnalysisFinding(
+            ERROR, "contradiction", "max_position_size",
+            f"max_position_size {policy.max_position_size:g} is below min_lot_size {policy.min_lot_size:g}; "
+            f"no order can be sized within the cap",
+        ))
+    if policy.kill_switch_enabled:
+        findings.append(AnalysisFinding(
+            WARNING, "unreachable_rule", "kill_switch_enabled",
+            "Kill switch is enabled: every order is blocked and no other control can change the outcome",
+        ))
+
+    seen: Dict[str, str] = {}
+    for entry in policy.restricted_instruments:
+        target = f"restricted_instruments[{entry!r}]"
+        symbol = entry.strip().upper()
+        if not symbol or entry != entry.strip() or any(ch.isspace() for ch in symbol):
+            findings.append(AnalysisFinding(
+                WARNING, "unreachable_rule", target,
+                f"{entry!r} contains whitespace and never matches an order symbol",
+            ))
+        elif ":" in symbol:
+            findings.append(AnalysisFinding(
+                WARNING, "unreachable_rule", target,
+                f"{entry!r} carries an exchange prefix; orders are matched on the bare symbol "
+                f"({symbol.split(':', 1)[1]!r})",
+            ))
+        elif _SHARE_CLASS_SEPARATORS.search(symbol):
+            variants = sorted({_SHARE_CLASS_SEPARATORS.sub(sep, symbol) for sep in "./-"} - {symbol})
+            findings.append(AnalysisFinding(
+                WARNING, "overlapping_aliases", target,
+                f"{entry!r} only matches this exact notation; {', '.join(variants)} would not be restricted",
+            ))
+        key = _SHARE_CLASS_SEPARATORS.sub("", symbol.split(":")[-1].replace(" ", ""))
+        if key in seen:
+            findings.append(AnalysisFinding(
+                WARNING, "overlapping_aliases", target,
+                f"{entry!r} duplicates {seen[key]!r} after normalization",
+            ))
+        else:
+            seen[key] = entry
+
+    for symbol, limit in sorted(policy.symbol_position_limits.items()):
+        target = f"symbol_position_limits[{symbol!r}]"
+        if limit < policy.min_lot_size:
+            findings.append(AnalysisFinding(
+                ERROR, "contradiction", target,
+                f"cap {limit:g} is below min_lot_size {policy.min_lot_size:g}; no {symbol} order can be sized within it",
+            ))
+        elif limit >= policy.max_position_size:
+            findings.append(AnalysisFinding(
+                WARNING, "unreachable_rule", target,
+                f"cap {limit:g} is not below max_position_size {policy.max_position_size:g} and never binds",
+            ))
+    if (
+        policy.max_single_name_pct is not None
+        and policy.max_single_name_pct < 1
+        and policy.concentration_min_gross_notional <= 0
+    ):
+        findings.append(AnalysisFinding(
+            ERROR, "contradiction", "concentration_min_gross_notional",
+            f"max_single_name_pct {policy.max_single_name_pct:.2%} applies from the first order; on a flat "
+            f"account that order is all of gross exposure and is always blocked without a gross floor",
+        ))
+    for field in ("sector_allocation_pct", "asset_class_allocation_pct"):
+        for name, pct in sorted(getattr(policy, field).items()):
+            if pct >= policy.max_capital_allocation_pct:
+                findings.append(AnalysisFinding(
+                    WARNING, "unreachable_rule", f"{field}[{name!r}]",
+                    f"{pct:.2%} is not below max_capital_allocation_pct "
+                    f"{policy.max_capital_allocation_pct:.2%} and never binds",
+                ))
+    return findings
+
+
+def analyze(
+    definitions: Optional[ConstraintDefinitions] = None,
+    policy: Optional[TradePolicyConfig] = None,
+) -> AnalysisReport:
+    """Analyze a constraint bundle, a trade policy, or both (cross-checks need both)."""
+    findings: List[AnalysisFinding] = []
+    if definitions is not None:
+        findings.extend(_check_bounds(definitions))
+        findings.extend(_check_aliases(definitions))
+        findings.extend(_check_articles(definitions))
+        findings.extend(_check_units(definitions, policy))
+    if policy is not None:
+        findings.extend(_check_policy(policy))
+    ordered = sorted(findings, key=lambda f: (f.severity != ERROR, f.code, f.target))
+    return AnalysisReport(findings=tuple(ordered))
+
+
+def format_report(report: AnalysisReport) -> str:
+    """Plain-text rendering used by the CLI."""
+    lines = [f"{f.severity.upper():7} {f.code:27} {f.target}: {f.message}" for f in report.findings]
+    lines.append(f"{len(report.errors)} error(s), {len(report.warnings)} warning(s)")
+    return "\n".join(lines)
@@ -0,0 +1,204 @@
+"""
+Chain-of-Thought Step Auditing
+Validates an agent's reasoning trace (AgentIntentRequest.chain_of_thought) step by step.
+
+Checks:
+1. Contradictions inside a step and against earlier steps (negation flips and opposing
+   directives between statements about the same thing)
+2. Numeric inconsistencies: a step restating a quantity/price/percentage from an earlier
+   step with a different value
+3. The final step agrees with the declared `intent` and `desired_state`
+
+The first divergent step index (0-based) is recorded so reviewers can see where the
+reasoning went wrong.
+"""
+
+from __future__ import annotations
+
+import re
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional, Tuple
+
+from app.core.entropy import _contradicts, _opposite_directive, _tokenize
+from app.core.divergence_backends import get_backend
+from app.core.numeric_claims import CLAIM_TOLERANCES, NumericClaim, extract_claims, extract_tickers
+from app.core.violations import ConstraintViolation
+
+
+VIOLATION_PREFIX = "Reasoning trace diverged"
+CHECK_ID = "reasoning_trace"  # check id in the constraint definitions
+MAX_STEPS = 50
+# Two statements are "about the same thing" when their lexical similarity reaches this level;
+# only then does a negation flip count as a contradiction.
+CONTRADICTION_SIMILARITY = 0.5
+
+_SENTENCE_SPLIT = re.compile(r"(?<=[.!?;])\s+")
+
+
+@dataclass
+class ReasoningFinding:
+    step: int
+    kind: str    # internal_contradiction | contradicts_earlier_step | numeric_inconsistency | intent_mismatch
+    detail: str
+
+    @property
+    def message(self) -> str:
+        return f"{VIOLATION_PREFIX} at step {self.step}: {self.detail}"
+
+    def to_violation(self) -> ConstraintViolation:
+        return ConstraintViolation(rule_id=CHECK_ID, message=self.message, variable=f"step_{self.step}")
+
+
+@dataclass
+class ReasoningAudit:
+    steps_checked: int
+    first_divergent_step: Optional[int]
+    findings: List[ReasoningFinding] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, object]:
+        return {
+            "steps_checked": self.steps_checked,
+            "first_divergent_step": self.first_divergent_step,
+            "findings": [
+                {"step": f.step, "kind": f.kind, "detail": f.detail} for f in self.findings
+            ],
+        }
+
+
+def _same_subject(a: str, b: str) -> bool:
+    similarity = get_backend("lexical-js").similarity_matrix([a, b])[0][1]
+    return similarity >= CONTRADICTION_SIMILARITY
+
+
+def _statements_conflict(a: str, b: str) -> bool:
+    if _opposite_directive(set(_tokenize(a)), set(_tokenize(b))) is not None:
+        return _same_subject(a, b) or bool(set(extract_tickers(a)) & set(extract_tickers(b)))
+    return _contradicts(a, b) and _same_subject(a, b)
+
+
+def _claim_key(claim: NumericClaim) -> Tuple[str, Optional[str], Optional[str], Optional[str]]:
+    return claim.kind, claim.subject, claim.unit, claim.cue
+
+
+def _disagrees(left: float, right: float, kind: str) -> bool:
+    scale = max(min(abs(left), abs(right)), 1e-9)
+    return abs(left - right) / scale > CLAIM_TOLERANCES.get(kind, 0.0)
+
+
+def _format(value: float) -> str:
+    return f"{value:,.0f}" if value == int(value) else f"{value:,.4g}"
+
+
+def _check_internal(step_index: int, step: str) -> List[ReasoningFinding]:
+    sentences = [s for s in _SENTENCE_SPLIT.split(step.strip()) if s]
+    for i in range(len(sentences)):
+        for j in range(i + 1, len(sentences)):
+            if _statements_conflict(sentences[i], sentences[j]):
+                return [
+                    ReasoningFinding(
+                        step=step_index,
+                        kind="internal_contradiction",
+                        detail=f'"{sentences[i]}" contradicts "{sentences[j]}"',
+                    )
+                ]
+    return []
+
+
+def _check_against_earlier(step_index: int, steps: List[str]) -> List[ReasoningFinding]:
+    findings: List[ReasoningFinding] = []
+    current = steps[step_index]
+    for earlier_index in range(step_index):
+        if _statements_conflict(steps[earlier_index], current):
+            findings.append(
+                ReasoningFinding(
+                    step=step_index,
+                    kind="contradicts_earlier_step",
+                    detail=f"contradicts step {earlier_index}",
+                )
+            )
+            break
+    return findings
+
+
+def _check_numeric(step_index: int, claims_by_step: List[List[NumericClaim]]) -> List[ReasoningFinding]:
+    earlier: Dict[Tuple, Tuple[int, float]] = {}
+    for index in range(step_index):
+        for claim in claims_by_step[index]:
+            earlier.setdefault(_claim_key(claim), (index, claim.value))
+
+    for claim in claims_by_step[step_index]:
+        previous = earlier.get(_claim_key(claim))
+        if previous is not None and _disagrees(previous[1], claim.value, claim.kind):
+            label = " ".join(part for part in (claim.kind, claim.subject) if part)
+            return [
+                ReasoningFinding(
+                    step=step_index,
+                    kind="numeric_inconsistency",
+                    detail=f"{label} {_format(claim.value)} vs {_format(previous[1])} in step {previous[0]}",
+                )
+            ]
+    return []
+
+
+def _check_final(step_index: int, final_step: str, reference: str, label: str) -> List[ReasoningFinding]:
+    findings: List[ReasoningFinding] = []
+    directive = _opposite_directive(set(_tokenize(final_step)), set(_tokenize(reference)))
+    if directive is not None:
+        findings.append(
+            ReasoningFinding(
+                step=step_index,
+                kind="intent_mismatch",
+                detail=f'final step says "{directive[0]}" but {label} says "{directive[1]}"',
+            )
+        )
+
+    final_claims = {(c.kind, c.subject, c.unit): c for c in extract_claims(final_step)}
+    for claim in extract_claims(reference):
+        stated = final_claims.get((claim.kind, claim.subject, claim.unit))
+        if stated is not None and _disagrees(stated.value, claim.value, claim.kind):
+            findings.append(
+                ReasoningFinding(
+                    step=step_index,
+                    kind="intent_mismatch",
+                    detail=f"final step {claim.kind} {_format(stated.value)} vs {label} {_format(claim.value)}",
+                )
+            )
+            break
+    return findings
+
+
+def audit_chain_of_thought(
+    steps: Optional[List[str]],
+    intent: Optional[str] = None,
+    desired_state: Optional[str] = None,
+) -> ReasoningAudit:
+    """Audit a reasoning trace. Returns an empty audit when no trace was supplied."""
+    steps = list((steps or [])[:MAX_STEPS])
+    findings: List[ReasoningFinding] = []
+    claims_by_step = [extract_claims(step) for step in steps]
+
+    for index, step in enumerate(steps):
+        findings.extend(_check_internal(index, step))
+        findings.extend(_check_against_earlier(index, steps))
+        findings.extend(_check_numeric(index, claims_by_step))
+
+    if steps:
+        final_index = len(steps) - 1
+        if intent:
+            findings.extend(_check_final(final_index, steps[final_index], intent, "intent"))
+        if desired_state:
+            findings.extend(_check_final(final_index, steps[final_index], desired_state, "desired_state"))
+
+    return ReasoningAudit(
+        steps_checked=len(steps),
+        first_divergent_step=min((f.step for f in findings), default=None),
+        findings=findings,
+    )
+
+
+def check_reasoning(
+    steps: Optional[List[str]], intent: Optional[str], desired_state: Optional[str]
+) -> Tuple[List[ConstraintViolation], ReasoningAudit]:
+    """check_logic-style violation records plus the full audit for evidence."""
+    audit = audit_chain_of_thought(steps, intent, desired_state)
+    return [finding.to_violation() for finding in audit.findings], audit
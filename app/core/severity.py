@@ -4,7 +4,10 @@ Rates violation severity by how far they exceed hard limits
 """
 
 from dataclasses import dataclass
-from typing import Optional
+from typing import List, Optional, Tuple
+
+from app.core.constraint_rules import ConstraintDefinitions, get_constraint_definitions
+from app.core.violations import ConstraintViolation
 
 
 @dataclass
@@ -15,19 +18,32 @@ class ViolationSeverity:
     impact_description: str
 
 
-CONSTRAINT_LIMITS = {
-    "leverage_ratio": 10.0,
-    "proposed_loss": -10_000.0,
-    "temperature": 0.0,
-    "pressure": 0.0,
-    "energy_in_vs_out": 0.0,  # Special case: energy_out - energy_in
-}
+def _overage_pct(actual_value: float, limit_value: float) -> float:
+    if limit_value == 0.0:
+        # For zero-based limits (temp, pressure), use absolute value
+        return abs(actual_value) * 100  # Arbitrary scale for absolute values
+    return (abs(actual_value - limit_value) / abs(limit_value)) * 100
+
+
+def _weight_and_impact(severity_pct: float, floor: Optional[float]) -> Tuple[float, str]:
+    # Determine weight based on severity
+    if severity_pct < 25:
+        weight = 1.0
+    elif severity_pct < 100:
+        weight = 2.0
+    else:
+        weight = 4.0
+    # The matching rule/check's declared weight is a floor
+    weight = max(weight, floor or 0.0)
+    impact = "Minor" if weight < 2.0 else "Medium" if weight < 4.0 else "Critical"
+    return weight, impact
 
 
 def calculate_violation_severity(
     violation_name: str,
     actual_value: float,
     limit_value: Optional[float] = None,
+    definitions: Optional[ConstraintDefinitions] = None,
 ) -> ViolationSeverity:
     """
     Score a constraint violation by severity.
@@ -38,28 +54,14 @@ def calculate_violation_severity(
         <25%: 1.0 (minor)
         25-100%: 2.0 (medium)
         >100%: 4.0 (critical)
+    - the severity_weight declared on the matching constraint rule/check is a floor
     """
+    definitions = definitions or get_constraint_definitions()
     if limit_value is None:
-        limit_value = CONSTRAINT_LIMITS.get(violation_name, 0.0)
-    
-    if limit_value == 0.0:
-        # For zero-based limits (temp, pressure), use absolute value
-        overage = abs(actual_value)
-        severity_pct = overage * 100  # Arbitrary scale for absolute values
-    else:
-        overage = abs(actual_value - limit_value)
-        severity_pct = (overage / abs(limit_value)) * 100
+        limit_value = definitions.limits().get(violation_name, 0.0)
     
-    # Determine weight based on severity
-    if severity_pct < 25:
-        weight = 1.0
-        impact = "Minor"
-    elif severity_pct < 100:
-        weight = 2.0
-        impact = "Medium"
-    else:
-        weight = 4.0
-        impact = "Critical"
+    severity_pct = _overage_pct(actual_value, limit_value)
+    weight, impact = _weight_and_impact(severity_pct, definitions.severity_weight_for(violation_name))
     
     description = f"{impact} violation: {severity_pct:.1f}% over limit (actual={actual_value}, limit={limit_value})"
     
@@ -71,6 +73,51 @@ def calculate_violation_severity(
     )
 
 
+def calculate_violation_severities(
+    violations: List[ConstraintViolation],
+    definitions: Optional[ConstraintDefinitions] = None,
+) -> List[ViolationSeverity]:
+    """
+    Score each violation record from check_logic on its actual value against its limit.
+    
+    Cross-sample disagreements carry their observed spread and tolerance, so a 10x
+    quantity disagreement rates as critical. Records without a measured value (e.g.
+    reasoning findings) score 0% and take the weight declared in the definitions.
+    """
+    definitions = definitions or get_constraint_definitions()
+    severities: List[ViolationSeverity] = []
+    for violation in violations:
+        floor = definitions.severity_weight_for(violation)
+        if violation.actual is None:
+            weight, impact = _weight_and_impact(0.0, floor)
+            severities.append(
+                ViolationSeverity(
+                    violation=violation.message,
+                    severity_pct=0.0,
+                    weight=weight,
+                    impact_description=f"{impact} violation: no measured overage",
+                )
+            )
+            continue
+        
+        limit_value = violation.limit if violation.limit is not None else 0.0
+        severity_pct = _overage_pct(violation.actual, limit_value)
+        weight, impact = _weight_and_impact(severity_pct, floor)
+        unit = f" {violation.unit}" if violation.unit else ""
+        severities.append(
+            ViolationSeverity(
+                violation=violation.message,
+                severity_pct=severity_pct,
+                weight=weight,
+                impact_description=(
+                    f"{impact} violation: {severity_pct:.1f}% over limit "
+                    f"(actual={violation.actual:g}{unit}, limit={limit_value:g}{unit})"
+                ),
+            )
+        )
+    return severities
+
+
 def calculate_overall_severity_score(violations: list[ViolationSeverity]) -> float:
     """
     Aggregate severity scores across all violations.
@@ -0,0 +1,155 @@
+"""
+Shadow Mode
+Evaluates live traffic against a candidate constraint bundle or trade policy without enforcing it.
+
+A tenant attaches at most one candidate of each kind (storage.set_shadow_candidate). The
+enforced verdict is always the live one; when the candidate would have decided differently
+the pair is stored in shadow_divergences for the /v4/shadow/report summary. Shadow failures
+are logged and never affect the live response.
+"""
+
+from __future__ import annotations
+
+import logging
+from typing import Any, Dict, List, Optional
+
+from app.core.constraint_rules import resolve_constraint_definitions
+from app.core.constraints import check_logic, constraint_inputs
+from app.core.decision_engine import decide
+from app.core.storage import get_constraint_bundle, get_shadow_candidate, insert_shadow_divergence
+from app.core.temporal_constraints import check_temporal_constraints
+from app.core.trade_guard import evaluate_basket, evaluate_pre_trade
+from app.core.violations import ConstraintViolation, violation_messages
+from app.models import AccountSnapshot, AgentIntentRequest, TradeOrder, TradePolicyConfig
+
+logger = logging.getLogger(__name__)
+
+SHADOW_CONSTRAINT_BUNDLE = "constraint_bundle"
+SHADOW_TRADE_POLICY = "trade_policy"
+SHADOW_KINDS = (SHADOW_CONSTRAINT_BUNDLE, SHADOW_TRADE_POLICY)
+
+
+def shadow_validation(
+    request_id: str,
+    request: AgentIntentRequest,
+    entropy_score: float,
+    reasoning_violations: List[ConstraintViolation],
+    latency_exceeded: bool,
+    live_action: str,
+    live_violations: List[str],
+) -> Optional[str]:
+    """
+    Re-judge a /v3 intent with the candidate bundle (same divergence score, reasoning
+    findings and latency flag). Temporal rules read the agent's live metric history.
+    Returns the shadow action, or None when no candidate is attached.
+    """
+    try:
+        candidate = get_shadow_candidate(request.tenant_id, SHADOW_CONSTRAINT_BUNDLE)
+        if candidate is None:
+            return None
+        version = candidate["candidate"]["version"]
+        bundle = get_constraint_bundle(request.tenant_id, version)
+        if bundle is None:
+            logger.warning("Shadow bundle v%s for tenant %s no longer exists", version, request.tenant_id)
+            return None
+        definitions = resolve_constraint_definitions(bundle)
+        records = check_logic(request, definitions)
+        records.extend(
+            check_temporal_constraints(request.tenant_id, request.agent_id, constraint_inputs(request), definitions)
+        )
+        records.extend(reasoning_violations)
+        shadow_action = decide(entropy_score, records, latency_exceeded=latency_exceeded).action
+        if shadow_action != live_action:
+            insert_shadow_divergence(
+                tenant_id=request.tenant_id,
+                kind=SHADOW_CONSTRAINT_BUNDLE,
+                reference_id=request_id,
+                agent_id=request.agent_id,
+                live_action=live_action,
+                shadow_action=shadow_action,
+                candidate_checksum=candidate["checksum"],
+                live={"action": live_action, "violations": live_violations},
+                shadow={
+                    "action": shadow_action,
+                    "violations": violation_messages(records),
+                    "constraint_bundle_version": version,
+                },
+            )
+        return shadow_action
+    except Exception:
+        logger.exception("Shadow validation failed for request %s", request_id)
+        return None
+
+
+def shadow_pre_trade(
+    decision_id: str,
+    tenant_id: str,
+    agent_id: Optional[str],
+    order: TradeOrder,
+    account: AccountSnapshot,
+    live_result: Dict[str, Any],
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]] = None,
+) -> Optional[str]:
+    """Re-evaluate a pre-trade order with the candidate policy. Returns the shadow decision."""
+    try:
+        candidate = get_shadow_candidate(tenant_id, SHADOW_TRADE_POLICY)
+        if candidate is None:
+            return None
+        policy = TradePolicyConfig(**candidate["candidate"])
+        shadow_result = evaluate_pre_trade(order=order, account=account, policy=policy, instruments=instruments)
+        _record_trade_divergence(tenant_id, decision_id, agent_id, candidate, policy, live_result, shadow_result)
+        return shadow_result["decision"]
+    except Exception:
+        logger.exception("Shadow pre-trade evaluation failed for decision %s", decision_id)
+        return None
+
+
+def shadow_basket(
+    basket_id: str,
+    tenant_id: str,
+    agent_id: Optional[str],
+    legs: List[TradeOrder],
+    account: AccountSnapshot,
+    mode: str,
+    live_result: Dict[str, Any],
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]] = None,
+) -> Optional[str]:
+    """Re-evaluate a basket as one unit with the candidate policy. Returns the shadow decision."""
+    try:
+        candidate = get_shadow_candidate(tenant_id, SHADOW_TRADE_POLICY)
+        if candidate is None:
+            return None
+        policy = TradePolicyConfig(**candidate["candidate"])
+        shadow_result = evaluate_basket(legs, account, policy, mode, instruments=instruments)
+        _record_trade_divergence(tenant_id, basket_id, agent_id, candidate, policy, live_result, shadow_result)
+        return shadow_result["decision"]
+    except Exception:
+        logger.exception("Shadow basket evaluation failed for basket %s", basket_id)
+        return None
+
+
+def _record_trade_divergence(
+    tenant_id: str,
+    reference_id: str,
+    agent_id: Optional[str],
+    candidate: Dict[str, Any],
+    policy: TradePolicyConfig,
+    live_result: Dict[str, Any],
+    shadow_result: Dict[str, Any],
+) -> None:
+    if shadow_result["decision"] == live_result["decision"]:
+        return
+    insert_shadow_divergence(
+        tenant_id=tenant_id,
+        kind=SHADOW_TRADE_POLICY,
+        reference_id=reference_id,
+        agent_id=agent_id,
+        live_action=live_result["decision"],
+        shadow_action=shadow_result["decision"],
+        candidate_checksum=candidate["checksum"],
+        live={key: live_result[key] for key in ("decision", "reason", "triggered_controls")},
+        shadow={
+            **{key: shadow_result[key] for key in ("decision", "reason", "triggered_controls")},
+            "policy_version": policy.policy_version,
+        },
+    )
@@ -1,12 +1,15 @@
 from __future__ import annotations
 
-from typing import Any, Dict, List
+from typing import Any, Dict, List, Optional
 
 from app.models import AccountSnapshot, SimulationRequest, TradeOrder
 from app.core.trade_guard import evaluate_pre_trade
 
 
-def run_policy_simulation(request: SimulationRequest) -> Dict[str, Any]:
+def run_policy_simulation(
+    request: SimulationRequest,
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]] = None,
+) -> Dict[str, Any]:
     capital = request.initial_capital
     peak_capital = request.initial_capital
     max_drawdown_observed = 0.0
@@ -49,7 +52,7 @@ def run_policy_simulation(request: SimulationRequest) -> Dict[str, Any]:
             leverage_ratio=event.leverage_ratio,
         )
 
-        result = evaluate_pre_trade(order=order, account=account, policy=request.policy)
+        result = evaluate_pre_trade(order=order, account=account, policy=request.policy, instruments=instruments)
         decision = result["decision"]
 
         if decision == "ALLOW":
@@ -7,7 +7,7 @@ import threading
 import hashlib
 from datetime import datetime, timedelta, timezone
 from pathlib import Path
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, List, Optional, Tuple
 
 try:
     import sqlcipher3.dbapi2 as sqlcipher
@@ -81,6 +81,12 @@ def _parse_execution_decision_row(row: sqlite3.Row) -> Dict[str, Any]:
         "order": json.loads(row["order_json"]),
         "account": json.loads(row["account_json"]),
         "policy": json.loads(row["policy_json"]),
+        "policy_id": row["policy_id"],
+        "policy_version": row["policy_version"],
+        "account_id": row["account_id"],
+        "ledger": json.loads(row["ledger_json"]) if row["ledger_json"] else None,
+        "basket_id": row["basket_id"],
+        "leg_index": row["leg_index"],
         "modified_order": json.loads(row["modified_order_json"]) if row["modified_order_json"] else None,
         "idempotency_key": row["idempotency_key"],
         "request_fingerprint": row["request_fingerprint"],
@@ -103,6 +109,26 @@ def _parse_simulation_run_row(row: sqlite3.Row) -> Dict[str, Any]:
     }
 
 
+def _parse_audit_event_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "audit_id": row["audit_id"],
+        "request_id": row["request_id"],
+        "tenant_id": row["tenant_id"],
+        "agent_id": row["agent_id"],
+        "event_type": row["event_type"],
+        "violations": json.loads(row["violations_json"]),
+        "violation_records": (
+            json.loads(row["violation_records_json"]) if row["violation_records_json"] else None
+        ),
+        "regulatory_articles": json.loads(row["regulatory_articles_json"]),
+        "message": row["message"],
+        "prev_hash": row["prev_hash"],
+        "chain_hash": row["event_hash"],
+        "event_hash": row["event_hash"],
+        "created_at": row["created_at"],
+    }
+
+
 def _insert_audit_event_with_cursor(
     cur: sqlite3.Cursor,
     request_id: str,
@@ -112,6 +138,7 @@ def _insert_audit_event_with_cursor(
     violations: List[str],
     regulatory_articles: List[str],
     message: str,
+    violation_records: Optional[List[Dict[str, Any]]] = None,
 ) -> int:
     if tenant_id is None:
         cur.execute(
@@ -134,12 +161,16 @@ def _insert_audit_event_with_cursor(
         "regulatory_articles": regulatory_articles,
         "message": message,
     }
+    if violation_records is not None:
+        # Only hashed when present so entries written before records existed still verify
+        event_data["violation_records"] = violation_records
     event_hash = hashlib.sha256((prev_hash + _stable_json(event_data)).encode("utf-8")).hexdigest()
     cur.execute(
         """
         INSERT INTO audit_log
-        (request_id, tenant_id, agent_id, event_type, violations_json, regulatory_articles_json, message, prev_hash, event_hash, created_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        (request_id, tenant_id, agent_id, event_type, violations_json, violation_records_json,
+         regulatory_articles_json, message, prev_hash, event_hash, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         """,
         (
             request_id,
@@ -147,6 +178,7 @@ def _insert_audit_event_with_cursor(
             agent_id,
             event_type,
             json.dumps(violations),
+            json.dumps(violation_records) if violation_records is not None else None,
             json.dumps(regulatory_articles),
             message,
             prev_hash,
@@ -265,6 +297,15 @@ def init_db() -> None:
                 """
             )
             _ensure_column(cur, "validation_results", "tenant_id", "TEXT")
+            _ensure_column(cur, "validation_results", "semantic_clusters_json", "TEXT")
+            _ensure_column(cur, "validation_results", "divergence_backend", "TEXT")
+            _ensure_column(cur, "validation_results", "divergence_backend_version", "TEXT")
+            _ensure_column(cur, "validation_results", "field_divergences_json", "TEXT")
+            _ensure_column(cur, "validation_results", "reasoning_audit_json", "TEXT")
+            _ensure_column(cur, "validation_results", "decision_table_version", "TEXT")
+            _ensure_column(cur, "validation_results", "constraint_bundle_version", "INTEGER")
+            _ensure_column(cur, "validation_results", "violation_records_json", "TEXT")
+            _ensure_column(cur, "validation_results", "agent_id", "TEXT")
 
             cur.execute(
                 """
@@ -284,6 +325,7 @@ def init_db() -> None:
                 """
             )
             _ensure_column(cur, "audit_log", "tenant_id", "TEXT")
+            _ensure_column(cur, "audit_log", "violation_records_json", "TEXT")
             cur.execute("CREATE INDEX IF NOT EXISTS idx_audit_agent ON audit_log(agent_id)")
             cur.execute("CREATE INDEX IF NOT EXISTS idx_audit_created ON audit_log(created_at)")
             cur.execute("CREATE INDEX IF NOT EXISTS idx_audit_tenant ON audit_log(tenant_id)")
@@ -329,7 +371,14 @@ def init_db() -> None:
             )
             _ensure_column(cur, "execution_decisions", "idempotency_key", "TEXT")
             _ensure_column(cur, "execution_decisions", "request_fingerprint", "TEXT")
+            _ensure_column(cur, "execution_decisions", "policy_id", "TEXT")
+            _ensure_column(cur, "execution_decisions", "policy_version", "INTEGER")
+            _ensure_column(cur, "execution_decisions", "account_id", "TEXT")
+            _ensure_column(cur, "execution_decisions", "ledger_json", "TEXT")
+            _ensure_column(cur, "execution_decisions", "basket_id", "TEXT")
+            _ensure_column(cur, "execution_decisions", "leg_index", "INTEGER")
             cur.execute("CREATE INDEX IF NOT EXISTS idx_exec_decisions_tenant ON execution_decisions(tenant_id)")
+            cur.execute("CREATE INDEX IF NOT EXISTS idx_exec_decisions_basket ON execution_decisions(basket_id)")
             cur.execute("CREATE INDEX IF NOT EXISTS idx_exec_decisions_created ON execution_decisions(created_at)")
             cur.execute(
                 "CREATE UNIQUE INDEX IF NOT EXISTS idx_exec_decisions_tenant_idempotency ON execution_decisions(tenant_id, idempotency_key) WHERE idempotency_key IS NOT NULL"
@@ -378,6 +427,327 @@ def init_db() -> None:
                 "CREATE UNIQUE INDEX IF NOT EXISTS idx_sim_runs_tenant_idempotency ON simulation_runs(tenant_id, idempotency_key) WHERE idempotency_key IS NOT NULL"
             )
 
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS tenant_validation_settings (
+                    tenant_id TEXT PRIMARY KEY,
+                    settings_json TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                )
+                """
+            )
+
+            # Per-tenant constraint bundles: versions are immutable once created
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS constraint_bundles (
+                    tenant_id TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    definitions_json TEXT NOT NULL,
+                    checksum TEXT NOT NULL,
+                    note TEXT,
+                    created_by TEXT,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, version)
+                )
+                """
+            )
+            # Activation history; the latest row not rolled back is the active bundle
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS constraint_bundle_activations (
+                    activation_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    tenant_id TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    activated_by TEXT,
+                    activated_at TEXT NOT NULL,
+                    rolled_back_by TEXT,
+                    rolled_back_at TEXT
+                )
+                """
+            )
+            cur.execute(
+                "CREATE INDEX IF NOT EXISTS idx_bundle_activations_tenant ON constraint_bundle_activations(tenant_id)"
+            )
+
+            # Server-side trade policies: versions are immutable once created
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS trade_policies (
+                    tenant_id TEXT NOT NULL,
+                    policy_id TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    policy_json TEXT NOT NULL,
+                    checksum TEXT NOT NULL,
+                    note TEXT,
+                    created_by TEXT,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, policy_id, version)
+                )
+                """
+            )
+            # Policy version bound to an (account, strategy) scope; '' stands for "any"
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS trade_policy_bindings (
+                    tenant_id TEXT NOT NULL,
+                    account_id TEXT NOT NULL,
+                    strategy_id TEXT NOT NULL,
+                    policy_id TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    bound_by TEXT,
+                    bound_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, account_id, strategy_id)
+                )
+                """
+            )
+            # (account, strategy) an API key's pre-trade requests are bound by; '' stands for none
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS api_key_trading_scopes (
+                    tenant_id TEXT NOT NULL,
+                    key_id TEXT NOT NULL,
+                    account_id TEXT NOT NULL,
+                    strategy_id TEXT NOT NULL,
+                    assigned_by TEXT,
+                    assigned_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, key_id)
+                )
+                """
+            )
+            # Tenant instrument reference: sector / asset class per symbol for exposure limits
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS instrument_reference (
+                    tenant_id TEXT NOT NULL,
+                    symbol TEXT NOT NULL,
+                    sector TEXT,
+                    asset_class TEXT,
+                    updated_by TEXT,
+                    updated_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, symbol)
+                )
+                """
+            )
+
+            # Basket (multi-leg) pre-trade decisions; legs are execution_decisions rows with basket_id
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS basket_decisions (
+                    basket_id TEXT PRIMARY KEY,
+                    tenant_id TEXT NOT NULL,
+                    agent_id TEXT,
+                    mode TEXT NOT NULL,
+                    decision TEXT NOT NULL,
+                    reason TEXT NOT NULL,
+                    triggered_controls_json TEXT NOT NULL,
+                    scale REAL NOT NULL,
+                    idempotency_key TEXT,
+                    request_fingerprint TEXT,
+                    created_at TEXT NOT NULL
+                )
+                """
+            )
+            cur.execute(
+                "CREATE UNIQUE INDEX IF NOT EXISTS idx_basket_decisions_tenant_idempotency ON basket_decisions(tenant_id, idempotency_key) WHERE idempotency_key IS NOT NULL"
+            )
+
+            # Server-side account ledger; account_id '' is the tenant's default account
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS ledger_orders (
+                    decision_id TEXT PRIMARY KEY,
+                    tenant_id TEXT NOT NULL,
+                    account_id TEXT NOT NULL,
+                    symbol TEXT NOT NULL,
+                    side TEXT NOT NULL,
+                    quantity REAL NOT NULL,
+                    price REAL NOT NULL,
+                    filled_quantity REAL NOT NULL DEFAULT 0,
+                    status TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                )
+                """
+            )
+            cur.execute(
+                "CREATE INDEX IF NOT EXISTS idx_ledger_orders_account ON ledger_orders(tenant_id, account_id, status)"
+            )
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS ledger_positions (
+                    tenant_id TEXT NOT NULL,
+                    account_id TEXT NOT NULL,
+                    symbol TEXT NOT NULL,
+                    quantity REAL NOT NULL,
+                    avg_price REAL NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, account_id, symbol)
+                )
+                """
+            )
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS ledger_accounts (
+                    tenant_id TEXT NOT NULL,
+                    account_id TEXT NOT NULL,
+                    pnl_date TEXT NOT NULL,
+                    realized_pnl REAL NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, account_id)
+                )
+                """
+            )
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS execution_reports (
+                    tenant_id TEXT NOT NULL,
+                    report_id TEXT NOT NULL,
+                    decision_id TEXT,
+                    report_type TEXT NOT NULL,
+                    quantity REAL,
+                    price REAL,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, report_id)
+                )
+                """
+            )
+            _ensure_column(cur, "execution_reports", "account_id", "TEXT")
+            _ensure_column(cur, "execution_reports", "symbol", "TEXT")
+            _ensure_column(cur, "execution_reports", "side", "TEXT")
+            _ensure_column(cur, "execution_reports", "order_status", "TEXT")
+            _ensure_column(cur, "execution_reports", "breaches_json", "TEXT")
+            cur.execute(
+                "CREATE INDEX IF NOT EXISTS idx_execution_reports_decision ON execution_reports(tenant_id, decision_id)"
+            )
+
+            # Per-agent metric history backing temporal (rate-of-change) constraints
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS agent_metric_history (
+                    history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    tenant_id TEXT,
+                    agent_id TEXT NOT NULL,
+                    variable TEXT NOT NULL,
+                    value REAL NOT NULL,
+                    request_id TEXT,
+                    recorded_at TEXT NOT NULL
+                )
+                """
+            )
+            cur.execute(
+                "CREATE INDEX IF NOT EXISTS idx_metric_history_agent ON agent_metric_history(tenant_id, agent_id, variable, recorded_at)"
+            )
+
+            # Shadow mode: candidate constraint bundle / trade policy evaluated but not enforced
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS shadow_candidates (
+                    tenant_id TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    candidate_json TEXT NOT NULL,
+                    checksum TEXT NOT NULL,
+                    attached_by TEXT,
+                    attached_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, kind)
+                )
+                """
+            )
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS shadow_divergences (
+                    divergence_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    tenant_id TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    reference_id TEXT NOT NULL,
+                    agent_id TEXT,
+                    live_action TEXT NOT NULL,
+                    shadow_action TEXT NOT NULL,
+                    candidate_checksum TEXT NOT NULL,
+                    live_json TEXT NOT NULL,
+                    shadow_json TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                )
+                """
+            )
+            cur.execute(
+                "CREATE INDEX IF NOT EXISTS idx_shadow_divergences_tenant ON shadow_divergences(tenant_id, created_at)"
+            )
+
+            # Reviewer-labelled outcomes for validation results and pre-trade decisions
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS validation_feedback (
+                    feedback_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    tenant_id TEXT NOT NULL,
+                    reference_type TEXT NOT NULL,
+                    reference_id TEXT NOT NULL,
+                    agent_id TEXT,
+                    action TEXT NOT NULL,
+                    outcome TEXT NOT NULL,
+                    rule_ids_json TEXT NOT NULL,
+                    divergence_score REAL,
+                    reviewer_id TEXT,
+                    note TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    UNIQUE (tenant_id, reference_type, reference_id)
+                )
+                """
+            )
+
+            # Drift tracker state per (tenant, agent); '' stands for "no tenant" / "no agent"
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS drift_states (
+                    tenant_id TEXT NOT NULL,
+                    agent_id TEXT NOT NULL,
+                    state_json TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, agent_id)
+                )
+                """
+            )
+
+            # Latest runtime audit chain verification per tenant (readiness probe freshness)
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS audit_chain_verifications (
+                    tenant_id TEXT PRIMARY KEY,
+                    intact INTEGER NOT NULL,
+                    event_count INTEGER NOT NULL,
+                    verified_at TEXT NOT NULL
+                )
+                """
+            )
+
+            # Single-row table the readiness probe writes to
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS health_probes (
+                    probe TEXT PRIMARY KEY,
+                    probed_at TEXT NOT NULL
+                )
+                """
+            )
+
+            # Fitted confidence calibration models; versions are immutable once created
+            cur.execute(
+                """
+                CREATE TABLE IF NOT EXISTS confidence_calibrations (
+                    tenant_id TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    method TEXT NOT NULL,
+                    model_json TEXT NOT NULL,
+                    metrics_json TEXT NOT NULL,
+                    sample_count INTEGER NOT NULL,
+                    fitted_by TEXT,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (tenant_id, version)
+                )
+                """
+            )
+
             conn.commit()
         finally:
             if conn is not None:
@@ -430,6 +800,15 @@ def insert_validation_result(
     context_reset: bool,
     latency_ms: float,
     tenant_id: Optional[str] = None,
+    semantic_clusters: Optional[List[int]] = None,
+    divergence_backend: Optional[str] = None,
+    divergence_backend_version: Optional[str] = None,
+    field_divergences: Optional[List[Dict[str, Any]]] = None,
+    reasoning_audit: Optional[Dict[str, Any]] = None,
+    decision_table_version: Optional[str] = None,
+    constraint_bundle_version: Optional[int] = None,
+    violation_records: Optional[List[Dict[str, Any]]] = None,
+    agent_id: Optional[str] = None,
 ) -> None:
     with _LOCK:
         conn = _connect()
@@ -437,17 +816,29 @@ def insert_validation_result(
         cur.execute(
             """
             INSERT OR REPLACE INTO validation_results
-            (request_id, tenant_id, status_code, action, divergence_score, violations_json, confidence_json,
-             severity_json, drift_json, recommendation, context_reset, latency_ms, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            (request_id, tenant_id, agent_id, status_code, action, divergence_score, violations_json, violation_records_json,
+             semantic_clusters_json,
+             divergence_backend, divergence_backend_version, field_divergences_json, reasoning_audit_json,
+             decision_table_version, constraint_bundle_version, confidence_json, severity_json, drift_json,
+             recommendation, context_reset, latency_ms, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             """,
             (
                 request_id,
                 tenant_id,
+                agent_id,
                 status_code,
                 action,
                 divergence_score,
                 json.dumps(violations),
+                json.dumps(violation_records) if violation_records is not None else None,
+                json.dumps(semantic_clusters) if semantic_clusters is not None else None,
+                divergence_backend,
+                divergence_backend_version,
+                json.dumps(field_divergences) if field_divergences is not None else None,
+                json.dumps(reasoning_audit) if reasoning_audit is not None else None,
+                decision_table_version,
+                constraint_bundle_version,
                 json.dumps(confidence),
                 json.dumps(severity),
                 json.dumps(drift),
@@ -481,10 +872,27 @@ def get_validation_result(request_id: str, tenant_id: Optional[str] = None) -> O
             return None
         return {
             "request_id": request_id,
+            "agent_id": row["agent_id"],
             "status_code": row["status_code"],
             "action": row["action"],
             "divergence_score": row["divergence_score"],
             "violations": json.loads(row["violations_json"]),
+            "violation_records": (
+                json.loads(row["violation_records_json"]) if row["violation_records_json"] else None
+            ),
+            "semantic_clusters": (
+                json.loads(row["semantic_clusters_json"]) if row["semantic_clusters_json"] else None
+            ),
+            "divergence_backend": row["divergence_backend"],
+            "divergence_backend_version": row["divergence_backend_version"],
+            "field_divergences": (
+                json.loads(row["field_divergences_json"]) if row["field_divergences_json"] else None
+            ),
+            "reasoning_audit": (
+                json.loads(row["reasoning_audit_json"]) if row["reasoning_audit_json"] else None
+            ),
+            "decision_table_version": row["decision_table_version"],
+            "constraint_bundle_version": row["constraint_bundle_version"],
             "confidence": json.loads(row["confidence_json"]),
             "severity": json.loads(row["severity_json"]),
             "drift": json.loads(row["drift_json"]),
@@ -495,95 +903,1228 @@ def get_validation_result(request_id: str, tenant_id: Optional[str] = None) -> O
         }
 
 
-def insert_audit_event(
-    request_id: str,
-    tenant_id: Optional[str],
-    agent_id: Optional[str],
-    event_type: str,
-    violations: List[str],
-    regulatory_articles: List[str],
-    message: str,
-) -> int:
-    """Insert audit event with hash chaining for cryptographic immutability.
-    
-    Each event includes prev_hash (chain link) and event_hash (content hash).
-    Regulators can verify: hash(prev_hash + event_json) == event_hash
-    """
+def get_tenant_validation_settings(tenant_id: Optional[str]) -> Dict[str, Any]:
+    """Per-tenant validation defaults (divergence backend, ...). Empty dict when unset."""
+    if tenant_id is None:
+        return {}
     with _LOCK:
         conn = _connect()
         cur = conn.cursor()
-        audit_id = _insert_audit_event_with_cursor(
-            cur=cur,
-            request_id=request_id,
-            tenant_id=tenant_id,
-            agent_id=agent_id,
-            event_type=event_type,
-            violations=violations,
-            regulatory_articles=regulatory_articles,
-            message=message,
+        cur.execute(
+            "SELECT settings_json FROM tenant_validation_settings WHERE tenant_id = ?",
+            (tenant_id,),
         )
-        conn.commit()
+        row = cur.fetchone()
         conn.close()
-        return int(audit_id)
+        return json.loads(row["settings_json"]) if row else {}
 
 
-def list_audit_events(tenant_id: str, agent_id: Optional[str] = None) -> List[Dict[str, Any]]:
+def update_tenant_validation_settings(tenant_id: str, updates: Dict[str, Any]) -> Dict[str, Any]:
+    """Merge `updates` into the tenant's settings. Keys set to None are removed."""
     with _LOCK:
         conn = _connect()
         cur = conn.cursor()
-        if agent_id:
-            cur.execute(
-                """
-                SELECT * FROM audit_log WHERE tenant_id = ? AND agent_id = ? ORDER BY audit_id DESC
-                """,
-                (tenant_id, agent_id),
-            )
-        else:
-            cur.execute("SELECT * FROM audit_log WHERE tenant_id = ? ORDER BY audit_id DESC", (tenant_id,))
-        rows = cur.fetchall()
+        cur.execute(
+            "SELECT settings_json FROM tenant_validation_settings WHERE tenant_id = ?",
+            (tenant_id,),
+        )
+        row = cur.fetchone()
+        settings = json.loads(row["settings_json"]) if row else {}
+        for key, value in updates.items():
+            if value is None:
+                settings.pop(key, None)
+            else:
+                settings[key] = value
+        cur.execute(
+            """
+            INSERT OR REPLACE INTO tenant_validation_settings (tenant_id, settings_json, updated_at)
+            VALUES (?, ?, ?)
+            """,
+            (tenant_id, json.dumps(settings), datetime.utcnow().isoformat()),
+        )
+        conn.commit()
         conn.close()
-        return [
-            {
-                "audit_id": row["audit_id"],
-                "request_id": row["request_id"],
-                "tenant_id": row["tenant_id"],
-                "agent_id": row["agent_id"],
-                "event_type": row["event_type"],
-                "violations": json.loads(row["violations_json"]),
-                "regulatory_articles": json.loads(row["regulatory_articles_json"]),
-                "message": row["message"],
-                "prev_hash": row["prev_hash"],
-                "chain_hash": row["event_hash"],
-                "event_hash": row["event_hash"],
-                "created_at": row["created_at"],
-            }
-            for row in rows
-        ]
+        return settings
 
 
-def reserve_pre_trade_frequency_slot(
-    tenant_id: str,
-    agent_id: Optional[str],
-    idempotency_key: str,
-    bucket_start: Optional[str] = None,
-) -> int:
-    effective_agent_id = agent_id or "__tenant__"
-    current_bucket = bucket_start or _minute_bucket()
-    retention_cutoff = (datetime.now(timezone.utc) - timedelta(minutes=2)).isoformat()
+def _parse_constraint_bundle_row(row: sqlite3.Row, active_version: Optional[int]) -> Dict[str, Any]:
+    return {
+        "tenant_id": row["tenant_id"],
+        "version": row["version"],
+        "definitions": json.loads(row["definitions_json"]),
+        "checksum": row["checksum"],
+        "note": row["note"],
+        "created_by": row["created_by"],
+        "created_at": row["created_at"],
+        "active": row["version"] == active_version,
+    }
+
 
+def _active_bundle_version(cur: sqlite3.Cursor, tenant_id: str) -> Optional[int]:
+    cur.execute(
+        """
+        SELECT version FROM constraint_bundle_activations
+        WHERE tenant_id = ? AND rolled_back_at IS NULL
+        ORDER BY activation_id DESC LIMIT 1
+        """,
+        (tenant_id,),
+    )
+    row = cur.fetchone()
+    return row["version"] if row else None
+
+
+def create_constraint_bundle(
+    tenant_id: str,
+    definitions: Dict[str, Any],
+    created_by: Optional[str] = None,
+    note: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Store a new immutable bundle version (validate it first). Does not activate it."""
+    definitions_json = _stable_json(definitions)
     with _LOCK:
         conn = _connect()
         cur = conn.cursor()
         cur.execute(
-            "DELETE FROM pre_trade_frequency_windows WHERE created_at < ?",
-            (retention_cutoff,),
+            "SELECT COALESCE(MAX(version), 0) + 1 AS next_version FROM constraint_bundles WHERE tenant_id = ?",
+            (tenant_id,),
         )
+        version = cur.fetchone()["next_version"]
         cur.execute(
-            "SELECT bucket_start FROM pre_trade_frequency_windows WHERE tenant_id = ? AND idempotency_key = ?",
-            (tenant_id, idempotency_key),
-        )
-        existing_row = cur.fetchone()
-        bucket_to_count = current_bucket
+            """
+            INSERT INTO constraint_bundles
+            (tenant_id, version, definitions_json, checksum, note, created_by, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                tenant_id,
+                version,
+                definitions_json,
+                hashlib.sha256(definitions_json.encode()).hexdigest(),
+                note,
+                created_by,
+                datetime.utcnow().isoformat(),
+            ),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM constraint_bundles WHERE tenant_id = ? AND version = ?",
+            (tenant_id, version),
+        )
+        bundle = _parse_constraint_bundle_row(cur.fetchone(), _active_bundle_version(cur, tenant_id))
+        conn.close()
+        return bundle
+
+
+def get_constraint_bundle(tenant_id: str, version: int) -> Optional[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM constraint_bundles WHERE tenant_id = ? AND version = ?",
+            (tenant_id, version),
+        )
+        row = cur.fetchone()
+        bundle = _parse_constraint_bundle_row(row, _active_bundle_version(cur, tenant_id)) if row else None
+        conn.close()
+        return bundle
+
+
+def list_constraint_bundles(tenant_id: str) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        active_version = _active_bundle_version(cur, tenant_id)
+        cur.execute(
+            "SELECT * FROM constraint_bundles WHERE tenant_id = ? ORDER BY version",
+            (tenant_id,),
+        )
+        bundles = [_parse_constraint_bundle_row(row, active_version) for row in cur.fetchall()]
+        conn.close()
+        return bundles
+
+
+def get_active_constraint_bundle(tenant_id: Optional[str]) -> Optional[Dict[str, Any]]:
+    """The tenant's active bundle, or None when the shipped defaults apply."""
+    if tenant_id is None:
+        return None
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        active_version = _active_bundle_version(cur, tenant_id)
+        bundle = None
+        if active_version is not None:
+            cur.execute(
+                "SELECT * FROM constraint_bundles WHERE tenant_id = ? AND version = ?",
+                (tenant_id, active_version),
+            )
+            bundle = _parse_constraint_bundle_row(cur.fetchone(), active_version)
+        conn.close()
+        return bundle
+
+
+def activate_constraint_bundle(
+    tenant_id: str, version: int, actor_id: Optional[str] = None
+) -> Dict[str, Optional[int]]:
+    """Make `version` the active bundle. Raises KeyError for an unknown version."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT 1 FROM constraint_bundles WHERE tenant_id = ? AND version = ?",
+            (tenant_id, version),
+        )
+        if cur.fetchone() is None:
+            conn.close()
+            raise KeyError(version)
+        previous_version = _active_bundle_version(cur, tenant_id)
+        cur.execute(
+            """
+            INSERT INTO constraint_bundle_activations (tenant_id, version, activated_by, activated_at)
+            VALUES (?, ?, ?, ?)
+            """,
+            (tenant_id, version, actor_id, datetime.utcnow().isoformat()),
+        )
+        conn.commit()
+        conn.close()
+        return {"previous_version": previous_version, "active_version": version}
+
+
+def rollback_constraint_bundle(tenant_id: str, actor_id: Optional[str] = None) -> Dict[str, Optional[int]]:
+    """
+    Undo the latest activation and return to the bundle active before it
+    (None = shipped defaults). Raises LookupError when nothing is active.
+    """
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            SELECT activation_id, version FROM constraint_bundle_activations
+            WHERE tenant_id = ? AND rolled_back_at IS NULL
+            ORDER BY activation_id DESC LIMIT 1
+            """,
+            (tenant_id,),
+        )
+        current = cur.fetchone()
+        if current is None:
+            conn.close()
+            raise LookupError("No active constraint bundle to roll back")
+        cur.execute(
+            """
+            UPDATE constraint_bundle_activations SET rolled_back_by = ?, rolled_back_at = ?
+            WHERE activation_id = ?
+            """,
+            (actor_id, datetime.utcnow().isoformat(), current["activation_id"]),
+        )
+        active_version = _active_bundle_version(cur, tenant_id)
+        conn.commit()
+        conn.close()
+        return {"previous_version": current["version"], "active_version": active_version}
+
+
+def _parse_trade_policy_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "tenant_id": row["tenant_id"],
+        "policy_id": row["policy_id"],
+        "version": row["version"],
+        "policy": json.loads(row["policy_json"]),
+        "checksum": row["checksum"],
+        "note": row["note"],
+        "created_by": row["created_by"],
+        "created_at": row["created_at"],
+    }
+
+
+def _parse_trade_policy_binding_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "tenant_id": row["tenant_id"],
+        "account_id": row["account_id"] or None,
+        "strategy_id": row["strategy_id"] or None,
+        "policy_id": row["policy_id"],
+        "version": row["version"],
+        "bound_by": row["bound_by"],
+        "bound_at": row["bound_at"],
+    }
+
+
+def create_trade_policy(
+    tenant_id: str,
+    policy_id: str,
+    policy: Dict[str, Any],
+    created_by: Optional[str] = None,
+    note: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Store the next immutable version of `policy_id`. Does not bind it."""
+    policy_json = _stable_json(policy)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            SELECT COALESCE(MAX(version), 0) + 1 AS next_version FROM trade_policies
+            WHERE tenant_id = ? AND policy_id = ?
+            """,
+            (tenant_id, policy_id),
+        )
+        version = cur.fetchone()["next_version"]
+        cur.execute(
+            """
+            INSERT INTO trade_policies
+            (tenant_id, policy_id, version, policy_json, checksum, note, created_by, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                tenant_id,
+                policy_id,
+                version,
+                policy_json,
+                hashlib.sha256(policy_json.encode()).hexdigest(),
+                note,
+                created_by,
+                datetime.utcnow().isoformat(),
+            ),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM trade_policies WHERE tenant_id = ? AND policy_id = ? AND version = ?",
+            (tenant_id, policy_id, version),
+        )
+        stored = _parse_trade_policy_row(cur.fetchone())
+        conn.close()
+        return stored
+
+
+def get_trade_policy(tenant_id: str, policy_id: str, version: Optional[int] = None) -> Optional[Dict[str, Any]]:
+    """One version of a policy; the latest when `version` is None."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        if version is None:
+            cur.execute(
+                """
+                SELECT * FROM trade_policies WHERE tenant_id = ? AND policy_id = ?
+                ORDER BY version DESC LIMIT 1
+                """,
+                (tenant_id, policy_id),
+            )
+        else:
+            cur.execute(
+                "SELECT * FROM trade_policies WHERE tenant_id = ? AND policy_id = ? AND version = ?",
+                (tenant_id, policy_id, version),
+            )
+        row = cur.fetchone()
+        conn.close()
+        return _parse_trade_policy_row(row) if row else None
+
+
+def list_trade_policies(tenant_id: str, policy_id: Optional[str] = None) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        if policy_id is None:
+            cur.execute(
+                "SELECT * FROM trade_policies WHERE tenant_id = ? ORDER BY policy_id, version",
+                (tenant_id,),
+            )
+        else:
+            cur.execute(
+                "SELECT * FROM trade_policies WHERE tenant_id = ? AND policy_id = ? ORDER BY version",
+                (tenant_id, policy_id),
+            )
+        policies = [_parse_trade_policy_row(row) for row in cur.fetchall()]
+        conn.close()
+        return policies
+
+
+def bind_trade_policy(
+    tenant_id: str,
+    policy_id: str,
+    version: int,
+    account_id: Optional[str] = None,
+    strategy_id: Optional[str] = None,
+    bound_by: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    Bind a policy version to an (account, strategy) scope, replacing any previous binding.
+    None binds for every account / strategy. Raises KeyError for an unknown version.
+    """
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT 1 FROM trade_policies WHERE tenant_id = ? AND policy_id = ? AND version = ?",
+            (tenant_id, policy_id, version),
+        )
+        if cur.fetchone() is None:
+            conn.close()
+            raise KeyError(f"{policy_id} v{version}")
+        cur.execute(
+            """
+            INSERT INTO trade_policy_bindings
+            (tenant_id, account_id, strategy_id, policy_id, version, bound_by, bound_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(tenant_id, account_id, strategy_id) DO UPDATE SET
+                policy_id = excluded.policy_id,
+                version = excluded.version,
+                bound_by = excluded.bound_by,
+                bound_at = excluded.bound_at
+            """,
+            (
+                tenant_id,
+                account_id or "",
+                strategy_id or "",
+                policy_id,
+                version,
+                bound_by,
+                datetime.utcnow().isoformat(),
+            ),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM trade_policy_bindings WHERE tenant_id = ? AND account_id = ? AND strategy_id = ?",
+            (tenant_id, account_id or "", strategy_id or ""),
+        )
+        binding = _parse_trade_policy_binding_row(cur.fetchone())
+        conn.close()
+        return binding
+
+
+def unbind_trade_policy(tenant_id: str, account_id: Optional[str] = None, strategy_id: Optional[str] = None) -> bool:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "DELETE FROM trade_policy_bindings WHERE tenant_id = ? AND account_id = ? AND strategy_id = ?",
+            (tenant_id, account_id or "", strategy_id or ""),
+        )
+        removed = cur.rowcount > 0
+        conn.commit()
+        conn.close()
+        return removed
+
+
+def list_trade_policy_bindings(tenant_id: str) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM trade_policy_bindings WHERE tenant_id = ? ORDER BY account_id, strategy_id",
+            (tenant_id,),
+        )
+        bindings = [_parse_trade_policy_binding_row(row) for row in cur.fetchall()]
+        conn.close()
+        return bindings
+
+
+def resolve_trade_policy_binding(
+    tenant_id: str, account_id: Optional[str] = None, strategy_id: Optional[str] = None
+) -> Optional[Dict[str, Any]]:
+    """Most specific binding: (account, strategy), then account, then strategy, then the tenant default."""
+    scopes = [
+        (account_id or "", strategy_id or ""),
+        (account_id or "", ""),
+        ("", strategy_id or ""),
+        ("", ""),
+    ]
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        binding = None
+        for scope_account, scope_strategy in dict.fromkeys(scopes):
+            cur.execute(
+                "SELECT * FROM trade_policy_bindings WHERE tenant_id = ? AND account_id = ? AND strategy_id = ?",
+                (tenant_id, scope_account, scope_strategy),
+            )
+            row = cur.fetchone()
+            if row is not None:
+                binding = _parse_trade_policy_binding_row(row)
+                break
+        conn.close()
+        return binding
+
+
+def trade_policy_registry_in_use(tenant_id: str) -> bool:
+    """True once the tenant stores any policy; client-supplied policies are then never enforced as-is."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute("SELECT 1 FROM trade_policies WHERE tenant_id = ? LIMIT 1", (tenant_id,))
+        in_use = cur.fetchone() is not None
+        conn.close()
+        return in_use
+
+
+def _parse_trading_scope_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "tenant_id": row["tenant_id"],
+        "key_id": row["key_id"],
+        "account_id": row["account_id"] or None,
+        "strategy_id": row["strategy_id"] or None,
+        "assigned_by": row["assigned_by"],
+        "assigned_at": row["assigned_at"],
+    }
+
+
+def set_api_key_trading_scope(
+    tenant_id: str,
+    key_id: str,
+    account_id: Optional[str] = None,
+    strategy_id: Optional[str] = None,
+    assigned_by: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Bind an API key's pre-trade requests to an account and/or strategy, replacing any previous scope."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            INSERT OR REPLACE INTO api_key_trading_scopes
+            (tenant_id, key_id, account_id, strategy_id, assigned_by, assigned_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            """,
+            (tenant_id, key_id, account_id or "", strategy_id or "", assigned_by, datetime.utcnow().isoformat()),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM api_key_trading_scopes WHERE tenant_id = ? AND key_id = ?",
+            (tenant_id, key_id),
+        )
+        scope = _parse_trading_scope_row(cur.fetchone())
+        conn.close()
+        return scope
+
+
+def get_api_key_trading_scope(tenant_id: str, key_id: str) -> Optional[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM api_key_trading_scopes WHERE tenant_id = ? AND key_id = ?",
+            (tenant_id, key_id),
+        )
+        row = cur.fetchone()
+        conn.close()
+        return _parse_trading_scope_row(row) if row else None
+
+
+def clear_api_key_trading_scope(tenant_id: str, key_id: str) -> bool:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "DELETE FROM api_key_trading_scopes WHERE tenant_id = ? AND key_id = ?",
+            (tenant_id, key_id),
+        )
+        removed = cur.rowcount > 0
+        conn.commit()
+        conn.close()
+        return removed
+
+
+def _parse_instrument_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "symbol": row["symbol"],
+        "sector": row["sector"],
+        "asset_class": row["asset_class"],
+        "updated_by": row["updated_by"],
+        "updated_at": row["updated_at"],
+    }
+
+
+def upsert_instruments(
+    tenant_id: str,
+    instruments: List[Dict[str, Any]],
+    updated_by: Optional[str] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Insert or replace reference entries ({symbol, sector, asset_class}) in one transaction.
+    Symbols are stored upper-case; returns the stored entries.
+    """
+    updated_at = datetime.utcnow().isoformat()
+    symbols = [str(entry["symbol"]).strip().upper() for entry in instruments]
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.executemany(
+            """
+            INSERT INTO instrument_reference (tenant_id, symbol, sector, asset_class, updated_by, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(tenant_id, symbol) DO UPDATE SET
+                sector = excluded.sector,
+                asset_class = excluded.asset_class,
+                updated_by = excluded.updated_by,
+                updated_at = excluded.updated_at
+            """,
+            [
+                (tenant_id, symbol, entry.get("sector"), entry.get("asset_class"), updated_by, updated_at)
+                for symbol, entry in zip(symbols, instruments)
+            ],
+        )
+        conn.commit()
+        placeholders = ", ".join("?" for _ in symbols)
+        cur.execute(
+            f"SELECT * FROM instrument_reference WHERE tenant_id = ? AND symbol IN ({placeholders}) ORDER BY symbol",
+            (tenant_id, *symbols),
+        )
+        stored = [_parse_instrument_row(row) for row in cur.fetchall()]
+        conn.close()
+        return stored
+
+
+def list_instruments(tenant_id: str) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute("SELECT * FROM instrument_reference WHERE tenant_id = ? ORDER BY symbol", (tenant_id,))
+        instruments = [_parse_instrument_row(row) for row in cur.fetchall()]
+        conn.close()
+        return instruments
+
+
+def delete_instrument(tenant_id: str, symbol: str) -> bool:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "DELETE FROM instrument_reference WHERE tenant_id = ? AND symbol = ?",
+            (tenant_id, symbol.strip().upper()),
+        )
+        removed = cur.rowcount > 0
+        conn.commit()
+        conn.close()
+        return removed
+
+
+def get_instrument_map(tenant_id: str) -> Dict[str, Dict[str, Optional[str]]]:
+    """Symbol -> {sector, asset_class} for pre-trade exposure limits."""
+    return {
+        entry["symbol"]: {"sector": entry["sector"], "asset_class": entry["asset_class"]}
+        for entry in list_instruments(tenant_id)
+    }
+
+
+def insert_agent_metrics(
+    tenant_id: Optional[str],
+    agent_id: str,
+    request_id: str,
+    values: Dict[str, float],
+    recorded_at: Optional[datetime] = None,
+) -> None:
+    """Append one request's values to the agent's metric history."""
+    if not values:
+        return
+    timestamp = (recorded_at or datetime.utcnow()).isoformat()
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.executemany(
+            """
+            INSERT INTO agent_metric_history (tenant_id, agent_id, variable, value, request_id, recorded_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            """,
+            [(tenant_id, agent_id, variable, value, request_id, timestamp) for variable, value in values.items()],
+        )
+        conn.commit()
+        conn.close()
+
+
+def list_agent_metric_history(
+    tenant_id: Optional[str],
+    agent_id: str,
+    variable: str,
+    since: Optional[datetime] = None,
+    limit: Optional[int] = None,
+) -> List[Dict[str, Any]]:
+    """Oldest-first history for one variable; `limit` keeps the most recent entries."""
+    query = "SELECT value, request_id, recorded_at FROM agent_metric_history WHERE tenant_id IS ? AND agent_id = ? AND variable = ?"
+    params: List[Any] = [tenant_id, agent_id, variable]
+    if since is not None:
+        query += " AND recorded_at >= ?"
+        params.append(since.isoformat())
+    query += " ORDER BY history_id DESC"
+    if limit is not None:
+        query += " LIMIT ?"
+        params.append(limit)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(query, params)
+        rows = cur.fetchall()
+        conn.close()
+    return [
+        {"value": row["value"], "request_id": row["request_id"], "recorded_at": row["recorded_at"]}
+        for row in reversed(rows)
+    ]
+
+
+def _parse_shadow_candidate_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "tenant_id": row["tenant_id"],
+        "kind": row["kind"],
+        "candidate": json.loads(row["candidate_json"]),
+        "checksum": row["checksum"],
+        "attached_by": row["attached_by"],
+        "attached_at": row["attached_at"],
+    }
+
+
+def set_shadow_candidate(
+    tenant_id: str,
+    kind: str,
+    candidate: Dict[str, Any],
+    attached_by: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Attach (or replace) the tenant's shadow candidate of this kind."""
+    candidate_json = _stable_json(candidate)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            INSERT OR REPLACE INTO shadow_candidates
+            (tenant_id, kind, candidate_json, checksum, attached_by, attached_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            """,
+            (
+                tenant_id,
+                kind,
+                candidate_json,
+                hashlib.sha256(candidate_json.encode()).hexdigest(),
+                attached_by,
+                datetime.utcnow().isoformat(),
+            ),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM shadow_candidates WHERE tenant_id = ? AND kind = ?",
+            (tenant_id, kind),
+        )
+        stored = _parse_shadow_candidate_row(cur.fetchone())
+        conn.close()
+        return stored
+
+
+def get_shadow_candidate(tenant_id: Optional[str], kind: str) -> Optional[Dict[str, Any]]:
+    if tenant_id is None:
+        return None
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM shadow_candidates WHERE tenant_id = ? AND kind = ?",
+            (tenant_id, kind),
+        )
+        row = cur.fetchone()
+        conn.close()
+        return _parse_shadow_candidate_row(row) if row else None
+
+
+def list_shadow_candidates(tenant_id: str) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute("SELECT * FROM shadow_candidates WHERE tenant_id = ? ORDER BY kind", (tenant_id,))
+        candidates = [_parse_shadow_candidate_row(row) for row in cur.fetchall()]
+        conn.close()
+        return candidates
+
+
+def clear_shadow_candidate(tenant_id: str, kind: str) -> bool:
+    """Detach the candidate; divergences already recorded are kept. False if none was attached."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute("DELETE FROM shadow_candidates WHERE tenant_id = ? AND kind = ?", (tenant_id, kind))
+        removed = cur.rowcount > 0
+        conn.commit()
+        conn.close()
+        return removed
+
+
+def insert_shadow_divergence(
+    tenant_id: str,
+    kind: str,
+    reference_id: str,
+    agent_id: Optional[str],
+    live_action: str,
+    shadow_action: str,
+    candidate_checksum: str,
+    live: Dict[str, Any],
+    shadow: Dict[str, Any],
+) -> None:
+    """Record a request whose shadow verdict differed from the enforced one."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            INSERT INTO shadow_divergences
+            (tenant_id, kind, reference_id, agent_id, live_action, shadow_action, candidate_checksum,
+             live_json, shadow_json, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                tenant_id,
+                kind,
+                reference_id,
+                agent_id,
+                live_action,
+                shadow_action,
+                candidate_checksum,
+                _stable_json(live),
+                _stable_json(shadow),
+                datetime.utcnow().isoformat(),
+            ),
+        )
+        conn.commit()
+        conn.close()
+
+
+def _shadow_divergence_filter(
+    tenant_id: str,
+    kind: Optional[str],
+    since: Optional[datetime],
+    until: Optional[datetime],
+) -> Tuple[str, List[Any]]:
+    clause = "WHERE tenant_id = ?"
+    params: List[Any] = [tenant_id]
+    if kind is not None:
+        clause += " AND kind = ?"
+        params.append(kind)
+    if since is not None:
+        clause += " AND created_at >= ?"
+        params.append(since.isoformat())
+    if until is not None:
+        clause += " AND created_at < ?"
+        params.append(until.isoformat())
+    return clause, params
+
+
+def list_shadow_divergences(
+    tenant_id: str,
+    kind: Optional[str] = None,
+    since: Optional[datetime] = None,
+    until: Optional[datetime] = None,
+    limit: int = 100,
+) -> List[Dict[str, Any]]:
+    """Most recent divergences first."""
+    clause, params = _shadow_divergence_filter(tenant_id, kind, since, until)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            f"SELECT * FROM shadow_divergences {clause} ORDER BY divergence_id DESC LIMIT ?",
+            params + [limit],
+        )
+        rows = cur.fetchall()
+        conn.close()
+    return [
+        {
+            "divergence_id": row["divergence_id"],
+            "kind": row["kind"],
+            "reference_id": row["reference_id"],
+            "agent_id": row["agent_id"],
+            "live_action": row["live_action"],
+            "shadow_action": row["shadow_action"],
+            "candidate_checksum": row["candidate_checksum"],
+            "live": json.loads(row["live_json"]),
+            "shadow": json.loads(row["shadow_json"]),
+            "created_at": row["created_at"],
+        }
+        for row in rows
+    ]
+
+
+def count_shadow_divergences(
+    tenant_id: str,
+    kind: Optional[str] = None,
+    since: Optional[datetime] = None,
+    until: Optional[datetime] = None,
+) -> List[Dict[str, Any]]:
+    """Divergence counts grouped by (kind, live_action, shadow_action)."""
+    clause, params = _shadow_divergence_filter(tenant_id, kind, since, until)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            f"""
+            SELECT kind, live_action, shadow_action, COUNT(*) AS count
+            FROM shadow_divergences {clause}
+            GROUP BY kind, live_action, shadow_action
+            ORDER BY kind, live_action, shadow_action
+            """,
+            params,
+        )
+        rows = [dict(row) for row in cur.fetchall()]
+        conn.close()
+        return rows
+
+
+def _parse_feedback_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "feedback_id": row["feedback_id"],
+        "tenant_id": row["tenant_id"],
+        "reference_type": row["reference_type"],
+        "reference_id": row["reference_id"],
+        "agent_id": row["agent_id"],
+        "action": row["action"],
+        "outcome": row["outcome"],
+        "rule_ids": json.loads(row["rule_ids_json"]),
+        "divergence_score": row["divergence_score"],
+        "reviewer_id": row["reviewer_id"],
+        "note": row["note"],
+        "created_at": row["created_at"],
+        "updated_at": row["updated_at"],
+    }
+
+
+def upsert_validation_feedback(
+    tenant_id: str,
+    reference_type: str,
+    reference_id: str,
+    agent_id: Optional[str],
+    action: str,
+    outcome: str,
+    rule_ids: List[str],
+    divergence_score: Optional[float],
+    reviewer_id: Optional[str] = None,
+    note: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Store the labelled outcome for a result; a later label for the same result replaces it."""
+    now = datetime.utcnow().isoformat()
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            INSERT INTO validation_feedback
+            (tenant_id, reference_type, reference_id, agent_id, action, outcome, rule_ids_json,
+             divergence_score, reviewer_id, note, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (tenant_id, reference_type, reference_id) DO UPDATE SET
+                outcome = excluded.outcome,
+                reviewer_id = excluded.reviewer_id,
+                note = excluded.note,
+                updated_at = excluded.updated_at
+            """,
+            (
+                tenant_id,
+                reference_type,
+                reference_id,
+                agent_id,
+                action,
+                outcome,
+                json.dumps(sorted(set(rule_ids))),
+                divergence_score,
+                reviewer_id,
+                note,
+                now,
+                now,
+            ),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM validation_feedback WHERE tenant_id = ? AND reference_type = ? AND reference_id = ?",
+            (tenant_id, reference_type, reference_id),
+        )
+        stored = _parse_feedback_row(cur.fetchone())
+        conn.close()
+        return stored
+
+
+def list_validation_feedback(
+    tenant_id: str,
+    agent_id: Optional[str] = None,
+    since: Optional[datetime] = None,
+    until: Optional[datetime] = None,
+) -> List[Dict[str, Any]]:
+    """Labels for the tenant, filtered on when the labelled result was first reviewed."""
+    query = "SELECT * FROM validation_feedback WHERE tenant_id = ?"
+    params: List[Any] = [tenant_id]
+    if agent_id is not None:
+        query += " AND agent_id = ?"
+        params.append(agent_id)
+    if since is not None:
+        query += " AND created_at >= ?"
+        params.append(since.isoformat())
+    if until is not None:
+        query += " AND created_at < ?"
+        params.append(until.isoformat())
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(query + " ORDER BY feedback_id", params)
+        entries = [_parse_feedback_row(row) for row in cur.fetchall()]
+        conn.close()
+        return entries
+
+
+def get_drift_state(tenant_id: Optional[str], agent_id: Optional[str]) -> Optional[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT state_json FROM drift_states WHERE tenant_id = ? AND agent_id = ?",
+            (tenant_id or "", agent_id or ""),
+        )
+        row = cur.fetchone()
+        conn.close()
+        return json.loads(row["state_json"]) if row else None
+
+
+def save_drift_state(tenant_id: Optional[str], agent_id: Optional[str], state: Dict[str, Any]) -> None:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "INSERT OR REPLACE INTO drift_states (tenant_id, agent_id, state_json, updated_at) VALUES (?, ?, ?, ?)",
+            (tenant_id or "", agent_id or "", json.dumps(state), datetime.utcnow().isoformat()),
+        )
+        conn.commit()
+        conn.close()
+
+
+def list_drift_states(tenant_id: Optional[str]) -> List[Dict[str, Any]]:
+    """Every tracker in the tenant's scope (agent_id None = requests without an agent)."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT agent_id, state_json, updated_at FROM drift_states WHERE tenant_id = ? ORDER BY agent_id",
+            (tenant_id or "",),
+        )
+        states = [
+            {
+                "agent_id": row["agent_id"] or None,
+                "state": json.loads(row["state_json"]),
+                "updated_at": row["updated_at"],
+            }
+            for row in cur.fetchall()
+        ]
+        conn.close()
+        return states
+
+
+def list_confidence_calibration_samples(tenant_id: str) -> List[Dict[str, Any]]:
+    """Labelled /v3 results with the confidence stored at validation time, oldest label first."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            SELECT f.reference_id, f.outcome, r.confidence_json
+            FROM validation_feedback f
+            JOIN validation_results r ON r.request_id = f.reference_id AND r.tenant_id = f.tenant_id
+            WHERE f.tenant_id = ? AND f.reference_type = 'validation'
+            ORDER BY f.feedback_id
+            """,
+            (tenant_id,),
+        )
+        samples = [
+            {
+                "request_id": row["reference_id"],
+                "outcome": row["outcome"],
+                "confidence": json.loads(row["confidence_json"]),
+            }
+            for row in cur.fetchall()
+        ]
+        conn.close()
+        return samples
+
+
+def _parse_confidence_calibration_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "tenant_id": row["tenant_id"],
+        "version": row["version"],
+        "method": row["method"],
+        "model": json.loads(row["model_json"]),
+        "metrics": json.loads(row["metrics_json"]),
+        "sample_count": row["sample_count"],
+        "fitted_by": row["fitted_by"],
+        "created_at": row["created_at"],
+    }
+
+
+def insert_confidence_calibration(
+    tenant_id: str,
+    method: str,
+    model: Dict[str, Any],
+    metrics: Dict[str, Any],
+    sample_count: int,
+    fitted_by: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Persist a fitted calibration as the tenant's next version (1, 2, ...)."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT COALESCE(MAX(version), 0) + 1 AS next_version FROM confidence_calibrations WHERE tenant_id = ?",
+            (tenant_id,),
+        )
+        version = cur.fetchone()["next_version"]
+        cur.execute(
+            """
+            INSERT INTO confidence_calibrations
+            (tenant_id, version, method, model_json, metrics_json, sample_count, fitted_by, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                tenant_id,
+                version,
+                method,
+                json.dumps(model),
+                json.dumps(metrics),
+                sample_count,
+                fitted_by,
+                datetime.utcnow().isoformat(),
+            ),
+        )
+        conn.commit()
+        cur.execute(
+            "SELECT * FROM confidence_calibrations WHERE tenant_id = ? AND version = ?",
+            (tenant_id, version),
+        )
+        calibration = _parse_confidence_calibration_row(cur.fetchone())
+        conn.close()
+        return calibration
+
+
+def get_confidence_calibration(tenant_id: str, version: int) -> Optional[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM confidence_calibrations WHERE tenant_id = ? AND version = ?",
+            (tenant_id, version),
+        )
+        row = cur.fetchone()
+        conn.close()
+        return _parse_confidence_calibration_row(row) if row else None
+
+
+def list_confidence_calibrations(tenant_id: str) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM confidence_calibrations WHERE tenant_id = ? ORDER BY version",
+            (tenant_id,),
+        )
+        calibrations = [_parse_confidence_calibration_row(row) for row in cur.fetchall()]
+        conn.close()
+        return calibrations
+
+
+def insert_audit_event(
+    request_id: str,
+    tenant_id: Optional[str],
+    agent_id: Optional[str],
+    event_type: str,
+    violations: List[str],
+    regulatory_articles: List[str],
+    message: str,
+    violation_records: Optional[List[Dict[str, Any]]] = None,
+) -> int:
+    """Insert audit event with hash chaining for cryptographic immutability.
+    
+    Each event includes prev_hash (chain link) and event_hash (content hash).
+    Regulators can verify: hash(prev_hash + event_json) == event_hash
+    """
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        audit_id = _insert_audit_event_with_cursor(
+            cur=cur,
+            request_id=request_id,
+            tenant_id=tenant_id,
+            agent_id=agent_id,
+            event_type=event_type,
+            violations=violations,
+            regulatory_articles=regulatory_articles,
+            message=message,
+            violation_records=violation_records,
+        )
+        conn.commit()
+        conn.close()
+        return int(audit_id)
+
+
+def list_audit_events(tenant_id: str, agent_id: Optional[str] = None) -> List[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        if agent_id:
+            cur.execute(
+                """
+                SELECT * FROM audit_log WHERE tenant_id = ? AND agent_id = ? ORDER BY audit_id DESC
+                """,
+                (tenant_id, agent_id),
+            )
+        else:
+            cur.execute("SELECT * FROM audit_log WHERE tenant_id = ? ORDER BY audit_id DESC", (tenant_id,))
+        rows = cur.fetchall()
+        conn.close()
+        return [_parse_audit_event_row(row) for row in rows]
+
+
+def list_runtime_audit_events_by_type(
+    tenant_id: Optional[str],
+    event_types: List[str],
+    agent_id: Optional[str] = None,
+    since: Optional[datetime] = None,
+    after_id: Optional[int] = None,
+    limit: int = 100,
+) -> List[Dict[str, Any]]:
+    """Newest-first runtime audit events of the given types; tenant_id None is the public /v2 scope."""
+    if not event_types:
+        return []
+    clauses = ["tenant_id IS ?", f"event_type IN ({', '.join('?' for _ in event_types)})"]
+    params: List[Any] = [tenant_id, *event_types]
+    if agent_id is not None:
+        clauses.append("agent_id = ?")
+        params.append(agent_id)
+    if since is not None:
+        clauses.append("created_at >= ?")
+        params.append(since.isoformat())
+    if after_id is not None:
+        clauses.append("audit_id > ?")
+        params.append(after_id)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            f"SELECT * FROM audit_log WHERE {' AND '.join(clauses)} ORDER BY audit_id DESC LIMIT ?",
+            (*params, limit),
+        )
+        rows = cur.fetchall()
+        conn.close()
+        return [_parse_audit_event_row(row) for row in rows]
+
+
+
+def reserve_pre_trade_frequency_slot(
+    tenant_id: str,
+    agent_id: Optional[str],
+    idempotency_key: str,
+    bucket_start: Optional[str] = None,
+) -> int:
+    effective_agent_id = agent_id or "__tenant__"
+    current_bucket = bucket_start or _minute_bucket()
+    retention_cutoff = (datetime.now(timezone.utc) - timedelta(minutes=2)).isoformat()
+
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "DELETE FROM pre_trade_frequency_windows WHERE created_at < ?",
+            (retention_cutoff,),
+        )
+        cur.execute(
+            "SELECT bucket_start FROM pre_trade_frequency_windows WHERE tenant_id = ? AND idempotency_key = ?",
+            (tenant_id, idempotency_key),
+        )
+        existing_row = cur.fetchone()
+        bucket_to_count = current_bucket
         if existing_row is None:
             cur.execute(
                 """
@@ -610,17 +2151,91 @@ def reserve_pre_trade_frequency_slot(
         return reservation_count
 
 
-def verify_runtime_audit_chain(tenant_id: str) -> bool:
-    with _LOCK:
-        conn = _connect()
-        cur = conn.cursor()
-        cur.execute(
-            "SELECT * FROM audit_log WHERE tenant_id = ? ORDER BY audit_id ASC",
-            (tenant_id,),
-        )
-        rows = cur.fetchall()
-        conn.close()
+def verify_runtime_audit_chain(tenant_id: str) -> bool:
+    """Recompute the tenant's hash chain; the outcome is kept in audit_chain_verifications."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM audit_log WHERE tenant_id = ? ORDER BY audit_id ASC",
+            (tenant_id,),
+        )
+        rows = cur.fetchall()
+        conn.close()
+
+    intact = _runtime_chain_intact(rows)
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            INSERT INTO audit_chain_verifications (tenant_id, intact, event_count, verified_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(tenant_id) DO UPDATE SET
+                intact = excluded.intact,
+                event_count = excluded.event_count,
+                verified_at = excluded.verified_at
+            """,
+            (tenant_id, int(intact), len(rows), datetime.utcnow().isoformat()),
+        )
+        conn.commit()
+        conn.close()
+    return intact
+
+
+def list_audit_chain_verifications() -> List[Dict[str, Any]]:
+    """Every tenant with runtime audit events and its latest verification (None if never verified)."""
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            """
+            SELECT a.tenant_id, v.intact, v.event_count, v.verified_at
+            FROM (SELECT DISTINCT tenant_id FROM audit_log WHERE tenant_id IS NOT NULL) a
+            LEFT JOIN audit_chain_verifications v ON v.tenant_id = a.tenant_id
+            ORDER BY a.tenant_id
+            """
+        )
+        rows = cur.fetchall()
+        conn.close()
+        return [
+            {
+                "tenant_id": row["tenant_id"],
+                "intact": None if row["intact"] is None else bool(row["intact"]),
+                "event_count": row["event_count"],
+                "verified_at": row["verified_at"],
+            }
+            for row in rows
+        ]
+
+
+def probe_database_write(timeout_seconds: float = 1.0) -> None:
+    """Write one row inside a transaction; raises if the database is locked, read-only or full."""
+    if not _LOCK.acquire(timeout=timeout_seconds):
+        raise TimeoutError("storage lock busy")
+    try:
+        conn = _connect()
+        try:
+            conn.execute(f"PRAGMA busy_timeout = {int(timeout_seconds * 1000)}")
+            conn.execute(
+                """
+                INSERT INTO health_probes (probe, probed_at) VALUES ('readiness', ?)
+                ON CONFLICT(probe) DO UPDATE SET probed_at = excluded.probed_at
+                """,
+                (datetime.utcnow().isoformat(),),
+            )
+            conn.commit()
+        finally:
+            conn.close()
+    finally:
+        _LOCK.release()
+
+
+def database_path() -> str:
+    return _get_db_path()
 
+
+def _runtime_chain_intact(rows: List[sqlite3.Row]) -> bool:
     prev_hash = "0" * 64
     for row in rows:
         if row["prev_hash"] != prev_hash:
@@ -635,6 +2250,8 @@ def verify_runtime_audit_chain(tenant_id: str) -> bool:
             "regulatory_articles": json.loads(row["regulatory_articles_json"]),
             "message": row["message"],
         }
+        if row["violation_records_json"] is not None:
+            event_data["violation_records"] = json.loads(row["violation_records_json"])
         expected_hash = hashlib.sha256((prev_hash + _stable_json(event_data)).encode("utf-8")).hexdigest()
         if row["event_hash"] != expected_hash:
             return False
@@ -728,6 +2345,112 @@ def get_latest_valid_snapshot(agent_id: str, tenant_id: str) -> Optional[Dict[st
         return result
 
 
+def _insert_execution_decision_with_cursor(
+    cur: sqlite3.Cursor,
+    decision_id: str,
+    tenant_id: str,
+    agent_id: Optional[str],
+    decision: str,
+    reason: str,
+    triggered_controls: List[str],
+    order: Dict[str, Any],
+    account: Dict[str, Any],
+    policy: Dict[str, Any],
+    modified_order: Optional[Dict[str, Any]],
+    idempotency_key: Optional[str],
+    request_fingerprint: str,
+    created_at: str,
+    policy_id: Optional[str] = None,
+    policy_version: Optional[int] = None,
+    account_id: Optional[str] = None,
+    ledger: Optional[Dict[str, Any]] = None,
+    basket_id: Optional[str] = None,
+    leg_index: Optional[int] = None,
+) -> Dict[str, Any]:
+    cur.execute(
+        """
+        INSERT INTO execution_decisions
+        (decision_id, tenant_id, agent_id, decision, reason, triggered_controls_json,
+         order_json, account_json, policy_json, policy_id, policy_version, account_id, ledger_json,
+         basket_id, leg_index, modified_order_json, idempotency_key, request_fingerprint, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        """,
+        (
+            decision_id,
+            tenant_id,
+            agent_id,
+            decision,
+            reason,
+            json.dumps(triggered_controls),
+            json.dumps(order),
+            json.dumps(account),
+            json.dumps(policy),
+            policy_id,
+            policy_version,
+            account_id,
+            json.dumps(ledger) if ledger is not None else None,
+            basket_id,
+            leg_index,
+            json.dumps(modified_order) if modified_order else None,
+            idempotency_key,
+            request_fingerprint,
+            created_at,
+        ),
+    )
+    if decision in ("ALLOW", "MODIFY"):
+        approved = modified_order or order
+        cur.execute(
+            """
+            INSERT INTO ledger_orders
+            (decision_id, tenant_id, account_id, symbol, side, quantity, price, filled_quantity,
+             status, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 0, 'OPEN', ?, ?)
+            """,
+            (
+                decision_id,
+                tenant_id,
+                account_id or "",
+                str(approved["symbol"]).upper(),
+                approved["side"],
+                float(approved["quantity"]),
+                float(approved["price"]),
+                created_at,
+                created_at,
+            ),
+        )
+    _insert_audit_event_with_cursor(
+        cur=cur,
+        request_id=decision_id,
+        tenant_id=tenant_id,
+        agent_id=agent_id,
+        event_type=f"PRE_TRADE_{decision}",
+        violations=triggered_controls,
+        regulatory_articles=[],
+        message=f"Pre-trade decision {decision}: {reason}",
+    )
+    return {
+        "decision_id": decision_id,
+        "tenant_id": tenant_id,
+        "agent_id": agent_id,
+        "decision": decision,
+        "reason": reason,
+        "triggered_controls": triggered_controls,
+        "order": order,
+        "account": account,
+        "policy": policy,
+        "policy_id": policy_id,
+        "policy_version": policy_version,
+        "account_id": account_id,
+        "ledger": ledger,
+        "basket_id": basket_id,
+        "leg_index": leg_index,
+        "modified_order": modified_order,
+        "idempotency_key": idempotency_key,
+        "request_fingerprint": request_fingerprint,
+        "created_at": created_at,
+    }
+
+
 def insert_execution_decision(
     decision_id: str,
     tenant_id: str,
@@ -740,7 +2463,17 @@ def insert_execution_decision(
     policy: Dict[str, Any],
     modified_order: Optional[Dict[str, Any]],
     idempotency_key: Optional[str],
+    policy_id: Optional[str] = None,
+    policy_version: Optional[int] = None,
+    account_id: Optional[str] = None,
+    ledger: Optional[Dict[str, Any]] = None,
 ) -> Dict[str, Any]:
+    """
+    policy_id/policy_version: the registry policy that was enforced (None = client-supplied).
+    ledger: the ledger view the order was evaluated with; not part of the idempotency
+    fingerprint since it changes with every other order on the account. Fresh ALLOW/MODIFY
+    decisions open a ledger order holding the approved quantity as pending exposure.
+    """
     request_payload = {
         "tenant_id": tenant_id,
         "agent_id": agent_id,
@@ -770,59 +2503,193 @@ def insert_execution_decision(
                     raise ValueError("Idempotency key already used with a different pre-trade request")
                 return existing_decision
 
+        stored = _insert_execution_decision_with_cursor(
+            cur=cur,
+            decision_id=decision_id,
+            tenant_id=tenant_id,
+            agent_id=agent_id,
+            decision=decision,
+            reason=reason,
+            triggered_controls=triggered_controls,
+            order=order,
+            account=account,
+            policy=policy,
+            modified_order=modified_order,
+            idempotency_key=idempotency_key,
+            request_fingerprint=request_fingerprint,
+            created_at=datetime.utcnow().isoformat(),
+            policy_id=policy_id,
+            policy_version=policy_version,
+            account_id=account_id,
+            ledger=ledger,
+        )
+        conn.commit()
+        conn.close()
+        return stored
+
+
+def _parse_basket_decision_row(row: sqlite3.Row, legs: List[Dict[str, Any]]) -> Dict[str, Any]:
+    return {
+        "basket_id": row["basket_id"],
+        "tenant_id": row["tenant_id"],
+        "agent_id": row["agent_id"],
+        "mode": row["mode"],
+        "decision": row["decision"],
+        "reason": row["reason"],
+        "triggered_controls": json.loads(row["triggered_controls_json"]),
+        "scale": row["scale"],
+        "idempotency_key": row["idempotency_key"],
+        "request_fingerprint": row["request_fingerprint"],
+        "created_at": row["created_at"],
+        "legs": legs,
+    }
+
+
+def _basket_legs_with_cursor(cur: sqlite3.Cursor, tenant_id: str, basket_id: str) -> List[Dict[str, Any]]:
+    cur.execute(
+        "SELECT * FROM execution_decisions WHERE tenant_id = ? AND basket_id = ? ORDER BY leg_index",
+        (tenant_id, basket_id),
+    )
+    return [_parse_execution_decision_row(row) for row in cur.fetchall()]
+
+
+def insert_basket_decision(
+    basket_id: str,
+    tenant_id: str,
+    agent_id: Optional[str],
+    mode: str,
+    decision: str,
+    reason: str,
+    triggered_controls: List[str],
+    scale: float,
+    legs: List[Dict[str, Any]],
+    account: Dict[str, Any],
+    policy: Dict[str, Any],
+    idempotency_key: Optional[str],
+    policy_id: Optional[str] = None,
+    policy_version: Optional[int] = None,
+    account_id: Optional[str] = None,
+    ledger: Optional[Dict[str, Any]] = None,
+) -> Dict[str, Any]:
+    """
+    Store a basket decision and one execution_decisions row per leg in a single transaction,
+    so approved legs open their ledger orders together or not at all. Each leg dict carries
+    decision_id, decision, reason, triggered_controls, order and modified_order.
+    Idempotent per (tenant, idempotency_key) like insert_execution_decision.
+    """
+    request_payload = {
+        "tenant_id": tenant_id,
+        "agent_id": agent_id,
+        "mode": mode,
+        "decision": decision,
+        "reason": reason,
+        "triggered_controls": triggered_controls,
+        "legs": [
+            {key: leg[key] for key in ("decision", "reason", "triggered_controls", "order", "modified_order")}
+            for leg in legs
+        ],
+        "account": account,
+        "policy": policy,
+    }
+    request_fingerprint = _fingerprint_payload(request_payload)
+
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        if idempotency_key:
+            cur.execute(
+                "SELECT * FROM basket_decisions WHERE tenant_id = ? AND idempotency_key = ?",
+                (tenant_id, idempotency_key),
+            )
+            existing_row = cur.fetchone()
+            if existing_row is not None:
+                existing = _parse_basket_decision_row(
+                    existing_row, _basket_legs_with_cursor(cur, tenant_id, existing_row["basket_id"])
+                )
+                conn.close()
+                if existing["request_fingerprint"] != request_fingerprint:
+                    raise ValueError("Idempotency key already used with a different basket request")
+                return existing
+
         created_at = datetime.utcnow().isoformat()
         cur.execute(
             """
-            INSERT INTO execution_decisions
-            (decision_id, tenant_id, agent_id, decision, reason, triggered_controls_json,
-             order_json, account_json, policy_json, modified_order_json, idempotency_key, request_fingerprint, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO basket_decisions
+            (basket_id, tenant_id, agent_id, mode, decision, reason, triggered_controls_json, scale,
+             idempotency_key, request_fingerprint, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             """,
             (
-                decision_id,
+                basket_id,
                 tenant_id,
                 agent_id,
+                mode,
                 decision,
                 reason,
                 json.dumps(triggered_controls),
-                json.dumps(order),
-                json.dumps(account),
-                json.dumps(policy),
-                json.dumps(modified_order) if modified_order else None,
+                scale,
                 idempotency_key,
                 request_fingerprint,
                 created_at,
             ),
         )
-        _insert_audit_event_with_cursor(
-            cur=cur,
-            request_id=decision_id,
-            tenant_id=tenant_id,
-            agent_id=agent_id,
-            event_type=f"PRE_TRADE_{decision}",
-            violations=triggered_controls,
-            regulatory_articles=[],
-            message=f"Pre-trade decision {decision}: {reason}",
-        )
+        stored_legs = [
+            _insert_execution_decision_with_cursor(
+                cur=cur,
+                decision_id=leg["decision_id"],
+                tenant_id=tenant_id,
+                agent_id=agent_id,
+                decision=leg["decision"],
+                reason=leg["reason"],
+                triggered_controls=leg["triggered_controls"],
+                order=leg["order"],
+                account=account,
+                policy=policy,
+                modified_order=leg["modified_order"],
+                idempotency_key=None,
+                request_fingerprint=_fingerprint_payload(request_payload["legs"][index]),
+                created_at=created_at,
+                policy_id=policy_id,
+                policy_version=policy_version,
+                account_id=account_id,
+                ledger=ledger,
+                basket_id=basket_id,
+                leg_index=index,
+            )
+            for index, leg in enumerate(legs)
+        ]
         conn.commit()
         conn.close()
         return {
-            "decision_id": decision_id,
+            "basket_id": basket_id,
             "tenant_id": tenant_id,
             "agent_id": agent_id,
+            "mode": mode,
             "decision": decision,
             "reason": reason,
             "triggered_controls": triggered_controls,
-            "order": order,
-            "account": account,
-            "policy": policy,
-            "modified_order": modified_order,
+            "scale": scale,
             "idempotency_key": idempotency_key,
             "request_fingerprint": request_fingerprint,
             "created_at": created_at,
+            "legs": stored_legs,
         }
 
 
+def get_basket_decision(basket_id: str, tenant_id: str) -> Optional[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM basket_decisions WHERE basket_id = ? AND tenant_id = ?",
+            (basket_id, tenant_id),
+        )
+        row = cur.fetchone()
+        basket = _parse_basket_decision_row(row, _basket_legs_with_cursor(cur, tenant_id, basket_id)) if row else None
+        conn.close()
+        return basket
+
+
 def get_execution_decision(decision_id: str, tenant_id: str) -> Optional[Dict[str, Any]]:
     with _LOCK:
         conn = _connect()
@@ -838,6 +2705,427 @@ def get_execution_decision(decision_id: str, tenant_id: str) -> Optional[Dict[st
         return _parse_execution_decision_row(row)
 
 
+# Ledger orders in these states still hold their unfilled quantity as pending exposure;
+# UNKNOWN (e.g. broker ACK timeout) is held until a later report resolves it
+_LEDGER_WORKING_STATUSES = ("OPEN", "PARTIALLY_FILLED", "UNKNOWN")
+_LEDGER_FILL_REPORTS = ("FILL", "PARTIAL_FILL")
+_RECONCILIATION_BREACH_EVENT = "EXECUTION_RECONCILIATION_BREACH"
+_UNKNOWN_STATE_EVENT = "EXECUTION_STATE_UNKNOWN"
+
+
+def _parse_ledger_order_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "decision_id": row["decision_id"],
+        "tenant_id": row["tenant_id"],
+        "account_id": row["account_id"] or None,
+        "symbol": row["symbol"],
+        "side": row["side"],
+        "quantity": row["quantity"],
+        "price": row["price"],
+        "filled_quantity": row["filled_quantity"],
+        "status": row["status"],
+        "created_at": row["created_at"],
+        "updated_at": row["updated_at"],
+    }
+
+
+def get_ledger_order(tenant_id: str, decision_id: str) -> Optional[Dict[str, Any]]:
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM ledger_orders WHERE tenant_id = ? AND decision_id = ?",
+            (tenant_id, decision_id),
+        )
+        row = cur.fetchone()
+        conn.close()
+        return _parse_ledger_order_row(row) if row is not None else None
+
+
+def get_account_ledger(tenant_id: str, account_id: Optional[str] = None) -> Dict[str, Any]:
+    """
+    Server-side view of an account: filled positions (average cost), signed pending quantity
+    of open approved orders, today's (UTC) realized PnL and gross exposure at cost.
+    """
+    scope = account_id or ""
+    today = datetime.utcnow().date().isoformat()
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM ledger_positions WHERE tenant_id = ? AND account_id = ? AND quantity != 0 ORDER BY symbol",
+            (tenant_id, scope),
+        )
+        positions = {
+            row["symbol"]: {"quantity": row["quantity"], "avg_price": row["avg_price"]} for row in cur.fetchall()
+        }
+        cur.execute(
+            "SELECT * FROM ledger_orders WHERE tenant_id = ? AND account_id = ? "
+            f"AND status IN ({', '.join('?' * len(_LEDGER_WORKING_STATUSES))}) ORDER BY created_at",
+            (tenant_id, scope, *_LEDGER_WORKING_STATUSES),
+        )
+        pending: Dict[str, float] = {}
+        pending_notional = 0.0
+        for row in cur.fetchall():
+            remaining = max(row["quantity"] - row["filled_quantity"], 0.0)
+            signed = remaining if row["side"] == "BUY" else -remaining
+            pending[row["symbol"]] = pending.get(row["symbol"], 0.0) + signed
+            pending_notional += remaining * row["price"]
+        cur.execute(
+            "SELECT * FROM ledger_accounts WHERE tenant_id = ? AND account_id = ?",
+            (tenant_id, scope),
+        )
+        account_row = cur.fetchone()
+        conn.close()
+
+    realized = account_row["realized_pnl"] if account_row is not None and account_row["pnl_date"] == today else 0.0
+    return {
+        "tenant_id": tenant_id,
+        "account_id": account_id,
+        "positions": positions,
+        "pending_orders": {symbol: qty for symbol, qty in pending.items() if qty != 0},
+        "daily_pnl": realized,
+        "pnl_date": today,
+        "gross_exposure": sum(abs(p["quantity"]) * p["avg_price"] for p in positions.values()) + pending_notional,
+    }
+
+
+def _apply_fill_with_cursor(
+    cur: sqlite3.Cursor,
+    tenant_id: str,
+    account_id: str,
+    symbol: str,
+    side: str,
+    quantity: float,
+    price: float,
+    now: str,
+) -> float:
+    """Move a fill into the position at average cost; returns the PnL it realized."""
+    cur.execute(
+        "SELECT quantity, avg_price FROM ledger_positions WHERE tenant_id = ? AND account_id = ? AND symbol = ?",
+        (tenant_id, account_id, symbol),
+    )
+    row = cur.fetchone()
+    held, avg_price = (row["quantity"], row["avg_price"]) if row is not None else (0.0, 0.0)
+    delta = quantity if side == "BUY" else -quantity
+
+    realized = 0.0
+    if held == 0 or (held > 0) == (delta > 0):
+        avg_price = (abs(held) * avg_price + quantity * price) / (abs(held) + quantity)
+    else:
+        closed = min(abs(delta), abs(held))
+        realized = closed * (price - avg_price) * (1 if held > 0 else -1)
+        if abs(delta) > abs(held):
+            avg_price = price  # Position flipped; the remainder opens at the fill price
+    held = round(held + delta, 12)
+    if held == 0:
+        avg_price = 0.0
+
+    cur.execute(
+        """
+        INSERT INTO ledger_positions (tenant_id, account_id, symbol, quantity, avg_price, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(tenant_id, account_id, symbol) DO UPDATE SET
+            quantity = excluded.quantity,
+            avg_price = excluded.avg_price,
+            updated_at = excluded.updated_at
+        """,
+        (tenant_id, account_id, symbol, held, avg_price, now),
+    )
+
+    today = now[:10]
+    cur.execute(
+        "SELECT pnl_date, realized_pnl FROM ledger_accounts WHERE tenant_id = ? AND account_id = ?",
+        (tenant_id, account_id),
+    )
+    account_row = cur.fetchone()
+    carried = account_row["realized_pnl"] if account_row is not None and account_row["pnl_date"] == today else 0.0
+    cur.execute(
+        """
+        INSERT INTO ledger_accounts (tenant_id, account_id, pnl_date, realized_pnl, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(tenant_id, account_id) DO UPDATE SET
+            pnl_date = excluded.pnl_date,
+            realized_pnl = excluded.realized_pnl,
+            updated_at = excluded.updated_at
+        """,
+        (tenant_id, account_id, today, carried + realized, now),
+    )
+    return realized
+
+
+def _parse_execution_report_row(row: sqlite3.Row) -> Dict[str, Any]:
+    return {
+        "report_id": row["report_id"],
+        "decision_id": row["decision_id"],
+        "report_type": row["report_type"],
+        "quantity": row["quantity"],
+        "price": row["price"],
+        "account_id": row["account_id"] or None,
+        "symbol": row["symbol"],
+        "side": row["side"],
+        "order_status": row["order_status"],
+        "breaches": json.loads(row["breaches_json"]) if row["breaches_json"] else [],
+        "created_at": row["created_at"],
+    }
+
+
+def _reconcile_fill(
+    order: Dict[str, Any], quantity: float, price: float, symbol: Optional[str], side: Optional[str]
+) -> List[Dict[str, Any]]:
+    """Breaches of a fill against the approved (possibly modified) order it executes."""
+    breaches: List[Dict[str, Any]] = []
+    if (symbol is not None and symbol != order["symbol"]) or (side is not None and side != order["side"]):
+        breaches.append(
+            {
+                "code": "order_mismatch",
+                "detail": f"Executed {side or order['side']} {symbol or order['symbol']} "
+                f"but approved {order['side']} {order['symbol']}",
+            }
+        )
+    if order["status"] in ("CANCELLED", "REJECTED"):
+        breaches.append(
+            {"code": "fill_after_cancel", "detail": f"Fill of {quantity:g} arrived after the order was {order['status']}"}
+        )
+    filled = order["filled_quantity"] + quantity
+    if filled > order["quantity"] + 1e-9:
+        breaches.append(
+            {
+                "code": "overfill",
+                "detail": f"Filled {filled:g} of approved {order['quantity']:g} (excess {filled - order['quantity']:g})",
+            }
+        )
+    through = price > order["price"] if order["side"] == "BUY" else price < order["price"]
+    if through and abs(price - order["price"]) > 1e-9:
+        breaches.append(
+            {"code": "price_through_limit", "detail": f"Filled at {price:g} through approved price {order['price']:g}"}
+        )
+    return breaches
+
+
+def record_execution_report(
+    tenant_id: str,
+    report_id: str,
+    decision_id: Optional[str],
+    report_type: str,
+    quantity: Optional[float] = None,
+    price: Optional[float] = None,
+    account_id: Optional[str] = None,
+    symbol: Optional[str] = None,
+    side: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    Apply a broker execution report to the ledger and reconcile it against the decision.
+
+    FILL completes and PARTIAL_FILL adds to the approved order (both incremental quantity at
+    price); a FILL that leaves part of the approved quantity unfilled (including one that does
+    not match the order) is flagged as an underfill and keeps the order working; CANCEL and REJECT release the unfilled remainder; UNKNOWN keeps it pending until a
+    later report. Fills are checked for overfill, fill after cancel/reject, a different
+    symbol/side than approved and a price through the approved price. Fills with no decision,
+    or against a decision that approved nothing, are still applied to the ledger (from the
+    reported account/symbol/side) and flagged. Breaches are written to the runtime audit log
+    as EXECUTION_RECONCILIATION_BREACH in the same transaction, UNKNOWN reports as
+    EXECUTION_STATE_UNKNOWN.
+
+    Reports are idempotent per report_id; a replay returns the stored report with
+    duplicate=True. Raises KeyError for a non-fill report without an approved order.
+    """
+    now = datetime.utcnow().isoformat()
+    symbol = symbol.upper() if symbol else None
+    is_fill = report_type in _LEDGER_FILL_REPORTS
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            "SELECT * FROM execution_reports WHERE tenant_id = ? AND report_id = ?",
+            (tenant_id, report_id),
+        )
+        existing = cur.fetchone()
+        order_row = decision_row = None
+        if decision_id is not None:
+            cur.execute(
+                "SELECT * FROM ledger_orders WHERE tenant_id = ? AND decision_id = ?",
+                (tenant_id, decision_id),
+            )
+            order_row = cur.fetchone()
+            cur.execute(
+                "SELECT * FROM execution_decisions WHERE tenant_id = ? AND decision_id = ?",
+                (tenant_id, decision_id),
+            )
+            decision_row = cur.fetchone()
+        if existing is not None:
+            conn.close()
+            return {
+                **_parse_execution_report_row(existing),
+                "realized_pnl": None,
+                "order": _parse_ledger_order_row(order_row) if order_row is not None else None,
+                "duplicate": True,
+            }
+        if order_row is None and not is_fill:
+            conn.close()
+            raise KeyError(decision_id)
+
+        agent_id = decision_row["agent_id"] if decision_row is not None else None
+        breaches: List[Dict[str, Any]] = []
+        realized = None
+        order = None
+        if order_row is None:
+            if decision_row is None:
+                breaches.append({"code": "no_decision", "detail": "Execution reported without a pre-trade decision"})
+            else:
+                breaches.append(
+                    {
+                        "code": "unapproved_execution",
+                        "detail": f"Execution reported for a {decision_row['decision']} decision",
+                    }
+                )
+                decided = json.loads(decision_row["order_json"])
+                account_id = account_id or decision_row["account_id"]
+                symbol = symbol or (str(decided["symbol"]).upper() if decided.get("symbol") else None)
+                side = side or decided.get("side")
+            if symbol and side:
+                realized = _apply_fill_with_cursor(cur, tenant_id, account_id or "", symbol, side, quantity, price, now)
+            else:
+                breaches[-1]["detail"] += "; not applied to the ledger (symbol and side unknown)"
+            status = None
+        else:
+            order = _parse_ledger_order_row(order_row)
+            filled, status = order["filled_quantity"], order["status"]
+            working = status in _LEDGER_WORKING_STATUSES
+            if is_fill:
+                fill_breaches = _reconcile_fill(order, quantity, price, symbol, side)
+                breaches.extend(fill_breaches)
+                mismatched = any(b["code"] == "order_mismatch" for b in fill_breaches)
+                realized = _apply_fill_with_cursor(
+                    cur,
+                    tenant_id,
+                    order_row["account_id"],
+                    symbol or order["symbol"],
+                    side or order["side"],
+                    quantity,
+                    price,
+                    now,
+                )
+                if not mismatched:
+                    filled += quantity
+                if working:
+                    complete = filled >= order["quantity"] - 1e-12
+                    if report_type == "FILL" and not complete:
+                        # Keep the remainder pending: the broker may still be working it
+                        breaches.append(
+                            {
+                                "code": "underfill",
+                                "detail": f"FILL leaves {order['quantity'] - filled:g} of "
+                                f"{order['quantity']:g} approved unfilled",
+                            }
+                        )
+                    status = "FILLED" if complete else "PARTIALLY_FILLED"
+            elif working:
+                status = {"CANCEL": "CANCELLED", "REJECT": "REJECTED", "UNKNOWN": "UNKNOWN"}[report_type]
+            cur.execute(
+                """
+                UPDATE ledger_orders SET filled_quantity = ?, status = ?, updated_at = ?
+                WHERE tenant_id = ? AND decision_id = ?
+                """,
+                (filled, status, now, tenant_id, decision_id),
+            )
+            order = {**order, "filled_quantity": filled, "status": status, "updated_at": now}
+
+        cur.execute(
+            """
+            INSERT INTO execution_reports
+            (tenant_id, report_id, decision_id, report_type, quantity, price, account_id, symbol, side,
+             order_status, breaches_json, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            """,
+            (
+                tenant_id,
+                report_id,
+                decision_id,
+                report_type,
+                quantity,
+                price,
+                account_id,
+                symbol,
+                side,
+                status,
+                json.dumps(breaches),
+                now,
+            ),
+        )
+        subject = f"decision {decision_id}" if decision_id else "no decision"
+        if breaches:
+            _insert_audit_event_with_cursor(
+                cur=cur,
+                request_id=decision_id or report_id,
+                tenant_id=tenant_id,
+                agent_id=agent_id,
+                event_type=_RECONCILIATION_BREACH_EVENT,
+                violations=[b["code"] for b in breaches],
+                regulatory_articles=[],
+                message=f"Execution report {report_id} ({report_type}, {subject}): "
+                + "; ".join(b["detail"] for b in breaches)
+                + ".",
+            )
+        if report_type == "UNKNOWN":
+            _insert_audit_event_with_cursor(
+                cur=cur,
+                request_id=decision_id or report_id,
+                tenant_id=tenant_id,
+                agent_id=agent_id,
+                event_type=_UNKNOWN_STATE_EVENT,
+                violations=[],
+                regulatory_articles=[],
+                message=f"Execution state unknown for {subject} (report {report_id}); "
+                f"{order['quantity'] - order['filled_quantity']:g} held as pending exposure.",
+            )
+        conn.commit()
+        conn.close()
+
+    return {
+        "report_id": report_id,
+        "decision_id": decision_id,
+        "report_type": report_type,
+        "quantity": quantity,
+        "price": price,
+        "account_id": account_id,
+        "symbol": symbol,
+        "side": side,
+        "order_status": status,
+        "breaches": breaches,
+        "realized_pnl": realized,
+        "order": order,
+        "duplicate": False,
+        "created_at": now,
+    }
+
+
+def list_execution_reports(
+    tenant_id: str,
+    decision_id: Optional[str] = None,
+    breaches_only: bool = False,
+    limit: int = 100,
+) -> List[Dict[str, Any]]:
+    """Newest first."""
+    clauses = ["tenant_id = ?"]
+    params: List[Any] = [tenant_id]
+    if decision_id is not None:
+        clauses.append("decision_id = ?")
+        params.append(decision_id)
+    if breaches_only:
+        clauses.append("breaches_json IS NOT NULL AND breaches_json != '[]'")
+    with _LOCK:
+        conn = _connect()
+        cur = conn.cursor()
+        cur.execute(
+            f"SELECT * FROM execution_reports WHERE {' AND '.join(clauses)} ORDER BY created_at DESC, rowid DESC LIMIT ?",
+            (*params, limit),
+        )
+        reports = [_parse_execution_report_row(row) for row in cur.fetchall()]
+        conn.close()
+        return reports
+
+
 def insert_simulation_run(
     simulation_id: str,
     tenant_id: str,
@@ -0,0 +1,292 @@
+"""
+Structured Sample Divergence
+Field-by-field comparison of JSON tool-call samples (sample_format="json").
+
+Each sample is flattened to dotted field paths ("order.qty", "legs[0].symbol") and
+every field is compared with the rule that fits its values:
+- exact:    enums, symbols, booleans (majority agreement)
+- numeric:  numbers (integers exact, floats within NUMERIC_FIELD_TOLERANCE)
+- semantic: free text (semantic entropy via the selected divergence backend)
+
+The divergence score keys off the decision fields - the ones that drive execution.
+Callers may name them; otherwise every exact/numeric field is a decision field and
+free-text fields (rationales, notes) are informational.
+"""
+
+from __future__ import annotations
+
+import json
+from dataclasses import dataclass
+from statistics import median
+from typing import TYPE_CHECKING, Any, Dict, List, Optional, Tuple
+
+from app.core.entropy import SemanticEntropyResult, semantic_entropy
+from app.core.violations import ConstraintViolation
+
+if TYPE_CHECKING:
+    from app.core.divergence_backends import DivergenceBackend
+
+
+VIOLATION_PREFIX = "Structured field disagreement"
+CHECK_ID = "structured_fields"  # check id in the constraint definitions
+NUMERIC_FIELD_TOLERANCE = 0.01
+MAX_REPORTED_VALUES = 5
+
+_MISSING_LABEL = "<missing>"
+_ABSENT = object()
+
+
+@dataclass
+class FieldDivergence:
+    field: str
+    comparison: str          # exact | numeric | semantic
+    divergence: float        # 0.0 all samples agree, 1.0 no agreement
+    values: List[str]        # distinct values in first-seen order (capped)
+    present_in: int          # number of samples that set the field
+    decision_field: bool
+    spread: float            # relative spread (numeric) or 1.0 on any exact mismatch
+    tolerance: float
+
+    @property
+    def message(self) -> str:
+        return f"{VIOLATION_PREFIX}: {self.field} ({' vs '.join(self.values)})"
+
+    def to_violation(self) -> ConstraintViolation:
+        return ConstraintViolation(
+            rule_id=CHECK_ID,
+            message=self.message,
+            variable=self.field,
+            actual=self.spread,
+            limit=self.tolerance,
+        )
+
+
+@dataclass
+class StructuredDivergenceResult:
+    score: float
+    clusters: List[int]
+    cluster_count: int
+    backend: str
+    backend_version: str
+    fields: List[FieldDivergence]
+
+
+def parse_structured_sample(sample: str) -> Dict[str, Any]:
+    parsed = json.loads(sample)
+    if not isinstance(parsed, dict):
+        raise ValueError("structured samples must be JSON objects")
+    return parsed
+
+
+def _flatten(value: Any, prefix: str = "") -> Dict[str, Any]:
+    if isinstance(value, dict) and value:
+        flat: Dict[str, Any] = {}
+        for key, item in value.items():
+            flat.update(_flatten(item, f"{prefix}.{key}" if prefix else str(key)))
+        return flat
+    if isinstance(value, list) and value:
+        flat = {}
+        for index, item in enumerate(value):
+            flat.update(_flatten(item, f"{prefix}[{index}]"))
+        return flat
+    return {prefix: value}
+
+
+def _is_number(value: Any) -> bool:
+    return isinstance(value, (int, float)) and not isinstance(value, bool)
+
+
+def _canonical(value: Any) -> str:
+    return value if isinstance(value, str) else json.dumps(value, sort_keys=True)
+
+
+def _comparison_for(values: List[Any]) -> str:
+    if values and all(_is_number(v) for v in values):
+        return "numeric"
+    if values and all(isinstance(v, str) for v in values) and any(any(c.isspace() for c in v) for v in values):
+        return "semantic"
+    return "exact"
+
+
+def _is_decision_field(field: str, comparison: str, decision_fields: Optional[List[str]]) -> bool:
+    if decision_fields is None:
+        return comparison != "semantic"
+    return any(
+        field == name or field.startswith(f"{name}.") or field.startswith(f"{name}[")
+        for name in decision_fields
+    )
+
+
+def _numeric_tolerance(values: List[Any]) -> float:
+    return 0.0 if all(isinstance(v, int) for v in values) else NUMERIC_FIELD_TOLERANCE
+
+
+def _within(value: float, reference: float, tolerance: float) -> bool:
+    return abs(value - reference) <= tolerance * max(abs(reference), 1e-9)
+
+
+def _values_agree(left: Any, right: Any, comparison: str, tolerance: float) -> bool:
+    if left is _ABSENT or right is _ABSENT:
+        return left is right
+    if comparison == "numeric":
+        return _within(left, right, tolerance)
+    return _canonical(left) == _canonical(right)
+
+
+def _compare_field(
+    field: str,
+    per_sample: List[Any],
+    decision_fields: Optional[List[str]],
+    backend: "DivergenceBackend | None",
+) -> FieldDivergence:
+    total = len(per_sample)
+    present = [v for v in per_sample if v is not _ABSENT]
+    comparison = _comparison_for(present)
+    missing_share = 1.0 - len(present) / total
+
+    distinct = list(dict.fromkeys(_canonical(v) for v in present))
+    if missing_share > 0:
+        distinct.append(_MISSING_LABEL)
+
+    tolerance = 0.0
+    spread = 0.0
+    if comparison == "numeric":
+        tolerance = _numeric_tolerance(present)
+        center = median(present)
+        agreeing = sum(1 for v in present if _within(v, center, tolerance))
+        divergence = 1.0 - agreeing / total
+        magnitudes = [abs(v) for v in present if v != 0]
+        spread = (max(present) - min(present)) / (min(magnitudes) if magnitudes else 1.0)
+        if missing_share > 0:
+            spread = max(spread, 1.0)
+    elif comparison == "semantic" and backend is not None and len(present) >= 2:
+        divergence = max(semantic_entropy(present, backend=backend).score, missing_share)
+        spread = 1.0 if len(distinct) > 1 else 0.0
+    else:
+        counts: Dict[str, int] = {}
+        for value in present:
+            counts[_canonical(value)] = counts.get(_canonical(value), 0) + 1
+        divergence = 1.0 - (max(counts.values()) if counts else 0) / total
+        spread = 1.0 if len(distinct) > 1 else 0.0
+
+    return FieldDivergence(
+        field=field,
+        comparison=comparison,
+        divergence=float(min(max(divergence, 0.0), 1.0)),
+        values=distinct[:MAX_REPORTED_VALUES],
+        present_in=len(present),
+        decision_field=_is_decision_field(field, comparison, decision_fields),
+        spread=spread,
+        tolerance=tolerance,
+    )
+
+
+def _compare_all(
+    samples: List[str],
+    decision_fields: Optional[List[str]],
+    backend: "DivergenceBackend | None",
+) -> Tuple[List[Dict[str, Any]], List[FieldDivergence]]:
+    flattened = [_flatten(parse_structured_sample(sample)) for sample in samples]
+    fields = list(dict.fromkeys(field for flat in flattened for field in flat))
+    reports = [
+        _compare_field(field, [flat.get(field, _ABSENT) for flat in flattened], decision_fields, backend)
+        for field in fields
+    ]
+    return flattened, reports
+
+
+def _cluster(flattened: List[Dict[str, Any]], reports: List[FieldDivergence]) -> List[int]:
+    """Samples share a cluster when they agree on every exact/numeric decision field."""
+    keyed = [r for r in reports if r.decision_field and r.comparison != "semantic"]
+    clusters: List[int] = []
+    representatives: List[int] = []
+    for index, flat in enumerate(flattened):
+        assigned = None
+        for cluster_id, rep in enumerate(representatives):
+            if all(
+                _values_agree(flat.get(r.field, _ABSENT), flattened[rep].get(r.field, _ABSENT), r.comparison, r.tolerance)
+                for r in keyed
+            ):
+                assigned = cluster_id
+                break
+        if assigned is None:
+            assigned = len(representatives)
+            representatives.append(index)
+        clusters.append(assigned)
+    return clusters
+
+
+def compare_structured_samples(
+    samples: List[str],
+    decision_fields: Optional[List[str]] = None,
+    backend: "DivergenceBackend | None" = None,
+) -> List[FieldDivergence]:
+    """Per-field divergence report. Free-text fields are skipped when no backend is given."""
+    return _compare_all(samples, decision_fields, backend)[1]
+
+
+def structured_divergence(
+    samples: List[str],
+    decision_fields: Optional[List[str]],
+    backend: "DivergenceBackend",
+) -> StructuredDivergenceResult:
+    """Divergence score = worst decision field (or worst field when none are decision fields)."""
+    flattened, reports = _compare_all(samples, decision_fields, backend)
+    scored = [r for r in reports if r.decision_field] or reports
+    clusters = _cluster(flattened, reports)
+    return StructuredDivergenceResult(
+        score=max((r.divergence for r in scored), default=0.0),
+        clusters=clusters,
+        cluster_count=len(set(clusters)),
+        backend=backend.name,
+        backend_version=backend.version,
+        fields=reports,
+    )
+
+
+def find_structured_disagreements(
+    samples: List[str],
+    decision_fields: Optional[List[str]] = None,
+) -> List[FieldDivergence]:
+    """Exact/numeric decision fields on which the samples disagree (hard violations)."""
+    return [
+        report
+        for report in compare_structured_samples(samples, decision_fields)
+        if report.decision_field and report.comparison != "semantic" and report.spread > report.tolerance
+    ]
+
+
+def check_structured_consistency(
+    samples: Optional[List[str]], decision_fields: Optional[List[str]] = None
+) -> List[ConstraintViolation]:
+    """check_logic violation records for structured decision-field disagreements."""
+    if not samples or len(samples) < 2:
+        return []
+    return [report.to_violation() for report in find_structured_disagreements(samples, decision_fields)]
+
+
+def score_sample_divergence(
+    request: Any,
+    backend: "DivergenceBackend",
+) -> "StructuredDivergenceResult | SemanticEntropyResult":
+    """Entry point for validation pipelines: structured mode for JSON samples, semantic entropy otherwise."""
+    if getattr(request, "sample_format", "text") == "json":
+        return structured_divergence(request.samples, request.decision_fields, backend)
+    return semantic_entropy(request.samples, backend=backend)
+
+
+def field_divergence_details(result: Any) -> List[Dict[str, Any]]:
+    """Serializable per-field report (empty for free-text samples)."""
+    if not isinstance(result, StructuredDivergenceResult):
+        return []
+    return [
+        {
+            "field": report.field,
+            "comparison": report.comparison,
+            "divergence": report.divergence,
+            "values": report.values,
+            "present_in": report.present_in,
+            "decision_field": report.decision_field,
+        }
+        for report in result.fields
+    ]
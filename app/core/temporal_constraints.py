@@ -0,0 +1,86 @@
+"""
+Temporal Constraints
+Rate-of-change rules evaluated per (tenant, agent_id) against persisted metric history.
+
+check_logic is stateless; these rules catch moves that are individually within limits
+(e.g. leverage 1x -> 9.9x between two intents). Only values from requests that were not
+blocked are recorded, so a rejected jump never becomes the new baseline.
+"""
+
+from __future__ import annotations
+
+import threading
+from contextlib import contextmanager
+from datetime import datetime, timedelta
+from typing import Dict, Iterator, List, Optional, Tuple
+
+from app.core.constraint_rules import ConstraintDefinitions, TemporalRule
+from app.core.storage import insert_agent_metrics, list_agent_metric_history
+from app.core.violations import ConstraintViolation
+
+
+_AGENT_LOCKS: Dict[Tuple[str, str], threading.Lock] = {}
+_AGENT_LOCKS_GUARD = threading.Lock()
+
+
+@contextmanager
+def agent_lock(tenant_id: Optional[str], agent_id: str) -> Iterator[None]:
+    """
+    Serialize check-then-record per (tenant, agent) so two concurrent requests cannot both be
+    judged against the same baseline. Process-local, like ledger.account_lock.
+    """
+    with _AGENT_LOCKS_GUARD:
+        lock = _AGENT_LOCKS.setdefault((tenant_id or "", agent_id), threading.Lock())
+    with lock:
+        yield
+
+
+def temporal_values(inputs: Dict[str, float], definitions: ConstraintDefinitions) -> Dict[str, float]:
+    """Inputs covered by temporal rules, keyed by each rule's canonical variable name."""
+    values: Dict[str, float] = {}
+    for rule in definitions.temporal:
+        alias = next((name for name in rule.variables if name in inputs), None)
+        if alias is not None:
+            values.setdefault(rule.variable, inputs[alias])
+    return values
+
+
+def _history(rule: TemporalRule, tenant_id: Optional[str], agent_id: str, now: datetime) -> List[float]:
+    if rule.kind == "max_window_delta":
+        since = now - timedelta(seconds=rule.window_seconds or 0.0)
+        entries = list_agent_metric_history(tenant_id, agent_id, rule.variable, since=since)
+    else:
+        entries = list_agent_metric_history(tenant_id, agent_id, rule.variable, limit=1)
+    return [entry["value"] for entry in entries]
+
+
+def check_temporal_constraints(
+    tenant_id: Optional[str],
+    agent_id: str,
+    inputs: Dict[str, float],
+    definitions: ConstraintDefinitions,
+    now: Optional[datetime] = None,
+) -> List[ConstraintViolation]:
+    """Violations for every temporal rule whose variable was supplied in this request."""
+    now = now or datetime.utcnow()
+    values = temporal_values(inputs, definitions)
+    violations: List[ConstraintViolation] = []
+    for rule in definitions.temporal:
+        if rule.variable not in values:
+            continue
+        violation = rule.violation(values[rule.variable], _history(rule, tenant_id, agent_id, now))
+        if violation is not None:
+            violations.append(violation)
+    return violations
+
+
+def record_temporal_values(
+    tenant_id: Optional[str],
+    agent_id: str,
+    request_id: str,
+    inputs: Dict[str, float],
+    definitions: ConstraintDefinitions,
+    now: Optional[datetime] = None,
+) -> None:
+    """Persist this request's values as the agent's new baseline (call only when not blocked)."""
+    insert_agent_metrics(tenant_id, agent_id, request_id, temporal_values(inputs, definitions), recorded_at=now)
@@ -2,7 +2,7 @@ from __future__ import annotations
 
 from datetime import datetime, timezone
 from math import floor
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, List, Optional, Tuple
 
 from app.models import AccountSnapshot, TradeOrder, TradePolicyConfig
 
@@ -48,11 +48,174 @@ def _normalize_symbol_quantities(values: Dict[str, float]) -> Dict[str, float]:
     return {symbol.upper(): float(quantity) for symbol, quantity in values.items()}
 
 
+def _symbol_exposures(account: AccountSnapshot) -> Dict[str, float]:
+    exposures: Dict[str, float] = {}
+    for values in (account.open_positions, account.pending_orders, account.unsettled_positions):
+        for symbol, quantity in _normalize_symbol_quantities(values).items():
+            exposures[symbol] = exposures.get(symbol, 0.0) + quantity
+    return exposures
+
+
+def _current_gross_exposure(account: AccountSnapshot, price: float) -> float:
+    inferred_exposure = price * sum(
+        abs(value)
+        for values in (account.open_positions, account.pending_orders, account.unsettled_positions)
+        for value in _normalize_symbol_quantities(values).values()
+    )
+    return max(account.current_gross_exposure, inferred_exposure, account.unsettled_notional)
+
+
+def _class_key(value: Optional[str]) -> Optional[str]:
+    return value.strip().lower() if value and value.strip() else None
+
+
+def _classification(
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]], symbol: str, field: str
+) -> Optional[str]:
+    return _class_key((instruments or {}).get(symbol, {}).get(field))
+
+
+def _other_symbol_notionals(
+    account: AccountSnapshot, symbol: str, price: float, concentration: bool = False
+) -> Dict[str, float]:
+    """
+    Gross notional of every other symbol held or pending, at its mark (else the order price).
+    The concentration denominator prefers concentration_mark_prices over mark_prices.
+    """
+    marks = {name.upper(): float(mark) for name, mark in account.mark_prices.items()}
+    if concentration:
+        marks.update({name.upper(): float(mark) for name, mark in account.concentration_mark_prices.items()})
+    return {
+        name: abs(quantity) * marks.get(name, price)
+        for name, quantity in _symbol_exposures(account).items()
+        if name != symbol and quantity != 0
+    }
+
+
+def _exposure_limit_checks(
+    order: TradeOrder,
+    account: AccountSnapshot,
+    policy: TradePolicyConfig,
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]],
+    quantity: float,
+    price: float,
+) -> Tuple[float, List[Tuple[str, str, str]]]:
+    """
+    Per-symbol, sector, asset-class and single-name concentration limits. Each limit becomes a
+    cap on the order symbol's absolute position; an order that would take the position beyond
+    both the cap and its current size is scaled down to the cap (MODIFY), or blocked when
+    nothing fits. Returns the adjusted quantity and (decision, control, reason) findings.
+    """
+    symbol = order.symbol.upper()
+    current = _symbol_exposures(account).get(symbol, 0.0)
+    others = _other_symbol_notionals(account, symbol, price)
+    findings: List[Tuple[str, str, str]] = []
+
+    # (control, cap on |position|, reason when blocked, reason when scaled down)
+    caps: List[Tuple[str, float, str, str]] = []
+    symbol_limit = policy.symbol_position_limits.get(symbol)
+    if symbol_limit is not None:
+        caps.append((
+            "symbol_position_limit",
+            symbol_limit,
+            f"{symbol} position {current:.4f} leaves no room under symbol cap {symbol_limit:.4f}",
+            f"Quantity reduced to keep {symbol} within symbol cap {symbol_limit:.4f}",
+        ))
+
+    for control, field, label, limits in (
+        ("sector_allocation", "sector", "Sector", policy.sector_allocation_pct),
+        ("asset_class_allocation", "asset_class", "Asset class", policy.asset_class_allocation_pct),
+    ):
+        if not limits:
+            continue
+        group = _classification(instruments, symbol, field)
+        if group is None:
+            findings.append((
+                "BLOCK",
+                "unclassified_instrument",
+                f"Instrument {order.symbol} has no {field} in the instrument reference",
+            ))
+            continue
+        pct = {_class_key(name): value for name, value in limits.items()}.get(group)
+        if pct is None:
+            continue
+        allowed_notional = account.capital * pct
+        group_notional = sum(
+            notional for name, notional in others.items() if _classification(instruments, name, field) == group
+        )
+        caps.append((
+            control,
+            (allowed_notional - group_notional) / price,
+            f"{label} {group} exposure {group_notional + abs(current) * price:.2f} leaves no room under "
+            f"cap {allowed_notional:.2f}",
+            f"Quantity reduced to keep {label.lower()} {group} within {pct:.2%} of capital",
+        ))
+
+    for control, cap, blocked_reason, modified_reason in caps:
+        projected = abs(current + _position_delta(order.side, quantity))
+        if projected <= max(cap, abs(current)):
+            continue
+        allowed_quantity = _max_additional_quantity(current, order.side, max(cap, 0.0))
+        if allowed_quantity <= 0:
+            findings.append(("BLOCK", control, blocked_reason))
+        else:
+            quantity = min(quantity, allowed_quantity)
+            findings.append(("MODIFY", control, modified_reason))
+
+    pct = policy.max_single_name_pct
+    projected = abs(current + _position_delta(order.side, quantity))
+    other_gross = sum(_other_symbol_notionals(account, symbol, price, concentration=True).values())
+    if (
+        pct is not None
+        and pct < 1
+        and projected > abs(current)
+        and other_gross + projected * price >= policy.concentration_min_gross_notional
+        and projected * price > pct * (other_gross + projected * price)
+    ):
+        # name <= pct * (others + name)  <=>  name <= pct * others / (1 - pct)
+        cap = pct * other_gross / (1 - pct) / price
+        allowed_quantity = _max_additional_quantity(current, order.side, cap)
+        if allowed_quantity <= 0:
+            findings.append((
+                "BLOCK",
+                "single_name_concentration",
+                f"{symbol} already holds {abs(current) * price:.2f} of {other_gross + abs(current) * price:.2f} "
+                f"gross, at or above the {pct:.2%} single-name cap",
+            ))
+        else:
+            quantity = min(quantity, allowed_quantity)
+            findings.append((
+                "MODIFY",
+                "single_name_concentration",
+                f"Quantity reduced to keep {symbol} within {pct:.2%} of gross exposure",
+            ))
+
+    return quantity, findings
+
+
+def _order_payload(order: TradeOrder, quantity: float, price: float) -> Dict[str, Any]:
+    return {
+        "order_id": order.order_id,
+        "symbol": order.symbol,
+        "side": order.side,
+        "quantity": round(quantity, 6),
+        "price": price,
+        "market_price": order.market_price,
+        "market_price_timestamp": order.market_price_timestamp.isoformat() if order.market_price_timestamp else None,
+        "leverage_ratio": order.leverage_ratio,
+    }
+
+
 def evaluate_pre_trade(
     order: TradeOrder,
     account: AccountSnapshot,
     policy: TradePolicyConfig,
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]] = None,
 ) -> Dict[str, Any]:
+    """
+    `instruments` is the tenant instrument reference (symbol -> sector / asset_class); it is
+    only needed when the policy sets sector or asset-class allocation limits.
+    """
     decision = "ALLOW"
     reasons: List[str] = []
     controls: List[str] = []
@@ -104,15 +267,7 @@ def evaluate_pre_trade(
             f"Orders/min {account.orders_last_minute} reached cap {policy.max_orders_per_minute}"
         )
 
-    symbol = order.symbol.upper()
-    open_positions = _normalize_symbol_quantities(account.open_positions)
-    pending_orders = _normalize_symbol_quantities(account.pending_orders)
-    unsettled_positions = _normalize_symbol_quantities(account.unsettled_positions)
-    current_symbol_exposure = (
-        open_positions.get(symbol, 0.0)
-        + pending_orders.get(symbol, 0.0)
-        + unsettled_positions.get(symbol, 0.0)
-    )
+    current_symbol_exposure = _symbol_exposures(account).get(order.symbol.upper(), 0.0)
 
     projected_position = current_symbol_exposure + _position_delta(order.side, adjusted_quantity)
     if abs(projected_position) > policy.max_position_size:
@@ -131,12 +286,7 @@ def evaluate_pre_trade(
                 f"Quantity reduced to keep projected position within cap {policy.max_position_size:.4f}"
             )
 
-    inferred_exposure = effective_price * (
-        sum(abs(value) for value in open_positions.values())
-        + sum(abs(value) for value in pending_orders.values())
-        + sum(abs(value) for value in unsettled_positions.values())
-    )
-    current_gross_exposure = max(account.current_gross_exposure, inferred_exposure, account.unsettled_notional)
+    current_gross_exposure = _current_gross_exposure(account, effective_price)
     allowed_notional = account.capital * policy.max_capital_allocation_pct
     proposed_notional = current_gross_exposure + (adjusted_quantity * effective_price)
     if proposed_notional > allowed_notional:
@@ -155,6 +305,14 @@ def evaluate_pre_trade(
                 f"Capital allocation reduced to {policy.max_capital_allocation_pct:.2%}"
             )
 
+    adjusted_quantity, exposure_findings = _exposure_limit_checks(
+        order, account, policy, instruments, adjusted_quantity, effective_price
+    )
+    for level, control, reason in exposure_findings:
+        decision = _max_decision(decision, level)
+        controls.append(control)
+        reasons.append(reason)
+
     baseline = max(account.avg_order_size, 1.0)
     if account.order_size_stddev > 0:
         z_score = abs(order.quantity - account.avg_order_size) / account.order_size_stddev
@@ -179,16 +337,7 @@ def evaluate_pre_trade(
             controls.append("invalid_modified_order")
             reasons.append("Modified order would violate tick size or minimum lot rules")
         else:
-            modified_order = {
-                "order_id": order.order_id,
-                "symbol": order.symbol,
-                "side": order.side,
-                "quantity": round(adjusted_quantity, 6),
-                "price": adjusted_price,
-                "market_price": order.market_price,
-                "market_price_timestamp": order.market_price_timestamp.isoformat() if order.market_price_timestamp else None,
-                "leverage_ratio": order.leverage_ratio,
-            }
+            modified_order = _order_payload(order, adjusted_quantity, adjusted_price)
 
     if not reasons:
         reasons.append("All policy checks passed")
@@ -199,3 +348,163 @@ def evaluate_pre_trade(
         "triggered_controls": sorted(set(controls)),
         "modified_order": modified_order,
     }
+
+
+BASKET_ALL_OR_NONE = "all_or_none"
+BASKET_SCALE_DOWN = "scale_down"
+BASKET_MODES = (BASKET_ALL_OR_NONE, BASKET_SCALE_DOWN)
+
+
+def evaluate_basket(
+    legs: List[TradeOrder],
+    account: AccountSnapshot,
+    policy: TradePolicyConfig,
+    mode: str = BASKET_ALL_OR_NONE,
+    instruments: Optional[Dict[str, Dict[str, Optional[str]]]] = None,
+) -> Dict[str, Any]:
+    """
+    Evaluate a multi-leg order as one unit.
+
+    Each leg gets the single-order checks (leg i counts as the i-th extra order this minute),
+    then the legs together are checked against per-symbol position caps (legs on the same
+    symbol net out), sector / asset-class allocation caps and the capital allocation cap,
+    sharing one projected exposure. Single-name concentration is only checked per leg. The
+    outcome is atomic: a blocked leg blocks the basket. Legs that would need a smaller
+    quantity block the basket under all_or_none; under scale_down every leg is scaled by the
+    same factor (keeping hedge ratios) and the basket is MODIFY.
+    """
+    if mode not in BASKET_MODES:
+        raise ValueError(f"mode must be one of {list(BASKET_MODES)}")
+
+    leg_results = [
+        evaluate_pre_trade(
+            leg,
+            account.model_copy(update={"orders_last_minute": account.orders_last_minute + index}),
+            policy,
+            instruments,
+        )
+        for index, leg in enumerate(legs)
+    ]
+
+    reasons: List[str] = []
+    controls: List[str] = []
+    scale = 1.0
+
+    symbol_deltas: Dict[str, float] = {}
+    for leg in legs:
+        symbol = leg.symbol.upper()
+        symbol_deltas[symbol] = symbol_deltas.get(symbol, 0.0) + _position_delta(leg.side, leg.quantity)
+    exposures = _symbol_exposures(account)
+    for symbol, delta in symbol_deltas.items():
+        current = exposures.get(symbol, 0.0)
+        cap = min(policy.max_position_size, policy.symbol_position_limits.get(symbol, policy.max_position_size))
+        # A net move toward flat is allowed even while the position is still beyond the cap
+        if delta == 0 or abs(current + delta) <= max(cap, abs(current)):
+            continue
+        allowed = _max_additional_quantity(current, "BUY" if delta > 0 else "SELL", cap)
+        scale = min(scale, allowed / abs(delta))
+        controls.append(
+            "basket_position_size" if cap == policy.max_position_size else "basket_symbol_position_limit"
+        )
+        reasons.append(f"Basket moves {symbol} position to {current + delta:.4f}, beyond cap {cap:.4f}")
+
+    prices = [leg.market_price or leg.price for leg in legs]
+    marks = {
+        **{symbol.upper(): float(mark) for symbol, mark in account.mark_prices.items()},
+        **{leg.symbol.upper(): price for leg, price in zip(legs, prices)},
+    }
+    for control, field, label, limits in (
+        ("basket_sector_allocation", "sector", "sector", policy.sector_allocation_pct),
+        ("basket_asset_class_allocation", "asset_class", "asset class", policy.asset_class_allocation_pct),
+    ):
+        pcts = {_class_key(name): pct for name, pct in limits.items()}
+        increases: Dict[str, float] = {}
+        for symbol, delta in symbol_deltas.items():
+            group = _classification(instruments, symbol, field)
+            if group in pcts:
+                current = exposures.get(symbol, 0.0)
+                increases[group] = increases.get(group, 0.0) + (abs(current + delta) - abs(current)) * marks[symbol]
+        for group, increase in increases.items():
+            if increase <= 0:
+                continue
+            held = sum(
+                abs(quantity) * marks.get(symbol, max(prices))
+                for symbol, quantity in exposures.items()
+                if _classification(instruments, symbol, field) == group
+            )
+            allowed_notional = account.capital * pcts[group]
+            if held + increase <= allowed_notional:
+                continue
+            # Exposure grows at most linearly in the scale, so scaling by headroom / increase fits
+            scale = min(scale, max(allowed_notional - held, 0.0) / increase)
+            controls.append(control)
+            reasons.append(
+                f"Basket adds {increase:.2f} to {label} {group} exposure {held:.2f}, beyond cap {allowed_notional:.2f}"
+            )
+
+    current_gross_exposure = max(_current_gross_exposure(account, price) for price in prices)
+    basket_notional = sum(leg.quantity * price for leg, price in zip(legs, prices))
+    allowed_notional = account.capital * policy.max_capital_allocation_pct
+    if current_gross_exposure + basket_notional > allowed_notional:
+        available_notional = max(allowed_notional - current_gross_exposure, 0.0)
+        scale = min(scale, available_notional / basket_notional)
+        controls.append("basket_capital_allocation")
+        reasons.append(
+            f"Basket notional {basket_notional:.2f} on exposure {current_gross_exposure:.2f} "
+            f"exceeds allocation cap {allowed_notional:.2f}"
+        )
+
+    for leg, result in zip(legs, leg_results):
+        if result["decision"] == "MODIFY":
+            scale = min(scale, result["modified_order"]["quantity"] / leg.quantity)
+
+    leg_decisions = {result["decision"] for result in leg_results}
+    if "BLOCK" in leg_decisions:
+        decision = "BLOCK"
+        reasons.append("Basket blocked because a leg is blocked")
+    elif scale <= 0:
+        decision = "BLOCK"
+        reasons.append("No capacity left for any part of the basket")
+    elif scale < 1 and mode == BASKET_ALL_OR_NONE:
+        decision = "BLOCK"
+        controls.append("basket_all_or_none")
+        reasons.append("All-or-none basket cannot be executed in full")
+    elif scale < 1:
+        decision = "MODIFY"
+        reasons.append(f"All legs scaled to {scale:.2%}")
+    elif "REVIEW" in leg_decisions:
+        # Statistical anomaly checks must not override deterministic hard controls.
+        decision = "REVIEW"
+    else:
+        decision = "ALLOW"
+
+    modified_orders: List[Optional[Dict[str, Any]]] = [None] * len(legs)
+    if decision == "MODIFY":
+        for index, leg in enumerate(legs):
+            quantity = _round_down(leg.quantity * scale, policy.min_lot_size)
+            price = _round_down(leg.price, policy.tick_size)
+            if quantity < policy.min_lot_size or price <= 0:
+                decision = "BLOCK"
+                controls.append("invalid_modified_order")
+                reasons.append("Scaled basket would violate tick size or minimum lot rules")
+                modified_orders = [None] * len(legs)
+                break
+            modified_orders[index] = _order_payload(leg, quantity, price)
+
+    basket_reason = "; ".join(reasons) if reasons else "All basket checks passed"
+    basket_controls = sorted(set(controls))
+    return {
+        "decision": decision,
+        "reason": basket_reason,
+        "triggered_controls": basket_controls,
+        "scale": {"MODIFY": scale, "BLOCK": 0.0}.get(decision, 1.0),
+        "legs": [
+            {
+                "decision": decision,
+                "reason": result["reason"] if result["decision"] == decision else f"{result['reason']}; basket: {basket_reason}",
+                "triggered_controls": sorted(set(result["triggered_controls"]) | set(basket_controls)),
+                "modified_order": modified,
+            }
+            for result, modified in zip(leg_results, modified_orders)
+        ],
+    }
@@ -0,0 +1,174 @@
+"""
+Server-side trade policy resolution for /v4/execution/pre-trade
+
+Tenants store trade policies as immutable versions and bind one version per
+(account, strategy) scope; the most specific binding applies. The scope is the one assigned
+to the caller's API key (trading_scope), not whatever the request names. A pre-trade
+request gets:
+
+1. the policy it references by `policy_id` (latest version unless `policy_version`), which
+   must be at least as strict as the binding for its scope; a reference is refused when no
+   binding (not even a tenant default) applies, since there is nothing to check it against, else
+2. the bound policy for its account / strategy.
+
+An inline `policy` sent with the request can only tighten the resolved policy; any looser
+field is refused. Tenants that have not stored a policy yet can opt in to the legacy
+behaviour of enforcing the client-supplied policy as-is (CLIENT_POLICY_SETTING).
+"""
+
+from dataclasses import dataclass
+from typing import List, Optional, Tuple
+
+from app.core.storage import (
+    get_api_key_trading_scope,
+    get_tenant_validation_settings,
+    get_trade_policy,
+    resolve_trade_policy_binding,
+    trade_policy_registry_in_use,
+)
+from app.models import TradePolicyConfig
+
+# Tenant validation setting: enforce an inline policy as-is while no policy is stored
+CLIENT_POLICY_SETTING = "client_trade_policy"
+
+# Limits where a lower value is the stricter one
+STRICTER_WHEN_LOWER = (
+    "max_daily_loss",
+    "max_position_size",
+    "max_leverage_ratio",
+    "max_orders_per_minute",
+    "abnormal_order_zscore",
+    "max_capital_allocation_pct",
+    "max_market_price_age_seconds",
+)
+# Order rounding rules have no stricter direction; overrides must keep them
+MUST_MATCH = ("min_lot_size", "tick_size")
+# Per-key limits; dropping a key the bound policy limits, or raising it, is looser
+KEYED_LIMITS = ("symbol_position_limits", "sector_allocation_pct", "asset_class_allocation_pct")
+
+
+class TradePolicyError(ValueError):
+    """No enforceable policy could be resolved for the request."""
+
+
+class UnknownTradePolicyError(TradePolicyError):
+    pass
+
+
+class TradingScopeError(TradePolicyError):
+    """The request names an account or strategy outside its API key's trading scope."""
+
+
+class LooserPolicyError(TradePolicyError):
+    def __init__(self, message: str, fields: List[str]):
+        super().__init__(message)
+        self.fields = fields
+
+
+@dataclass
+class ResolvedTradePolicy:
+    policy: TradePolicyConfig
+    policy_id: Optional[str]  # None = client-supplied (tenant has no stored policies)
+    version: Optional[int]
+    source: str  # "reference", "binding" or "client"
+    override_applied: bool = False
+
+
+def looser_fields(candidate: TradePolicyConfig, bound: TradePolicyConfig) -> List[str]:
+    """Fields where `candidate` allows something `bound` does not."""
+    fields = [name for name in STRICTER_WHEN_LOWER if getattr(candidate, name) > getattr(bound, name)]
+    fields.extend(name for name in MUST_MATCH if getattr(candidate, name) != getattr(bound, name))
+    if bound.kill_switch_enabled and not candidate.kill_switch_enabled:
+        fields.append("kill_switch_enabled")
+    candidate_restricted = {symbol.strip().upper() for symbol in candidate.restricted_instruments}
+    if any(symbol.strip().upper() not in candidate_restricted for symbol in bound.restricted_instruments):
+        fields.append("restricted_instruments")
+    for name in KEYED_LIMITS:
+        candidate_limits = {key.lower(): limit for key, limit in getattr(candidate, name).items()}
+        if any(
+            candidate_limits.get(key.lower(), float("inf")) > limit for key, limit in getattr(bound, name).items()
+        ):
+            fields.append(name)
+    if bound.max_single_name_pct is not None:
+        if candidate.max_single_name_pct is None or candidate.max_single_name_pct > bound.max_single_name_pct:
+            fields.append("max_single_name_pct")
+        if candidate.concentration_min_gross_notional > bound.concentration_min_gross_notional:
+            fields.append("concentration_min_gross_notional")
+    return fields
+
+
+def _stored_policy(tenant_id: str, policy_id: str, version: Optional[int]) -> tuple:
+    stored = get_trade_policy(tenant_id, policy_id, version)
+    if stored is None:
+        label = f"{policy_id} v{version}" if version is not None else policy_id
+        raise UnknownTradePolicyError(f"Unknown trade policy {label}")
+    return TradePolicyConfig(**stored["policy"]), stored["version"]
+
+
+def trading_scope(
+    tenant_id: str,
+    key_id: Optional[str],
+    account_id: Optional[str] = None,
+    strategy_id: Optional[str] = None,
+) -> Tuple[Optional[str], Optional[str]]:
+    """
+    The (account, strategy) assigned to the caller's API key. A request may repeat them but not
+    name another; keys without a scope (and session tokens) get the tenant default binding.
+    """
+    scope = get_api_key_trading_scope(tenant_id, key_id) if key_id else None
+    assigned = (scope["account_id"], scope["strategy_id"]) if scope else (None, None)
+    for name, requested, allowed in zip(("account_id", "strategy_id"), (account_id, strategy_id), assigned):
+        if requested is not None and requested != allowed:
+            raise TradingScopeError(f"{name} {requested} is outside this API key's trading scope")
+    return assigned
+
+
+def resolve_trade_policy(
+    tenant_id: str,
+    policy_id: Optional[str] = None,
+    policy_version: Optional[int] = None,
+    account_id: Optional[str] = None,
+    strategy_id: Optional[str] = None,
+    override: Optional[TradePolicyConfig] = None,
+) -> ResolvedTradePolicy:
+    binding = resolve_trade_policy_binding(tenant_id, account_id, strategy_id)
+    if policy_id is not None:
+        policy, version = _stored_policy(tenant_id, policy_id, policy_version)
+        if binding is None:
+            raise TradePolicyError(
+                f"Trade policy {policy_id} v{version} cannot be referenced: no binding applies to this "
+                "account/strategy; bind a tenant default policy to check references against"
+            )
+        if (binding["policy_id"], binding["version"]) != (policy_id, version):
+            bound, _ = _stored_policy(tenant_id, binding["policy_id"], binding["version"])
+            fields = looser_fields(policy, bound)
+            if fields:
+                raise LooserPolicyError(
+                    f"Trade policy {policy_id} v{version} is looser than the bound policy "
+                    f"{binding['policy_id']} v{binding['version']} on: {', '.join(fields)}",
+                    fields,
+                )
+        resolved = ResolvedTradePolicy(policy, policy_id, version, "reference")
+    elif binding is not None:
+        policy, version = _stored_policy(tenant_id, binding["policy_id"], binding["version"])
+        resolved = ResolvedTradePolicy(policy, binding["policy_id"], version, "binding")
+    elif trade_policy_registry_in_use(tenant_id):
+        raise TradePolicyError("No trade policy is bound for this account/strategy; reference one by policy_id")
+    elif override is not None and get_tenant_validation_settings(tenant_id).get(CLIENT_POLICY_SETTING):
+        return ResolvedTradePolicy(override, None, None, "client")
+    else:
+        raise TradePolicyError(
+            "No trade policy stored for this tenant; create one (client-supplied policies are "
+            "only enforced for tenants that enable them)"
+        )
+
+    if override is not None:
+        fields = looser_fields(override, resolved.policy)
+        if fields:
+            raise LooserPolicyError(
+                f"Policy override is looser than {resolved.policy_id} v{resolved.version} on: {', '.join(fields)}",
+                fields,
+            )
+        resolved.policy = override
+        resolved.override_applied = True
+    return resolved
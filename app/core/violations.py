@@ -0,0 +1,45 @@
+"""
+Constraint Violation Records
+Typed violations returned by check_logic and consumed by severity, compliance and audit.
+
+`actual` and `limit` carry the values that were compared, so severity is scored on the
+real overage instead of a placeholder. For cross-sample checks (numeric claims, structured
+fields) they hold the observed relative spread and the allowed tolerance. Relational rules
+report the two sides of their comparison and list every participating input in `inputs`.
+Temporal rules score the absolute change (`actual`) against the allowed change (`limit`)
+and record the `previous` and `current` values and the `window_seconds` compared over.
+"""
+
+from __future__ import annotations
+
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, Iterable, List, Optional
+
+
+@dataclass(frozen=True)
+class ConstraintViolation:
+    rule_id: str                       # constraint rule or check id from the definitions
+    message: str                       # legacy violation string
+    variable: Optional[str] = None
+    actual: Optional[float] = None
+    limit: Optional[float] = None
+    unit: Optional[str] = None
+    inputs: Optional[Dict[str, float]] = None  # every participating variable (relational rules)
+    previous: Optional[float] = None          # temporal rules: value the change is measured from
+    current: Optional[float] = None
+    window_seconds: Optional[float] = None
+
+    def __str__(self) -> str:
+        return self.message
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def violation_messages(violations: Iterable[ConstraintViolation]) -> List[str]:
+    """Legacy string list kept on API responses and audit entries."""
+    return [violation.message for violation in violations]
+
+
+def violation_records(violations: Iterable[ConstraintViolation]) -> List[Dict[str, Any]]:
+    return [violation.to_dict() for violation in violations]
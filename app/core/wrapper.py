@@ -1,6 +1,8 @@
 from typing import Callable, TypeVar, Any
 from app.core.constraints import check_logic
 from app.core.entropy import hallucination_divergence
+from app.core.decision_engine import decide
+from app.core.violations import violation_messages
 from app.models import ValidationRequest
 
 T = TypeVar("T")
@@ -17,12 +19,12 @@ def constraint_wrapper(func: Callable[..., T]) -> Callable[..., T]:
         entropy_score = hallucination_divergence(request.samples)
         violations = check_logic(request)
 
-        if entropy_score > 0.4 or violations:
+        if decide(entropy_score, violations).action != "ALLOW":
             raise ValueError(
                 {
                     "status": "Logic Violation",
                     "entropy_score": entropy_score,
-                    "violations": violations,
+                    "violations": violation_messages(violations),
                 }
             )
 
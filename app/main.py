@@ -1,8 +1,9 @@
-from fastapi import FastAPI, HTTPException, BackgroundTasks, Depends, Request, Response
+from fastapi import FastAPI, HTTPException, BackgroundTasks, Depends, Query, Request, Response
 from fastapi.responses import JSONResponse
 from fastapi.middleware.trustedhost import TrustedHostMiddleware
 from fastapi.middleware.cors import CORSMiddleware
 from datetime import datetime, timezone
+from typing import List, Optional, Tuple
 from app.models import (
     ValidationRequest,
     ValidationResponse,
@@ -12,10 +13,16 @@ from app.models import (
     RewindResponse,
     PreTradeRequest,
     PreTradeResponse,
+    ExecutionReportRequest,
+    BasketPreTradeRequest,
+    BasketPreTradeResponse,
+    BasketLegResult,
     TradeOrder,
     SimulationRequest,
     SimulationResponse,
     SimulationSummary,
+    FeedbackRequest,
+    FeedbackResponse,
 )
 from app.models_health import (
     ValidationMetrics,
@@ -25,32 +32,75 @@ from app.models_health import (
     DriftMetrics,
     IndicatorStatus,
 )
-from app.core.entropy import hallucination_divergence
+from app.core.divergence_backends import resolve_backend
+from app.core.structured_divergence import field_divergence_details, score_sample_divergence
 from app.core.constraints import check_logic
+from app.core.calibration import active_calibration
 from app.core.confidence import calculate_confidence
-from app.core.severity import calculate_violation_severity, calculate_overall_severity_score
-from app.core.drift import request_history
-from app.core.parallel_validation import run_parallel_validation
+from app.core.severity import calculate_violation_severities, calculate_overall_severity_score
+from app.core.drift import (
+    ALERT_EVENT_TYPES,
+    CRITICAL_VIOLATION_EVENT_TYPES,
+    drift_registry,
+    record_drift_audit_events,
+)
+from app.core.parallel_validation import mark_validation_queued, run_queued_validation
+from app.core.constraint_rules import ConstraintDefinitions, resolve_constraint_definitions
 from app.core.storage import (
     insert_request,
     get_validation_result,
     get_latest_valid_snapshot,
     list_audit_events,
+    list_drift_states,
+    list_runtime_audit_events_by_type,
     insert_audit_event,
     verify_runtime_audit_chain,
     insert_execution_decision,
     get_execution_decision,
+    insert_basket_decision,
+    get_basket_decision,
+    get_account_ledger,
+    get_instrument_map,
+    record_execution_report,
+    list_execution_reports,
     insert_simulation_run,
     get_simulation_run,
     reserve_pre_trade_frequency_slot,
+    get_tenant_validation_settings,
+    get_active_constraint_bundle,
+    count_shadow_divergences,
+    list_shadow_divergences,
+    upsert_validation_feedback,
+    list_validation_feedback,
+)
+from app.core.trade_guard import evaluate_basket, evaluate_pre_trade
+from app.core.ledger import account_lock, reconcile_account, record_ledger_discrepancies
+from app.core.trade_policies import (
+    LooserPolicyError,
+    TradePolicyError,
+    TradingScopeError,
+    UnknownTradePolicyError,
+    resolve_trade_policy,
+    trading_scope,
 )
-from app.core.trade_guard import evaluate_pre_trade
+from app.core.shadow import SHADOW_KINDS, shadow_basket, shadow_pre_trade
+from app.core.feedback import DEFAULT_DIVERGENCE_THRESHOLDS, compute_feedback_metrics, feedback_subject
+from app.core.onboarding import record_config_change
 from app.core.simulation import run_policy_simulation
 from app.core.compliance import map_violations_to_articles
+from app.core.violations import violation_messages, violation_records
+from app.core.decision_engine import action_label_and_reason, decide, recommendation_for
 from app.core.pdf_export import generate_audit_pdf
 from app.core.security import get_security_headers, init_security, SecurityConfigError
-from app.core.entropy import load_embedding_model
-from app.routes.onboarding import get_current_tenant, require_permission, router as onboarding_router
+from app.core.entropy import load_embedding_model, sample_embeddings
+from app.core.health import public_report, readiness
+from app.routes.onboarding import (
+    get_current_key_metadata,
+    get_current_tenant,
+    get_optional_key_metadata,
+    require_permission,
+    router as onboarding_router,
+)
 from uuid import uuid4
 import json
 import os
@@ -177,7 +227,7 @@ async def rate_limit_middleware(request: Request, call_next):
     Limits: {_RATE_LIMIT_REQUESTS} requests per {_RATE_LIMIT_WINDOW_SECONDS}s per IP.
     """
     # Skip rate limiting for health checks
-    if request.url.path in ["/v2/health", "/health"]:
+    if request.url.path in ["/v2/health", "/v2/health/live", "/v2/health/ready", "/health"]:
         return await call_next(request)
     
     # Get client IP (considering proxy headers)
@@ -274,13 +324,48 @@ async def watcher_gateway_middleware(request: Request, call_next):
     return response
 
 
-@app.post("/v1/validate", response_model=ValidationResponse)
-def validate(request: ValidationRequest) -> ValidationResponse:
-    entropy_score = hallucination_divergence(request.samples)
-    violations = check_logic(request)
+def _resolve_request_backend(request, tenant_settings=None):
+    """Resolve the divergence backend for a request; unknown names are a client error."""
+    try:
+        return resolve_backend(
+            name=request.divergence_backend,
+            ensemble=request.divergence_ensemble,
+            tenant_settings=tenant_settings,
+        )
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
 
-    if entropy_score > 0.4 or violations:
-        status_code = 424 if violations else 403
+
+def _caller_validation_settings(key_metadata: Optional[dict]) -> Optional[dict]:
+    """Tenant validation settings for authenticated callers of the public v1/v2 endpoints."""
+    if key_metadata is None:
+        return None
+    return get_tenant_validation_settings(key_metadata["tenant_id"])
+
+
+def _caller_constraint_definitions(key_metadata: Optional[dict]) -> ConstraintDefinitions:
+    """The caller's active constraint bundle, as on /v3; anonymous callers get the shipped defaults."""
+    if key_metadata is None:
+        return resolve_constraint_definitions(None)
+    return resolve_constraint_definitions(get_active_constraint_bundle(key_metadata["tenant_id"]))
+
+
+@app.post("/v1/validate", response_model=ValidationResponse)
+def validate(
+    request: ValidationRequest,
+    key_metadata: Optional[dict] = Depends(get_optional_key_metadata),
+) -> ValidationResponse:
+    entropy = score_sample_divergence(
+        request, _resolve_request_backend(request, _caller_validation_settings(key_metadata))
+    )
+    entropy_score = entropy.score
+    records = check_logic(request, _caller_constraint_definitions(key_metadata))
+    violations = violation_messages(records)
+    decision = decide(entropy_score, records)
+
+    if decision.action != "ALLOW":
+        # Legacy v1 transport: REVIEW is surfaced as HTTP 403 "Logic Violation"
+        status_code = 424 if decision.action == "BLOCK" else 403
         raise HTTPException(
             status_code=status_code,
             detail={
@@ -290,9 +375,14 @@ def validate(request: ValidationRequest) -> ValidationResponse:
                     if violations
                     else f"Semantic divergence too high (score={entropy_score:.3f})"
                 ),
+                "action": decision.action,
+                "decision_status_code": decision.status_code,
+                "decision_table_version": decision.table_version,
                 "entropy_score": entropy_score,
                 "violations": violations,
-                "context_reset": True if status_code == 424 else False,
+                "violation_records": violation_records(records),
+                "semantic_clusters": entropy.clusters,
+                "context_reset": decision.context_reset,
             },
         )
 
@@ -300,28 +390,44 @@ def validate(request: ValidationRequest) -> ValidationResponse:
         status="OK",
         entropy_score=entropy_score,
         violations=[],
+        semantic_clusters=entropy.clusters,
+        action=decision.action,
+        decision_table_version=decision.table_version,
     )
 
 
 @app.post("/v2/validate", response_model=ValidationMetrics)
-def validate_advanced(request: ValidationRequest) -> ValidationMetrics:
+def validate_advanced(
+    request: ValidationRequest,
+    key_metadata: Optional[dict] = Depends(get_optional_key_metadata),
+) -> ValidationMetrics:
     """
     Advanced validation endpoint with confidence, severity, and drift detection.
     Returns validation results with health indicator.
     
     Returns indicator (GREEN/YELLOW/RED) + action_label for decision support.
+    Authenticated callers are scored with their tenant's default divergence backend,
+    checked against their tenant's active constraint bundle and given their tenant's selected
+    confidence calibration, as on /v3. Their drift is tracked per (tenant, agent_id) and
+    the response carries the request_id that drift alerts and audit events are filed under.
     """
     timestamp = datetime.now(timezone.utc)
-    entropy_score = hallucination_divergence(request.samples)
-    violations = check_logic(request)
+    request_id = str(uuid4())
+    tenant_id = key_metadata["tenant_id"] if key_metadata else None
+    agent_id = request.agent_id if key_metadata else None
+    entropy = score_sample_divergence(
+        request, _resolve_request_backend(request, _caller_validation_settings(key_metadata))
+    )
+    entropy_score = entropy.score
+    definitions = _caller_constraint_definitions(key_metadata)
+    records = check_logic(request, definitions)
+    violations = violation_messages(records)
     
     # Feature 1: Confidence scoring
-    confidence = calculate_confidence(entropy_score, violations)
+    confidence = calculate_confidence(entropy_score, violations, active_calibration(tenant_id))
     
-    # Feature 2: Severity-weighted violations
-    violation_severity_objects = [
-        calculate_violation_severity(v, 0.0) for v in violations
-    ]
+    # Feature 2: Severity-weighted violations (actual vs limit from each record)
+    violation_severity_objects = calculate_violation_severities(records, definitions)
     # Convert ViolationSeverity to ViolationDetail for response
     violation_severities = [
         ViolationDetail(
@@ -334,35 +440,32 @@ def validate_advanced(request: ValidationRequest) -> ValidationMetrics:
     ]
     overall_severity = calculate_overall_severity_score(violation_severity_objects)
     
-    # Feature 3: Drift detection
-    request_history.record(entropy_score, len(violations))
-    drift = request_history.detect_drift(entropy_score)
+    # Feature 3: Drift detection (unauthenticated traffic shares the public scope)
+    drift = drift_registry.record(
+        tenant_id, agent_id, entropy_score, len(violations), sample_embeddings(request.samples)
+    )
+    record_drift_audit_events(request_id, tenant_id, agent_id, drift)
     
-    # Determine action and status code
-    if violations or entropy_score > 0.4:
-        if overall_severity >= 3.0:
-            action = "BLOCK"
-            status_code = 424
-            recommendation = f"CRITICAL: {violation_severity_objects[0].impact_description if violation_severity_objects else 'High divergence'}"
-        else:
-            action = "REVIEW"
-            status_code = 400
-            recommendation = f"Manual review recommended. Confidence: {confidence.risk_level}"
-    else:
-        action = "ALLOW"
-        status_code = 200
-        recommendation = "Safe to execute"
+    # Determine action and status code (shared decision table)
+    decision = decide(entropy_score, records)
+    action = decision.action
+    status_code = decision.status_code
+    context_reset = decision.context_reset
+    recommendation = recommendation_for(
+        decision,
+        confidence.risk_level,
+        violation_severity_objects[0].impact_description if violation_severity_objects else None,
+    )
     
-    context_reset = False
     if status_code == 424:
-        context_reset = True
-        articles = map_violations_to_articles(violations)
+        articles = map_violations_to_articles(records, definitions)
         insert_audit_event(
-            request_id="inline",
-            tenant_id=None,
-            agent_id=None,
+            request_id=request_id,
+            tenant_id=tenant_id,
+            agent_id=agent_id,
             event_type="EXECUTION_BLOCKED",
             violations=violations,
+            violation_records=violation_records(records),
             regulatory_articles=articles,
             message=(
                 "Execution blocked via /v2/validate: "
@@ -372,28 +475,22 @@ def validate_advanced(request: ValidationRequest) -> ValidationMetrics:
 
     # Compute indicator and action_label for CRO clarity
     indicator = _compute_indicator(status_code, confidence.score, overall_severity)
-    action_label = {
-        "ALLOW": "ALLOW",
-        "REVIEW": "REVIEW",
-        "BLOCK": "BLOCK",
-    }.get(action, "REVIEW")
-    action_reason = {
-        "ALLOW": f"Safe to execute (confidence={confidence.score:.2f})",
-        "REVIEW": f"Requires review: {confidence.risk_level} confidence ({confidence.score:.2f})",
-        "BLOCK": f"Execution blocked: {'; '.join(violations) if violations else 'High divergence'}",
-    }.get(action, "Unknown decision")
+    action_label, action_reason = action_label_and_reason(decision, confidence.score, violations)
 
     return ValidationMetrics(
-        request_id=None,
+        request_id=request_id,
         timestamp=timestamp,
         status_code=status_code,
         divergence_score=entropy_score,
         violations=violations,
-        confidence=ConfidenceMetrics(
-            score=confidence.score,
-            risk_level=confidence.risk_level,
-            explanation=confidence.explanation,
-        ),
+        violation_records=violation_records(records),
+        semantic_clusters=entropy.clusters,
+        semantic_cluster_count=entropy.cluster_count,
+        divergence_backend=entropy.backend,
+        divergence_backend_version=entropy.backend_version,
+        sample_format=request.sample_format,
+        field_divergences=field_divergence_details(entropy),
+        confidence=ConfidenceMetrics(**confidence.to_dict()),
         violation_severities=violation_severities,
         overall_severity_score=overall_severity if violation_severities else None,
         drift=DriftMetrics(
@@ -402,18 +499,45 @@ def validate_advanced(request: ValidationRequest) -> ValidationMetrics:
             historical_mean=drift.historical_mean,
             current_value=drift.current_value,
             explanation=drift.explanation,
+            fired_detectors=drift.fired_detectors,
+            detectors=drift.detectors,
         ),
         indicator=indicator,
         action_label=action_label,
         action_reason=action_reason,
         action=action,
+        decision_table_version=decision.table_version,
         recommendation=recommendation,
         context_reset=context_reset,
     )
 
 
+def _scope_health(tenant_id: Optional[str], agent_id: Optional[str]) -> HealthMetrics:
+    history = drift_registry.scope(tenant_id, agent_id)
+    stats = history.get_stats()
+    violation_rate = history.violation_rate()
+    last_critical = list_runtime_audit_events_by_type(
+        tenant_id, list(CRITICAL_VIOLATION_EVENT_TYPES), agent_id=agent_id, limit=1
+    )
+    return HealthMetrics(
+        tenant_id=tenant_id,
+        agent_id=agent_id,
+        uptime_requests=stats["count"],
+        recent_divergence_avg=round(stats["mean"], 3),
+        recent_violation_rate=round(violation_rate, 3),
+        drift_detected=history.drift_detected,
+        last_critical_violation=last_critical[0]["created_at"] if last_critical else None,
+        status=history.compute_health_status(),
+        # Compute indicator for CRO (primary interface)
+        indicator=_compute_health_indicator(violation_rate, history.drift_detected),
+    )
+
+
 @app.get("/v2/health")
-def health_metrics() -> Response:
+def health_metrics(
+    agent_id: Optional[str] = None,
+    key_metadata: Optional[dict] = Depends(get_optional_key_metadata),
+) -> Response:
     """
     System health endpoint for monitoring and operations.
     Provides aggregated metrics about recent behavior.
@@ -421,40 +545,17 @@ def health_metrics() -> Response:
     PRIMARY CONTRACT: indicator (GREEN/YELLOW/RED) is single source of truth for CRO.
     CRO polls every 2 seconds for real-time status.
     
+    Scope: the caller's tenant when authenticated (one agent with `agent_id`, else all of
+    the tenant's agents pooled); unauthenticated callers see the public /v2/validate scope.
+    Dependency health is reported by /v2/health/ready.
+    
     Returns 503 Service Unavailable when status is CRITICAL.
     """
-    stats = request_history.get_stats()
-    
-    recent_divergence_avg = stats["mean"]
-    violation_rate = (
-        sum(request_history.violation_counts) / len(request_history.violation_counts)
-        if request_history.violation_counts
-        else 0.0
-    )
-    
-    # Determine overall status
-    if recent_divergence_avg > 0.5 or violation_rate > 0.5:
-        status = "CRITICAL"
-    elif recent_divergence_avg > 0.35 or violation_rate > 0.3:
-        status = "DEGRADED"
-    else:
-        status = "HEALTHY"
-    
-    # Compute indicator for CRO (primary interface)
-    indicator = _compute_health_indicator(violation_rate, False)  # drift_detected would be global flag
-    
-    metrics = HealthMetrics(
-        uptime_requests=stats["count"],
-        recent_divergence_avg=round(recent_divergence_avg, 3),
-        recent_violation_rate=round(violation_rate, 3),
-        drift_detected=False,  # Would check global drift state
-        last_critical_violation=None,  # Would track from history
-        status=status,
-        indicator=indicator,
-    )
+    tenant_id = key_metadata["tenant_id"] if key_metadata else None
+    metrics = _scope_health(tenant_id, agent_id)
     
     # Return 503 when system is CRITICAL (alerts monitoring systems)
-    if status == "CRITICAL":
+    if metrics.status == "CRITICAL":
         return JSONResponse(
             status_code=503,
             content=metrics.model_dump()
@@ -466,6 +567,38 @@ def health_metrics() -> Response:
     )
 
 
+@app.get("/v2/health/live")
+def liveness() -> dict:
+    """Liveness probe: the process is up and serving. Touches no dependencies."""
+    return {"status": "alive", "checked_at": datetime.utcnow().isoformat()}
+
+
+@app.get("/v2/health/ready")
+def readiness_probe() -> Response:
+    """
+    Readiness probe: database writes, audit chain verification freshness, embedding model
+    state, background queue depth and free disk. Returns 503 when any check fails.
+
+    Unauthenticated and exempt from rate limiting, so checks run at most once per
+    HEALTH_READY_CACHE_SECONDS and the body carries only booleans and counts.
+    """
+    report = readiness(max_age_seconds=float(os.getenv("HEALTH_READY_CACHE_SECONDS", "5")))
+    return JSONResponse(status_code=200 if report["ready"] else 503, content=public_report(report))
+
+
+@app.get("/v2/health/tenant")
+def tenant_health(
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    """The caller's tenant: pooled health plus one entry per agent with a drift baseline."""
+    agents = [entry["agent_id"] for entry in list_drift_states(current_tenant) if entry["agent_id"] is not None]
+    return {
+        "tenant": _scope_health(current_tenant, None).model_dump(),
+        "agents": [_scope_health(current_tenant, agent_id).model_dump() for agent_id in agents],
+    }
+
+
 @app.post("/v3/intent", response_model=IntentAck)
 def submit_intent(
     request: AgentIntentRequest,
@@ -477,6 +610,8 @@ def submit_intent(
         raise HTTPException(status_code=403, detail="Cannot submit intents for another tenant")
 
     request = request.model_copy(update={"tenant_id": current_tenant})
+    # Reject unknown backends at submission instead of failing in the background task
+    _resolve_request_backend(request, get_tenant_validation_settings(current_tenant))
     request_id = str(uuid4())
     insert_request(
         request_id=request_id,
@@ -487,7 +622,8 @@ def submit_intent(
         payload=request.model_dump(),
         tenant_id=current_tenant,
     )
-    background_tasks.add_task(run_parallel_validation, request_id, request)
+    mark_validation_queued()
+    background_tasks.add_task(run_queued_validation, request_id, request)
     return IntentAck(
         request_id=request_id,
         status="PENDING",
@@ -513,6 +649,15 @@ def get_intent_status(
         action=result["action"],
         divergence_score=result["divergence_score"],
         violations=result["violations"],
+        violation_records=result["violation_records"],
+        semantic_clusters=result["semantic_clusters"],
+        divergence_backend=result["divergence_backend"],
+        divergence_backend_version=result["divergence_backend_version"],
+        field_divergences=result["field_divergences"],
+        reasoning_audit=result["reasoning_audit"],
+        decision_table_version=result["decision_table_version"],
+        constraint_bundle_version=result["constraint_bundle_version"],
+        confidence=result["confidence"],
         recommendation=result["recommendation"],
         context_reset=result["context_reset"],
         latency_ms=result["latency_ms"],
@@ -520,6 +665,108 @@ def get_intent_status(
     )
 
 
+@app.post("/v3/feedback", response_model=FeedbackResponse)
+def submit_feedback(
+    request: FeedbackRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: dict = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config")),
+) -> FeedbackResponse:
+    """
+    Label a /v3 validation result (request_id) or /v4 pre-trade decision (decision_id) as
+    safe or unsafe. Relabelling replaces the outcome; every label is audited with its reviewer.
+    Labels feed precision/recall metrics, so validate-only agent keys cannot submit them.
+    """
+    subject = feedback_subject(current_tenant, request_id=request.request_id, decision_id=request.decision_id)
+    if subject is None:
+        raise HTTPException(status_code=404, detail="Validation result or decision not found")
+
+    stored = upsert_validation_feedback(
+        tenant_id=current_tenant,
+        reviewer_id=key_metadata["user_id"],
+        outcome=request.outcome,
+        note=request.note,
+        **subject,
+    )
+    record_config_change(
+        tenant_id=current_tenant,
+        actor_id=key_metadata["user_id"],
+        event_type="validation_feedback_recorded",
+        target=f"{subject['reference_type']}:{subject['reference_id']}",
+        details={
+            "feedback_id": stored["feedback_id"],
+            "action": subject["action"],
+            "outcome": request.outcome,
+            "note": request.note,
+        },
+    )
+    return FeedbackResponse(**{key: value for key, value in stored.items() if key != "tenant_id"})
+
+
+@app.get("/v3/feedback/metrics")
+def get_feedback_metrics(
+    agent_id: Optional[str] = None,
+    since: Optional[datetime] = None,
+    until: Optional[datetime] = None,
+    divergence_threshold: Optional[List[float]] = Query(default=None),
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    """
+    Precision/recall/confusion metrics from labelled outcomes, overall and per agent/rule,
+    plus a sweep of divergence cutoffs. Naive timestamps are read as UTC.
+    """
+    thresholds = divergence_threshold or list(DEFAULT_DIVERGENCE_THRESHOLDS)
+    if any(not 0.0 <= threshold <= 1.0 for threshold in thresholds):
+        raise HTTPException(status_code=400, detail="divergence_threshold must be between 0 and 1")
+    since_utc = since.astimezone(timezone.utc).replace(tzinfo=None) if since and since.tzinfo else since
+    until_utc = until.astimezone(timezone.utc).replace(tzinfo=None) if until and until.tzinfo else until
+
+    entries = list_validation_feedback(current_tenant, agent_id=agent_id, since=since_utc, until=until_utc)
+    return {
+        "tenant_id": current_tenant,
+        "agent_id": agent_id,
+        "since": since_utc.isoformat() if since_utc else None,
+        "until": until_utc.isoformat() if until_utc else None,
+        **compute_feedback_metrics(entries, thresholds),
+    }
+
+
+@app.get("/v3/alerts")
+def list_alerts(
+    agent_id: Optional[str] = None,
+    event_type: Optional[List[str]] = Query(default=None),
+    since: Optional[datetime] = None,
+    after_id: Optional[int] = None,
+    limit: int = Query(default=100, ge=1, le=1000),
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    """
+    Drift detections and health status transitions from the runtime audit log, newest first.
+    Poll with `after_id` set to the highest `audit_id` seen to receive only new alerts.
+    """
+    event_types = event_type or list(ALERT_EVENT_TYPES)
+    unknown = sorted(set(event_types) - set(ALERT_EVENT_TYPES))
+    if unknown:
+        raise HTTPException(
+            status_code=400,
+            detail=f"Unknown alert type(s) {unknown}; expected one of {list(ALERT_EVENT_TYPES)}",
+        )
+    since_utc = since.astimezone(timezone.utc).replace(tzinfo=None) if since and since.tzinfo else since
+
+    alerts = list_runtime_audit_events_by_type(
+        current_tenant, event_types, agent_id=agent_id, since=since_utc, after_id=after_id, limit=limit
+    )
+    return {
+        "tenant_id": current_tenant,
+        "agent_id": agent_id,
+        "alert_count": len(alerts),
+        "latest_audit_id": alerts[0]["audit_id"] if alerts else after_id,
+        "alerts": alerts,
+    }
+
+
 @app.post("/v3/rewind/{agent_id}", response_model=RewindResponse)
 def rewind_agent(
     agent_id: str,
@@ -575,15 +822,61 @@ def export_compliance_ledger(
     return Response(content=pdf_bytes, media_type="application/pdf", headers=headers)
 
 
+def _request_trading_scope(request, key_metadata: dict) -> Tuple[Optional[str], Optional[str]]:
+    """(account, strategy) from the caller's API key; naming any other is a 403."""
+    try:
+        return trading_scope(request.tenant_id, key_metadata.get("key_id"), request.account_id, request.strategy_id)
+    except TradingScopeError as exc:
+        raise HTTPException(status_code=403, detail=str(exc)) from exc
+
+
+def _resolve_request_policy(request, account_id: Optional[str], strategy_id: Optional[str]):
+    """Trade policy for a single-order or basket pre-trade request, as HTTP errors."""
+    try:
+        return resolve_trade_policy(
+            request.tenant_id,
+            policy_id=request.policy_id,
+            policy_version=request.policy_version,
+            account_id=account_id,
+            strategy_id=strategy_id,
+            override=request.policy,
+        )
+    except UnknownTradePolicyError as exc:
+        raise HTTPException(status_code=404, detail=str(exc)) from exc
+    except LooserPolicyError as exc:
+        raise HTTPException(status_code=403, detail={"message": str(exc), "fields": exc.fields}) from exc
+    except TradePolicyError as exc:
+        raise HTTPException(status_code=422, detail=str(exc)) from exc
+
+
 @app.post("/v4/execution/pre-trade", response_model=PreTradeResponse)
 def enforce_pre_trade_policy(
     request: PreTradeRequest,
     current_tenant: str = Depends(get_current_tenant),
+    key_metadata: dict = Depends(get_current_key_metadata),
     _: bool = Depends(require_permission("validate")),
 ) -> PreTradeResponse:
+    """
+    Evaluate an order against the tenant's server-side trade policy.
+
+    The policy is the one referenced by `policy_id` (which may not be looser than the binding
+    for the API key's trading scope), else the bound one; naming an `account_id` /
+    `strategy_id` outside that scope is refused with 403. An inline `policy` may only tighten
+    it (403 otherwise); tenants without stored policies that opted in to client-supplied
+    policies have their inline policy enforced as-is.
+    The decision records the resolved policy id and version.
+
+    Daily PnL, positions, pending orders and gross exposure are the worse of the snapshot and
+    the server-side ledger for the API key's account (the tenant default account for keys
+    without one); disagreements are audited as LEDGER_DISCREPANCY.
+    Sector and asset-class limits classify symbols with the tenant instrument reference.
+    """
     if request.tenant_id != current_tenant:
         raise HTTPException(status_code=403, detail="Cannot evaluate pre-trade policy for another tenant")
 
+    account_id, strategy_id = _request_trading_scope(request, key_metadata)
+    resolved = _resolve_request_policy(request, account_id, strategy_id)
+
     decision_id = str(uuid4())
     reservation_count = reserve_pre_trade_frequency_slot(
         tenant_id=request.tenant_id,
@@ -596,35 +889,60 @@ def enforce_pre_trade_policy(
         }
     )
 
-    result = evaluate_pre_trade(
-        order=request.order,
-        account=effective_account,
-        policy=request.policy,
-    )
+    instruments = get_instrument_map(request.tenant_id)
+
+    # The approved quantity becomes pending exposure only once stored, so hold the account
+    with account_lock(request.tenant_id, account_id):
+        evaluated_account, ledger_record = reconcile_account(request.tenant_id, account_id, effective_account)
+        result = evaluate_pre_trade(
+            order=request.order,
+            account=evaluated_account,
+            policy=resolved.policy,
+            instruments=instruments,
+        )
+        try:
+            stored_decision = insert_execution_decision(
+                decision_id=decision_id,
+                tenant_id=request.tenant_id,
+                agent_id=request.agent_id,
+                decision=result["decision"],
+                reason=result["reason"],
+                triggered_controls=result["triggered_controls"],
+                order=request.order.model_dump(),
+                account=effective_account.model_dump(mode="json"),
+                policy=resolved.policy.model_dump(),
+                modified_order=result["modified_order"],
+                idempotency_key=request.idempotency_key,
+                policy_id=resolved.policy_id,
+                policy_version=resolved.version,
+                account_id=account_id,
+                ledger=ledger_record,
+            )
+        except ValueError as exc:
+            raise HTTPException(status_code=409, detail=str(exc)) from exc
 
     modified_order_model = None
-    if result["modified_order"] is not None:
-        modified_order_model = TradeOrder(**result["modified_order"])
+    if stored_decision["modified_order"] is not None:
+        modified_order_model = TradeOrder(**stored_decision["modified_order"])
 
-    try:
-        stored_decision = insert_execution_decision(
+    # Idempotent replays return the stored decision; only fresh decisions are shadowed and audited
+    if stored_decision["decision_id"] == decision_id:
+        record_ledger_discrepancies(
             decision_id=decision_id,
             tenant_id=request.tenant_id,
             agent_id=request.agent_id,
-            decision=result["decision"],
-            reason=result["reason"],
-            triggered_controls=result["triggered_controls"],
-            order=request.order.model_dump(),
-            account=effective_account.model_dump(mode="json"),
-            policy=request.policy.model_dump(),
-            modified_order=result["modified_order"],
-            idempotency_key=request.idempotency_key,
+            account_id=account_id,
+            discrepancies=ledger_record["discrepancies"],
+        )
+        shadow_pre_trade(
+            decision_id=decision_id,
+            tenant_id=request.tenant_id,
+            agent_id=request.agent_id,
+            order=request.order,
+            account=evaluated_account,
+            live_result=result,
+            instruments=instruments,
         )
-    except ValueError as exc:
-        raise HTTPException(status_code=409, detail=str(exc)) from exc
-
-    if stored_decision["modified_order"] is not None:
-        modified_order_model = TradeOrder(**stored_decision["modified_order"])
 
     return PreTradeResponse(
         decision_id=stored_decision["decision_id"],
@@ -632,6 +950,8 @@ def enforce_pre_trade_policy(
         reason=stored_decision["reason"],
         triggered_controls=stored_decision["triggered_controls"],
         modified_order=modified_order_model,
+        policy_id=stored_decision["policy_id"],
+        policy_version=stored_decision["policy_version"],
         created_at=stored_decision["created_at"],
     )
 
@@ -648,6 +968,229 @@ def get_pre_trade_decision(
     return decision
 
 
+@app.post("/v4/execution/basket", response_model=BasketPreTradeResponse)
+def enforce_basket_pre_trade_policy(
+    request: BasketPreTradeRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: dict = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("validate")),
+) -> BasketPreTradeResponse:
+    """
+    Evaluate a spread or basket atomically against one shared projected exposure.
+
+    Policy resolution and the account ledger work as for /v4/execution/pre-trade, and each
+    leg counts against the order-frequency cap. A blocked leg blocks every leg. `mode`
+    decides what happens when the legs only fit at a smaller size: `all_or_none` blocks the
+    basket, `scale_down` scales every leg by the same factor (MODIFY). Each leg is stored as
+    its own decision (for execution reports) linked by the returned `basket_id`. A shadow
+    trade policy candidate re-evaluates the whole basket; divergences reference the basket_id.
+    """
+    if request.tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot evaluate pre-trade policy for another tenant")
+
+    account_id, strategy_id = _request_trading_scope(request, key_metadata)
+    resolved = _resolve_request_policy(request, account_id, strategy_id)
+
+    basket_id = str(uuid4())
+    reservation_counts = [
+        reserve_pre_trade_frequency_slot(
+            tenant_id=request.tenant_id,
+            agent_id=request.agent_id,
+            idempotency_key=f"{request.idempotency_key}:leg{index}",
+        )
+        for index in range(len(request.legs))
+    ]
+    effective_account = request.account.model_copy(
+        update={
+            "orders_last_minute": max(request.account.orders_last_minute, max(reservation_counts[0] - 1, 0))
+        }
+    )
+    instruments = get_instrument_map(request.tenant_id)
+
+    with account_lock(request.tenant_id, account_id):
+        evaluated_account, ledger_record = reconcile_account(request.tenant_id, account_id, effective_account)
+        result = evaluate_basket(request.legs, evaluated_account, resolved.policy, request.mode, instruments=instruments)
+        try:
+            stored = insert_basket_decision(
+                basket_id=basket_id,
+                tenant_id=request.tenant_id,
+                agent_id=request.agent_id,
+                mode=request.mode,
+                decision=result["decision"],
+                reason=result["reason"],
+                triggered_controls=result["triggered_controls"],
+                scale=result["scale"],
+                legs=[
+                    {**leg_result, "decision_id": str(uuid4()), "order": leg.model_dump()}
+                    for leg, leg_result in zip(request.legs, result["legs"])
+                ],
+                account=effective_account.model_dump(mode="json"),
+                policy=resolved.policy.model_dump(),
+                idempotency_key=request.idempotency_key,
+                policy_id=resolved.policy_id,
+                policy_version=resolved.version,
+                account_id=account_id,
+                ledger=ledger_record,
+            )
+        except ValueError as exc:
+            raise HTTPException(status_code=409, detail=str(exc)) from exc
+
+    # Idempotent replays return the stored basket; only fresh baskets are shadowed and audited
+    if stored["basket_id"] == basket_id:
+        record_ledger_discrepancies(
+            decision_id=basket_id,
+            tenant_id=request.tenant_id,
+            agent_id=request.agent_id,
+            account_id=account_id,
+            discrepancies=ledger_record["discrepancies"],
+        )
+        shadow_basket(
+            basket_id=basket_id,
+            tenant_id=request.tenant_id,
+            agent_id=request.agent_id,
+            legs=request.legs,
+            account=evaluated_account,
+            mode=request.mode,
+            live_result=result,
+            instruments=instruments,
+        )
+
+    return BasketPreTradeResponse(
+        basket_id=stored["basket_id"],
+        decision=stored["decision"],
+        reason=stored["reason"],
+        mode=stored["mode"],
+        scale=stored["scale"],
+        triggered_controls=stored["triggered_controls"],
+        legs=[
+            BasketLegResult(
+                leg_index=leg["leg_index"],
+                decision_id=leg["decision_id"],
+                decision=leg["decision"],
+                reason=leg["reason"],
+                triggered_controls=leg["triggered_controls"],
+                modified_order=TradeOrder(**leg["modified_order"]) if leg["modified_order"] else None,
+            )
+            for leg in stored["legs"]
+        ],
+        policy_id=stored["legs"][0]["policy_id"],
+        policy_version=stored["legs"][0]["policy_version"],
+        created_at=stored["created_at"],
+    )
+
+
+@app.get("/v4/execution/basket/{basket_id}")
+def get_basket_pre_trade_decision(
+    basket_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    basket = get_basket_decision(basket_id, current_tenant)
+    if basket is None:
+        raise HTTPException(status_code=404, detail="Basket not found")
+    return basket
+
+
+@app.post("/v4/execution/reports")
+def report_execution(
+    request: ExecutionReportRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("report_executions")),
+):
+    """
+    Ingest a broker execution report for a pre-trade decision and reconcile it.
+
+    FILL / PARTIAL_FILL move `quantity` at `price` into the account ledger; CANCEL and REJECT
+    release the unfilled remainder; UNKNOWN (e.g. an ACK timeout) keeps it pending. Fills are
+    reconciled against the approved `modified_order`/`order`: overfills, fills after a cancel,
+    symbol/side mismatches, prices through the approved price and executions without an
+    approving decision are returned as `breaches` and audited. Replaying a `report_id` is a no-op.
+    """
+    try:
+        return record_execution_report(
+            tenant_id=current_tenant,
+            report_id=request.report_id,
+            decision_id=request.decision_id,
+            report_type=request.report_type,
+            quantity=request.quantity,
+            price=request.price,
+            account_id=request.account_id,
+            symbol=request.symbol,
+            side=request.side,
+        )
+    except KeyError as exc:
+        raise HTTPException(status_code=404, detail="No approved order for this decision") from exc
+
+
+@app.get("/v4/execution/reports")
+def get_execution_reports(
+    decision_id: Optional[str] = None,
+    breaches_only: bool = False,
+    limit: int = Query(default=100, ge=1, le=1000),
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    """Execution reports (newest first) with their reconciliation breaches."""
+    return {
+        "tenant_id": current_tenant,
+        "reports": list_execution_reports(
+            current_tenant, decision_id=decision_id, breaches_only=breaches_only, limit=limit
+        ),
+    }
+
+
+@app.get("/v4/execution/ledger")
+def get_execution_ledger(
+    account_id: Optional[str] = None,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    """Server-side positions, pending orders, realized PnL and exposure for one account."""
+    return get_account_ledger(current_tenant, account_id)
+
+
+@app.get("/v4/shadow/report")
+def get_shadow_report(
+    since: Optional[datetime] = None,
+    until: Optional[datetime] = None,
+    kind: Optional[str] = None,
+    include_divergences: bool = False,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("read_results")),
+):
+    """
+    Summarize where shadow candidates disagreed with the enforced verdicts in [since, until).
+    Naive timestamps are read as UTC.
+    """
+    if kind is not None and kind not in SHADOW_KINDS:
+        raise HTTPException(status_code=400, detail=f"kind must be one of {list(SHADOW_KINDS)}")
+    since_utc = since.astimezone(timezone.utc).replace(tzinfo=None) if since and since.tzinfo else since
+    until_utc = until.astimezone(timezone.utc).replace(tzinfo=None) if until and until.tzinfo else until
+
+    transitions = count_shadow_divergences(current_tenant, kind=kind, since=since_utc, until=until_utc)
+
+    def _total(live_action: str, shadow_action: str) -> int:
+        return sum(
+            t["count"] for t in transitions if t["live_action"] == live_action and t["shadow_action"] == shadow_action
+        )
+
+    report = {
+        "tenant_id": current_tenant,
+        "kind": kind,
+        "since": since_utc.isoformat() if since_utc else None,
+        "until": until_utc.isoformat() if until_utc else None,
+        "total_divergences": sum(t["count"] for t in transitions),
+        "allow_to_block": _total("ALLOW", "BLOCK"),
+        "block_to_allow": _total("BLOCK", "ALLOW"),
+        "transitions": transitions,
+    }
+    if include_divergences:
+        report["divergences"] = list_shadow_divergences(
+            current_tenant, kind=kind, since=since_utc, until=until_utc
+        )
+    return report
+
+
 @app.post("/v4/simulation/run", response_model=SimulationResponse)
 def run_simulation(
     request: SimulationRequest,
@@ -659,7 +1202,7 @@ def run_simulation(
 
     simulation_id = str(uuid4())
 
-    simulation = run_policy_simulation(request)
+    simulation = run_policy_simulation(request, instruments=get_instrument_map(request.tenant_id))
 
     try:
         stored_simulation = insert_simulation_run(
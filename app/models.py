@@ -1,6 +1,44 @@
 from datetime import datetime
-from typing import List, Optional, Dict, Any
-from pydantic import BaseModel, Field, field_validator
+from typing import List, Literal, Optional, Dict, Any
+import json
+from pydantic import BaseModel, Field, field_validator, model_validator
+
+
+# Semantic entropy needs several samples; high-stakes intents may send up to 20.
+MIN_SAMPLES = 3
+MAX_SAMPLES = 20
+
+
+def _validate_ensemble(v: Optional[Dict[str, float]]) -> Optional[Dict[str, float]]:
+    if v is None:
+        return v
+    if not v:
+        raise ValueError('divergence_ensemble must name at least one backend')
+    for name, weight in v.items():
+        if weight <= 0:
+            raise ValueError(f'divergence_ensemble weight for {name} must be positive')
+    return v
+
+
+def _validate_samples(v: List[str]) -> List[str]:
+    if not MIN_SAMPLES <= len(v) <= MAX_SAMPLES:
+        raise ValueError(f'samples must contain between {MIN_SAMPLES} and {MAX_SAMPLES} responses')
+    for i, sample in enumerate(v):
+        if not sample or not isinstance(sample, str):
+            raise ValueError(f'sample[{i}] must be a non-empty string')
+    return v
+
+
+def _validate_structured_samples(sample_format: str, samples: List[str]) -> None:
+    if sample_format != "json":
+        return
+    for i, sample in enumerate(samples):
+        try:
+            parsed = json.loads(sample)
+        except json.JSONDecodeError as e:
+            raise ValueError(f'sample[{i}] is not valid JSON: {e.msg}')
+        if not isinstance(parsed, dict):
+            raise ValueError(f'sample[{i}] must be a JSON object when sample_format is "json"')
 
 
 class PhysicsPayload(BaseModel):
@@ -13,20 +51,30 @@ class FinancialPayload(BaseModel):
 
 
 class ValidationRequest(BaseModel):
-    samples: List[str] = Field(..., min_length=3, max_length=3)
+    samples: List[str] = Field(..., min_length=MIN_SAMPLES, max_length=MAX_SAMPLES)
+    agent_id: Optional[str] = Field(default=None, min_length=1, max_length=128)  # /v2 drift scope
     physics: Optional[PhysicsPayload] = None
     financial: Optional[FinancialPayload] = None
     metrics: Optional[Dict[str, float]] = None
+    divergence_backend: Optional[str] = Field(default=None, min_length=1, max_length=64)
+    divergence_ensemble: Optional[Dict[str, float]] = None
+    sample_format: Literal["text", "json"] = "text"
+    decision_fields: Optional[List[str]] = Field(default=None, min_length=1, max_length=50)
     
     @field_validator('samples')
     @classmethod
     def validate_samples(cls, v: List[str]) -> List[str]:
-        if len(v) != 3:
-            raise ValueError('samples must contain exactly 3 responses')
-        for i, sample in enumerate(v):
-            if not sample or not isinstance(sample, str):
-                raise ValueError(f'sample[{i}] must be a non-empty string')
-        return v
+        return _validate_samples(v)
+
+    @field_validator('divergence_ensemble')
+    @classmethod
+    def validate_ensemble(cls, v: Optional[Dict[str, float]]) -> Optional[Dict[str, float]]:
+        return _validate_ensemble(v)
+
+    @model_validator(mode='after')
+    def validate_structured_samples(self):
+        _validate_structured_samples(self.sample_format, self.samples)
+        return self
 
 
 class AgentStateSnapshot(BaseModel):
@@ -52,28 +100,54 @@ class AgentIntentRequest(BaseModel):
     agent_id: str
     intent: str
     desired_state: Optional[str] = None
-    samples: List[str] = Field(..., min_length=3, max_length=3)
+    samples: List[str] = Field(..., min_length=MIN_SAMPLES, max_length=MAX_SAMPLES)
     physics: Optional[PhysicsPayload] = None
     financial: Optional[FinancialPayload] = None
     metrics: Optional[Dict[str, float]] = None
-    chain_of_thought: Optional[List[str]] = None
+    chain_of_thought: Optional[List[str]] = None  # Audited up to reasoning_audit.MAX_STEPS steps
     state_snapshot: Optional[AgentStateSnapshot] = None
+    divergence_backend: Optional[str] = Field(default=None, min_length=1, max_length=64)
+    divergence_ensemble: Optional[Dict[str, float]] = None
+    sample_format: Literal["text", "json"] = "text"
+    decision_fields: Optional[List[str]] = Field(default=None, min_length=1, max_length=50)
     
     @field_validator('samples')
     @classmethod
     def validate_samples(cls, v: List[str]) -> List[str]:
-        if len(v) != 3:
-            raise ValueError('samples must contain exactly 3 responses')
-        for i, sample in enumerate(v):
-            if not sample or not isinstance(sample, str):
-                raise ValueError(f'sample[{i}] must be a non-empty string')
-        return v
+        return _validate_samples(v)
+
+    @field_validator('divergence_ensemble')
+    @classmethod
+    def validate_ensemble(cls, v: Optional[Dict[str, float]]) -> Optional[Dict[str, float]]:
+        return _validate_ensemble(v)
+
+    @model_validator(mode='after')
+    def validate_structured_samples(self):
+        _validate_structured_samples(self.sample_format, self.samples)
+        return self
+
+
+class ViolationRecord(BaseModel):
+    rule_id: str
+    message: str
+    variable: Optional[str] = None
+    actual: Optional[float] = None
+    limit: Optional[float] = None
+    unit: Optional[str] = None
+    inputs: Optional[Dict[str, float]] = None
+    previous: Optional[float] = None
+    current: Optional[float] = None
+    window_seconds: Optional[float] = None
 
 
 class ValidationResponse(BaseModel):
     status: str
     entropy_score: float
     violations: List[str]
+    violation_records: List[ViolationRecord] = Field(default_factory=list)
+    semantic_clusters: List[int] = Field(default_factory=list)
+    action: str = "ALLOW"
+    decision_table_version: Optional[str] = None
 
 
 class IntentAck(BaseModel):
@@ -82,12 +156,30 @@ class IntentAck(BaseModel):
     received_at: str
 
 
+class FieldDivergenceDetail(BaseModel):
+    field: str
+    comparison: str  # exact | numeric | semantic
+    divergence: float
+    values: List[str]
+    present_in: int
+    decision_field: bool
+
+
 class ParallelValidationStatus(BaseModel):
     request_id: str
     status_code: int
     action: str
     divergence_score: float
     violations: List[str]
+    violation_records: Optional[List[ViolationRecord]] = None
+    semantic_clusters: Optional[List[int]] = None
+    divergence_backend: Optional[str] = None
+    divergence_backend_version: Optional[str] = None
+    field_divergences: Optional[List[FieldDivergenceDetail]] = None
+    reasoning_audit: Optional[Dict[str, Any]] = None  # Chain-of-thought findings, first_divergent_step
+    decision_table_version: Optional[str] = None
+    constraint_bundle_version: Optional[int] = None  # Tenant bundle applied; None = shipped defaults
+    confidence: Optional[Dict[str, Any]] = None  # score, raw_score, calibrated_score, calibration_version
     recommendation: str
     context_reset: bool
     latency_ms: float
@@ -131,8 +223,19 @@ class AccountSnapshot(BaseModel):
     orders_last_minute: int = Field(default=0, ge=0)
     avg_order_size: float = Field(default=0.0, ge=0.0)
     order_size_stddev: float = Field(default=0.0, ge=0.0)
+    mark_prices: Dict[str, float] = Field(default_factory=dict)  # Per-symbol marks for sector / concentration limits
+    # Marks for the single-name concentration denominator when they differ (set by the ledger)
+    concentration_mark_prices: Dict[str, float] = Field(default_factory=dict)
     as_of: Optional[datetime] = None
 
+    @field_validator("mark_prices", "concentration_mark_prices")
+    @classmethod
+    def _positive_marks(cls, v: Dict[str, float]) -> Dict[str, float]:
+        for symbol, price in v.items():
+            if price <= 0:
+                raise ValueError(f"mark price for {symbol} must be positive")
+        return v
+
 
 class TradePolicyConfig(BaseModel):
     policy_version: str = Field(default="default", min_length=1, max_length=128)
@@ -147,6 +250,35 @@ class TradePolicyConfig(BaseModel):
     max_market_price_age_seconds: int = Field(default=30, ge=1)
     restricted_instruments: List[str] = Field(default_factory=list)
     kill_switch_enabled: bool = Field(default=False)
+    # Per-symbol position caps (quantity), enforced alongside max_position_size
+    symbol_position_limits: Dict[str, float] = Field(default_factory=dict)
+    # Gross notional per sector / asset class as a fraction of capital; keys match the
+    # tenant instrument reference case-insensitively
+    sector_allocation_pct: Dict[str, float] = Field(default_factory=dict)
+    asset_class_allocation_pct: Dict[str, float] = Field(default_factory=dict)
+    # No single symbol above this fraction of gross exposure, once gross reaches the floor
+    max_single_name_pct: Optional[float] = Field(default=None, gt=0.0, le=1.0)
+    concentration_min_gross_notional: float = Field(default=0.0, ge=0.0)
+
+    @field_validator("symbol_position_limits")
+    @classmethod
+    def _normalize_symbol_limits(cls, v: Dict[str, float]) -> Dict[str, float]:
+        normalized: Dict[str, float] = {}
+        for symbol, limit in v.items():
+            if limit <= 0:
+                raise ValueError(f"symbol_position_limits[{symbol}] must be positive")
+            normalized[symbol.strip().upper()] = limit
+        return normalized
+
+    @field_validator("sector_allocation_pct", "asset_class_allocation_pct")
+    @classmethod
+    def _valid_allocation_pcts(cls, v: Dict[str, float]) -> Dict[str, float]:
+        for name, pct in v.items():
+            if not name.strip():
+                raise ValueError("allocation keys must not be blank")
+            if not 0 < pct <= 1:
+                raise ValueError(f"allocation for {name} must be in (0, 1]")
+        return {name.strip(): pct for name, pct in v.items()}
 
 
 class PreTradeRequest(BaseModel):
@@ -155,7 +287,17 @@ class PreTradeRequest(BaseModel):
     agent_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
     order: TradeOrder
     account: AccountSnapshot
-    policy: TradePolicyConfig
+    policy: Optional[TradePolicyConfig] = None  # Tightening override; sole policy for opted-in tenants without stored ones
+    policy_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    policy_version: Optional[int] = Field(default=None, ge=1)
+    account_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    strategy_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+
+    @model_validator(mode="after")
+    def _version_needs_policy_id(self) -> "PreTradeRequest":
+        if self.policy_version is not None and self.policy_id is None:
+            raise ValueError("policy_version requires policy_id")
+        return self
 
 
 class PreTradeResponse(BaseModel):
@@ -164,7 +306,99 @@ class PreTradeResponse(BaseModel):
     reason: str
     triggered_controls: List[str]
     modified_order: Optional[TradeOrder] = None
+    policy_id: Optional[str] = None
+    policy_version: Optional[int] = None
+    created_at: str
+
+
+class BasketPreTradeRequest(BaseModel):
+    idempotency_key: str = Field(..., min_length=8, max_length=128)
+    tenant_id: str = Field(..., min_length=1, max_length=128)
+    agent_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    legs: List[TradeOrder] = Field(..., min_length=1, max_length=50)
+    mode: Literal["all_or_none", "scale_down"] = "all_or_none"
+    account: AccountSnapshot
+    policy: Optional[TradePolicyConfig] = None  # Same override rules as PreTradeRequest
+    policy_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    policy_version: Optional[int] = Field(default=None, ge=1)
+    account_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    strategy_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+
+    @model_validator(mode="after")
+    def _version_needs_policy_id(self) -> "BasketPreTradeRequest":
+        if self.policy_version is not None and self.policy_id is None:
+            raise ValueError("policy_version requires policy_id")
+        return self
+
+
+class BasketLegResult(BaseModel):
+    leg_index: int
+    decision_id: str
+    decision: str
+    reason: str
+    triggered_controls: List[str]
+    modified_order: Optional[TradeOrder] = None
+
+
+class BasketPreTradeResponse(BaseModel):
+    basket_id: str
+    decision: str
+    reason: str
+    mode: str
+    scale: float
+    triggered_controls: List[str]
+    legs: List[BasketLegResult]
+    policy_id: Optional[str] = None
+    policy_version: Optional[int] = None
+    created_at: str
+
+
+class ExecutionReportRequest(BaseModel):
+    report_id: str = Field(..., min_length=1, max_length=128)  # Broker execution id; replays are ignored
+    decision_id: Optional[str] = Field(default=None, min_length=1, max_length=128)  # None = executed without one
+    report_type: Literal["FILL", "PARTIAL_FILL", "CANCEL", "REJECT", "UNKNOWN"]
+    quantity: Optional[float] = Field(default=None, gt=0)  # This report's fill, not the cumulative quantity
+    price: Optional[float] = Field(default=None, gt=0)
+    # What the broker says executed; defaults to the approved order
+    account_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    symbol: Optional[str] = Field(default=None, min_length=1, max_length=32)
+    side: Optional[str] = Field(default=None, pattern="^(BUY|SELL)$")
+
+    @model_validator(mode="after")
+    def _fill_has_quantity_and_price(self) -> "ExecutionReportRequest":
+        if self.report_type in ("FILL", "PARTIAL_FILL") and (self.quantity is None or self.price is None):
+            raise ValueError("FILL and PARTIAL_FILL reports require quantity and price")
+        if self.report_type not in ("FILL", "PARTIAL_FILL") and self.decision_id is None:
+            raise ValueError(f"{self.report_type} reports require decision_id")
+        return self
+
+
+class FeedbackRequest(BaseModel):
+    request_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    decision_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    outcome: Literal["safe", "unsafe"]
+    note: Optional[str] = Field(default=None, max_length=1000)
+
+    @model_validator(mode="after")
+    def _exactly_one_reference(self) -> "FeedbackRequest":
+        if (self.request_id is None) == (self.decision_id is None):
+            raise ValueError("Provide exactly one of request_id or decision_id")
+        return self
+
+
+class FeedbackResponse(BaseModel):
+    feedback_id: int
+    reference_type: str
+    reference_id: str
+    agent_id: Optional[str] = None
+    action: str
+    outcome: str
+    rule_ids: List[str]
+    divergence_score: Optional[float] = None
+    reviewer_id: Optional[str] = None
+    note: Optional[str] = None
     created_at: str
+    updated_at: str
 
 
 class SimulationEvent(BaseModel):
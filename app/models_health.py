@@ -4,10 +4,12 @@ Structured metrics for health monitoring and validation results
 """
 
 from pydantic import BaseModel
-from typing import List, Optional
+from typing import Any, Dict, List, Optional
 from datetime import datetime
 from enum import Enum
 
+from app.models import FieldDivergenceDetail, ViolationRecord
+
 
 class IndicatorStatus(str, Enum):
     """System health indicator (GREEN/YELLOW/RED). Computed server-side."""
@@ -20,6 +22,9 @@ class ConfidenceMetrics(BaseModel):
     score: float
     risk_level: str
     explanation: str
+    raw_score: Optional[float] = None  # Heuristic score; equals score when uncalibrated
+    calibrated_score: Optional[float] = None
+    calibration_version: Optional[int] = None
 
 
 class ViolationDetail(BaseModel):
@@ -35,6 +40,8 @@ class DriftMetrics(BaseModel):
     historical_mean: float
     current_value: float
     explanation: str
+    fired_detectors: List[str] = []  # Enabled detectors that fired (zscore, cusum, ...)
+    detectors: Optional[Dict[str, Dict[str, Any]]] = None  # Per-detector statistic, threshold, ready
 
 
 class ValidationMetrics(BaseModel):
@@ -51,6 +58,13 @@ class ValidationMetrics(BaseModel):
     status_code: int
     divergence_score: float
     violations: List[str]
+    violation_records: List[ViolationRecord] = []  # Typed records (rule id, actual vs limit)
+    semantic_clusters: Optional[List[int]] = None  # Meaning cluster id per sample
+    semantic_cluster_count: Optional[int] = None
+    divergence_backend: Optional[str] = None
+    divergence_backend_version: Optional[str] = None
+    sample_format: str = "text"
+    field_divergences: List[FieldDivergenceDetail] = []  # Per-field report when sample_format="json"
     
     # Advanced metrics
     confidence: ConfidenceMetrics
@@ -65,6 +79,7 @@ class ValidationMetrics(BaseModel):
     
     # Summary for dashboard
     action: str  # "ALLOW", "REVIEW", "BLOCK"
+    decision_table_version: Optional[str] = None
     recommendation: str
     context_reset: Optional[bool] = None
     latency_ms: Optional[float] = None
@@ -78,6 +93,8 @@ class HealthMetrics(BaseModel):
     - drift_detected
     - last_critical_violation
     """
+    tenant_id: Optional[str] = None  # Scope reported; None = unauthenticated /v2 traffic
+    agent_id: Optional[str] = None  # None = every agent in the tenant
     uptime_requests: int
     recent_divergence_avg: float
     recent_violation_rate: float
@@ -8,6 +8,9 @@ Implements:
 4. Audit log endpoints
 5. Multi-tenancy middleware
 6. JWT authentication and token refresh
+7. Tenant validation settings and constraint bundles
+8. Shadow-mode candidates
+9. Trade policy registry and bindings
 """
 
 from fastapi import APIRouter, HTTPException, Request, Depends, Header
@@ -32,11 +35,51 @@ from app.core.onboarding import (
     list_audit_log,
     verify_audit_chain,
     has_permission,
+    record_config_change,
     Role,
     SupportTier,
     APIKeyScope,
     init_onboarding_db
 )
+from app.core.storage import (
+    get_tenant_validation_settings,
+    update_tenant_validation_settings,
+    create_constraint_bundle,
+    get_constraint_bundle,
+    get_active_constraint_bundle,
+    list_constraint_bundles,
+    activate_constraint_bundle,
+    rollback_constraint_bundle,
+    set_shadow_candidate,
+    list_shadow_candidates,
+    clear_shadow_candidate,
+    get_confidence_calibration,
+    list_confidence_calibrations,
+    create_trade_policy,
+    get_trade_policy,
+    list_trade_policies,
+    bind_trade_policy,
+    unbind_trade_policy,
+    list_trade_policy_bindings,
+    set_api_key_trading_scope,
+    get_api_key_trading_scope,
+    clear_api_key_trading_scope,
+    upsert_instruments,
+    list_instruments,
+    delete_instrument,
+)
+from app.core.drift_detectors import DETECTORS_SETTING, parse_detector_config
+from app.core.calibration import CALIBRATION_SETTING, CalibrationError, fit_tenant_calibration
+from app.core.shadow import SHADOW_CONSTRAINT_BUNDLE, SHADOW_KINDS, SHADOW_TRADE_POLICY
+from app.core.trade_policies import CLIENT_POLICY_SETTING
+from app.models import TradePolicyConfig
+from app.core.divergence_backends import resolve_backend
+from app.core.constraint_rules import (
+    ConstraintDefinitionError,
+    parse_bundle_definitions,
+    resolve_constraint_definitions,
+)
+from app.core.policy_analyzer import analyze
 from app.core.security import (
     create_access_token,
     create_refresh_token,
@@ -66,7 +109,7 @@ class UserCreateRequest(BaseModel):
 
 class APIKeyCreateRequest(BaseModel):
     user_id: Optional[str] = None
-    scope: str = Field(..., pattern="^(validate-only|admin|read-metrics)$")
+    scope: str = Field(..., pattern="^(validate-only|admin|read-metrics|execution-reports)$")
     expires_in_days: int = Field(default=90, ge=1, le=365)
 
 
@@ -74,6 +117,75 @@ class UserRoleChangeRequest(BaseModel):
     new_role: str = Field(..., pattern="^(admin|risk-officer|analyst|viewer)$")
 
 
+class DivergenceBackendRequest(BaseModel):
+    divergence_backend: Optional[str] = Field(default=None, min_length=1, max_length=64)
+    divergence_ensemble: Optional[Dict[str, float]] = None
+
+
+class DriftDetectorsRequest(BaseModel):
+    enabled: Optional[List[str]] = Field(default=None, max_length=10)  # None = reset to defaults
+    parameters: Optional[Dict[str, Dict[str, float]]] = None
+
+
+class ConstraintBundleRequest(BaseModel):
+    rules: List[Dict[str, Any]] = Field(..., min_length=1, max_length=200)
+    relations: Optional[List[Dict[str, Any]]] = Field(default=None, max_length=100)
+    temporal: Optional[List[Dict[str, Any]]] = Field(default=None, max_length=100)
+    checks: Optional[List[Dict[str, Any]]] = Field(default=None, max_length=50)
+    note: Optional[str] = Field(default=None, max_length=500)
+
+
+class BundleAnalysisRequest(BaseModel):
+    version: Optional[int] = Field(default=None, ge=1)
+    bundle: Optional[ConstraintBundleRequest] = None
+    policy: Optional[TradePolicyConfig] = None
+
+
+class ShadowBundleRequest(BaseModel):
+    version: int = Field(..., ge=1)
+
+
+class TradePolicyCreateRequest(BaseModel):
+    policy_id: str = Field(..., min_length=1, max_length=128, pattern=r"^[A-Za-z0-9_.:-]+$")
+    policy: TradePolicyConfig
+    note: Optional[str] = Field(default=None, max_length=500)
+
+
+class TradePolicyBindingRequest(BaseModel):
+    policy_id: str = Field(..., min_length=1, max_length=128)
+    version: Optional[int] = Field(default=None, ge=1)  # None = latest version
+    account_id: Optional[str] = Field(default=None, min_length=1, max_length=128)  # None = any account
+    strategy_id: Optional[str] = Field(default=None, min_length=1, max_length=128)  # None = any strategy
+
+
+class TradingScopeRequest(BaseModel):
+    account_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    strategy_id: Optional[str] = Field(default=None, min_length=1, max_length=128)
+
+
+class ClientTradePolicyRequest(BaseModel):
+    enabled: bool
+
+
+class InstrumentReferenceEntry(BaseModel):
+    symbol: str = Field(..., min_length=1, max_length=32, pattern=r"^[A-Za-z0-9_./:-]+$")
+    sector: Optional[str] = Field(default=None, min_length=1, max_length=128)
+    asset_class: Optional[str] = Field(default=None, min_length=1, max_length=128)
+
+
+class InstrumentReferenceRequest(BaseModel):
+    instruments: List[InstrumentReferenceEntry] = Field(..., min_length=1, max_length=1000)
+
+
+class CalibrationFitRequest(BaseModel):
+    method: str = Field(default="isotonic", pattern="^(isotonic|platt)$")
+    activate: bool = False
+
+
+class CalibrationSelectRequest(BaseModel):
+    version: Optional[int] = Field(default=None, ge=1)  # None = report raw confidence only
+
+
 # ============================================================================
 # DEPENDENCY: EXTRACT & VALIDATE TENANT FROM API KEY
 # ============================================================================
@@ -114,6 +226,13 @@ async def get_current_key_metadata(authorization: Optional[str] = Header(None))
     return key_metadata
 
 
+async def get_optional_key_metadata(authorization: Optional[str] = Header(None)) -> Optional[Dict[str, Any]]:
+    """Key metadata when an Authorization header is sent, None for anonymous callers."""
+    if authorization is None:
+        return None
+    return await get_current_key_metadata(authorization)
+
+
 async def get_current_tenant(
     key_metadata: Dict[str, Any] = Depends(get_current_key_metadata)
 ) -> str:
@@ -127,6 +246,7 @@ _ACTION_SCOPES: Dict[str, Set[str]] = {
     "view_audit": {APIKeyScope.ADMIN.value, APIKeyScope.READ_METRICS.value},
     "validate": {APIKeyScope.ADMIN.value, APIKeyScope.VALIDATE_ONLY.value},
     "read_results": {APIKeyScope.ADMIN.value, APIKeyScope.READ_METRICS.value},
+    "report_executions": {APIKeyScope.ADMIN.value, APIKeyScope.EXECUTION_REPORTS.value},
 }
 
 
@@ -410,6 +530,7 @@ async def create_api_key_endpoint(
     - validate-only: Can only call /v2/validate
     - admin: Full API access
     - read-metrics: Read-only access to audit logs and health metrics
+    - execution-reports: Can only post broker execution reports (/v4/execution/reports)
     """
     try:
         existing_keys = list_api_keys(tenant_id)
@@ -603,6 +724,916 @@ async def verify_audit_chain_endpoint(
     }
 
 
+# ============================================================================
+# VALIDATION SETTINGS ENDPOINTS
+# ============================================================================
+
+@router.get("/tenants/{tenant_id}/divergence-backend")
+async def get_divergence_backend_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Return the tenant's default divergence backend (or ensemble) for hallucination scoring."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    settings = get_tenant_validation_settings(tenant_id)
+    try:
+        resolve_backend(name=settings.get("divergence_backend"), ensemble=settings.get("divergence_ensemble"))
+        setting_error = None
+    except ValueError as exc:
+        setting_error = str(exc)  # Validations fall back to the global default
+    return {
+        "tenant_id": tenant_id,
+        "divergence_backend": settings.get("divergence_backend"),
+        "divergence_ensemble": settings.get("divergence_ensemble"),
+        "resolved_backend": resolve_backend(tenant_settings=settings).name,
+        "setting_error": setting_error,
+    }
+
+
+@router.put("/tenants/{tenant_id}/divergence-backend")
+async def set_divergence_backend_endpoint(
+    tenant_id: str,
+    request: DivergenceBackendRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Set the tenant's default divergence backend or weighted ensemble.
+    
+    Requests can still override per call. Send both fields empty to revert to the
+    global default. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    try:
+        # Validate before persisting so a typo cannot break every later validation
+        backend = resolve_backend(name=request.divergence_backend, ensemble=request.divergence_ensemble)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    
+    settings = update_tenant_validation_settings(
+        tenant_id,
+        {
+            "divergence_backend": request.divergence_backend,
+            "divergence_ensemble": request.divergence_ensemble,
+        },
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="divergence_backend_changed",
+        target=backend.name,
+        details={
+            "divergence_backend": request.divergence_backend,
+            "divergence_ensemble": request.divergence_ensemble,
+        },
+    )
+    
+    return {
+        "tenant_id": tenant_id,
+        "divergence_backend": settings.get("divergence_backend"),
+        "divergence_ensemble": settings.get("divergence_ensemble"),
+        "resolved_backend": backend.name,
+    }
+
+
+@router.get("/tenants/{tenant_id}/drift-detectors")
+async def get_drift_detectors_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Return which drift detectors can raise alerts for the tenant and their parameter overrides."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    config = parse_detector_config(get_tenant_validation_settings(tenant_id).get(DETECTORS_SETTING))
+    return {"tenant_id": tenant_id, **config.to_dict()}
+
+
+@router.put("/tenants/{tenant_id}/drift-detectors")
+async def set_drift_detectors_endpoint(
+    tenant_id: str,
+    request: DriftDetectorsRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Choose the drift detectors (zscore, cusum, page_hinkley, ewma, ks, semantic, violation_rate) that raise alerts
+    and override their parameters. Send both fields empty to revert to the defaults (zscore, semantic, violation_rate).
+    This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    raw = request.model_dump(exclude_none=True) or None
+    try:
+        config = parse_detector_config(raw)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    
+    update_tenant_validation_settings(tenant_id, {DETECTORS_SETTING: config.to_dict() if raw else None})
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="drift_detectors_changed",
+        target=",".join(config.enabled),
+        details=config.to_dict(),
+    )
+    return {"tenant_id": tenant_id, **config.to_dict()}
+
+
+# ============================================================================
+# CONSTRAINT BUNDLE ENDPOINTS
+# ============================================================================
+
+def _bundle_summary(bundle: Dict[str, Any]) -> Dict[str, Any]:
+    return {key: value for key, value in bundle.items() if key != "definitions"}
+
+
+def _bundle_document(request: ConstraintBundleRequest) -> Dict[str, Any]:
+    document: Dict[str, Any] = {"rules": request.rules}
+    if request.relations is not None:
+        document["relations"] = request.relations
+    if request.temporal is not None:
+        document["temporal"] = request.temporal
+    if request.checks is not None:
+        document["checks"] = request.checks
+    return document
+
+
+@router.post("/tenants/{tenant_id}/constraint-bundles", status_code=201)
+async def create_constraint_bundle_endpoint(
+    tenant_id: str,
+    request: ConstraintBundleRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Store a new constraint bundle version for the tenant.
+    
+    Versions are assigned server-side and immutable; the bundle is not live until
+    activated. Rules, relations, temporal rules and checks use the same schema as app/core/constraint_rules.json and are
+    validated before storage. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    document = _bundle_document(request)
+    try:
+        parse_bundle_definitions(document, version="pending")
+    except ConstraintDefinitionError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    
+    bundle = create_constraint_bundle(
+        tenant_id,
+        document,
+        created_by=key_metadata["user_id"],
+        note=request.note,
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="constraint_bundle_created",
+        target=f"constraint-bundle:v{bundle['version']}",
+        details={"version": bundle["version"], "checksum": bundle["checksum"], "note": request.note},
+    )
+    return bundle
+
+
+@router.get("/tenants/{tenant_id}/constraint-bundles")
+async def list_constraint_bundles_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """List the tenant's bundle versions (without rule bodies) and the active version."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    bundles = list_constraint_bundles(tenant_id)
+    active = next((b["version"] for b in bundles if b["active"]), None)
+    return {
+        "tenant_id": tenant_id,
+        "active_version": active,
+        "bundles": [_bundle_summary(b) for b in bundles],
+    }
+
+
+@router.get("/tenants/{tenant_id}/constraint-bundles/{version}")
+async def get_constraint_bundle_endpoint(
+    tenant_id: str,
+    version: int,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Return one bundle version including its rules."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    bundle = get_constraint_bundle(tenant_id, version)
+    if bundle is None:
+        raise HTTPException(status_code=404, detail="Constraint bundle not found")
+    return bundle
+
+
+@router.post("/tenants/{tenant_id}/constraint-bundles/analyze")
+async def analyze_constraint_bundle_endpoint(
+    tenant_id: str,
+    request: BundleAnalysisRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Statically analyze a constraint bundle and/or trade policy.
+    
+    Analyzes the submitted `bundle`, else the stored `version`, else the tenant's active
+    bundle (or the shipped defaults). Supplying `policy` adds trade-policy checks and
+    bundle/policy cross-checks. Reports contradictions, unreachable rules, overlapping
+    aliases, missing regulatory mappings and unit mismatches.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    if request.bundle is not None and request.version is not None:
+        raise HTTPException(status_code=400, detail="Send either bundle or version, not both")
+    
+    if request.bundle is not None:
+        try:
+            definitions = parse_bundle_definitions(_bundle_document(request.bundle), version="pending")
+        except ConstraintDefinitionError as e:
+            raise HTTPException(status_code=400, detail=str(e))
+        analyzed = "submitted"
+    elif request.version is not None:
+        bundle = get_constraint_bundle(tenant_id, request.version)
+        if bundle is None:
+            raise HTTPException(status_code=404, detail="Constraint bundle not found")
+        definitions = resolve_constraint_definitions(bundle)
+        analyzed = f"v{request.version}"
+    else:
+        active = get_active_constraint_bundle(tenant_id)
+        definitions = resolve_constraint_definitions(active)
+        analyzed = f"v{active['version']}" if active else f"defaults {definitions.version}"
+    
+    report = analyze(definitions, request.policy)
+    return {"tenant_id": tenant_id, "bundle": analyzed, **report.to_dict()}
+
+
+@router.post("/tenants/{tenant_id}/constraint-bundles/{version}/activate")
+async def activate_constraint_bundle_endpoint(
+    tenant_id: str,
+    version: int,
+    force: bool = False,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Make a bundle version live for all later validations.
+    
+    The bundle is analyzed first; analyzer errors (e.g. contradictory rules) reject the
+    activation with 409 unless `force=true`. Warnings are returned with the result.
+    This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    bundle = get_constraint_bundle(tenant_id, version)
+    if bundle is None:
+        raise HTTPException(status_code=404, detail="Constraint bundle not found")
+    
+    report = analyze(resolve_constraint_definitions(bundle))
+    if not report.ok and not force:
+        raise HTTPException(
+            status_code=409,
+            detail={"message": "Constraint bundle failed analysis", "analysis": report.to_dict()},
+        )
+    
+    result = activate_constraint_bundle(tenant_id, version, actor_id=key_metadata["user_id"])
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="constraint_bundle_activated",
+        target=f"constraint-bundle:v{version}",
+        details={
+            **result,
+            "checksum": bundle["checksum"],
+            "analysis_errors": len(report.errors),
+            "analysis_warnings": len(report.warnings),
+            "forced": force and not report.ok,
+        },
+    )
+    return {"tenant_id": tenant_id, **result, "analysis": report.to_dict()}
+
+
+@router.post("/tenants/{tenant_id}/constraint-bundles/rollback")
+async def rollback_constraint_bundle_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Undo the latest activation, returning to the previously active bundle
+    (or the shipped defaults). This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    try:
+        result = rollback_constraint_bundle(tenant_id, actor_id=key_metadata["user_id"])
+    except LookupError as e:
+        raise HTTPException(status_code=409, detail=str(e))
+    
+    active_version = result["active_version"]
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="constraint_bundle_rolled_back",
+        target=f"constraint-bundle:v{active_version}" if active_version is not None else "constraint-bundle:defaults",
+        details=result,
+    )
+    return {"tenant_id": tenant_id, **result}
+
+
+# ============================================================================
+# CONFIDENCE CALIBRATION ENDPOINTS
+# ============================================================================
+
+@router.get("/tenants/{tenant_id}/confidence-calibrations")
+async def list_confidence_calibrations_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """List fitted calibration versions with their Brier scores and the one in use."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    return {
+        "tenant_id": tenant_id,
+        "active_version": get_tenant_validation_settings(tenant_id).get(CALIBRATION_SETTING),
+        "calibrations": list_confidence_calibrations(tenant_id),
+    }
+
+
+@router.post("/tenants/{tenant_id}/confidence-calibrations", status_code=201)
+async def fit_confidence_calibration_endpoint(
+    tenant_id: str,
+    request: CalibrationFitRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Fit a new calibration version on the tenant's labelled results (see /v3/feedback).
+    
+    With `activate=true` it is applied to later validations immediately.
+    This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    try:
+        calibration = fit_tenant_calibration(tenant_id, request.method, fitted_by=key_metadata["user_id"])
+    except CalibrationError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+    
+    if request.activate:
+        update_tenant_validation_settings(tenant_id, {CALIBRATION_SETTING: calibration["version"]})
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="confidence_calibration_fitted",
+        target=f"confidence-calibration:v{calibration['version']}",
+        details={
+            "method": request.method,
+            "sample_count": calibration["sample_count"],
+            "brier_raw": calibration["metrics"]["brier_raw"],
+            "brier_calibrated": calibration["metrics"]["brier_calibrated"],
+            "activated": request.activate,
+        },
+    )
+    return calibration
+
+
+@router.put("/tenants/{tenant_id}/confidence-calibration")
+async def select_confidence_calibration_endpoint(
+    tenant_id: str,
+    request: CalibrationSelectRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Choose the calibration version applied to later validations, or send
+    `version: null` to go back to the raw heuristic score. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    if request.version is not None and get_confidence_calibration(tenant_id, request.version) is None:
+        raise HTTPException(status_code=404, detail="Confidence calibration not found")
+    
+    previous_version = get_tenant_validation_settings(tenant_id).get(CALIBRATION_SETTING)
+    update_tenant_validation_settings(tenant_id, {CALIBRATION_SETTING: request.version})
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="confidence_calibration_selected",
+        target=(
+            f"confidence-calibration:v{request.version}" if request.version is not None
+            else "confidence-calibration:raw"
+        ),
+        details={"previous_version": previous_version, "active_version": request.version},
+    )
+    return {"tenant_id": tenant_id, "previous_version": previous_version, "active_version": request.version}
+
+
+# ============================================================================
+# SHADOW-MODE ENDPOINTS
+# ============================================================================
+
+@router.get("/tenants/{tenant_id}/shadow")
+async def list_shadow_candidates_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """List the candidate bundle and trade policy currently evaluated in shadow mode."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    return {"tenant_id": tenant_id, "candidates": list_shadow_candidates(tenant_id)}
+
+
+@router.put("/tenants/{tenant_id}/shadow/constraint-bundle")
+async def set_shadow_bundle_endpoint(
+    tenant_id: str,
+    request: ShadowBundleRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Evaluate a stored bundle version against live /v3 traffic without enforcing it.
+    
+    Replaces any bundle already in shadow mode. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    bundle = get_constraint_bundle(tenant_id, request.version)
+    if bundle is None:
+        raise HTTPException(status_code=404, detail="Constraint bundle not found")
+    
+    candidate = set_shadow_candidate(
+        tenant_id,
+        SHADOW_CONSTRAINT_BUNDLE,
+        {"version": request.version},
+        attached_by=key_metadata["user_id"],
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="shadow_candidate_attached",
+        target=f"constraint-bundle:v{request.version}",
+        details={"kind": SHADOW_CONSTRAINT_BUNDLE, "version": request.version, "checksum": bundle["checksum"]},
+    )
+    return candidate
+
+
+@router.put("/tenants/{tenant_id}/shadow/trade-policy")
+async def set_shadow_trade_policy_endpoint(
+    tenant_id: str,
+    request: TradePolicyConfig,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Evaluate a candidate trade policy against live pre-trade orders without enforcing it.
+    
+    Replaces any policy already in shadow mode. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    candidate = set_shadow_candidate(
+        tenant_id,
+        SHADOW_TRADE_POLICY,
+        request.model_dump(),
+        attached_by=key_metadata["user_id"],
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="shadow_candidate_attached",
+        target=f"trade-policy:{request.policy_version}",
+        details={"kind": SHADOW_TRADE_POLICY, "checksum": candidate["checksum"]},
+    )
+    return candidate
+
+
+@router.delete("/tenants/{tenant_id}/shadow/{kind}", status_code=204)
+async def clear_shadow_candidate_endpoint(
+    tenant_id: str,
+    kind: str,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Stop shadow evaluation for one kind (constraint_bundle or trade_policy).
+    
+    Recorded divergences are kept for reporting. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    if kind not in SHADOW_KINDS:
+        raise HTTPException(status_code=400, detail=f"kind must be one of {list(SHADOW_KINDS)}")
+    
+    if not clear_shadow_candidate(tenant_id, kind):
+        raise HTTPException(status_code=404, detail="No shadow candidate attached")
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="shadow_candidate_detached",
+        target=kind,
+        details={"kind": kind},
+    )
+    return {}
+
+
+# ============================================================================
+# TRADE POLICY REGISTRY ENDPOINTS
+# ============================================================================
+
+def _binding_target(account_id: Optional[str], strategy_id: Optional[str]) -> str:
+    return f"trade-policy-binding:{account_id or '*'}/{strategy_id or '*'}"
+
+
+@router.post("/tenants/{tenant_id}/trade-policies", status_code=201)
+async def create_trade_policy_endpoint(
+    tenant_id: str,
+    request: TradePolicyCreateRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Store a new version of a trade policy for the tenant.
+    
+    Versions are assigned per policy_id server-side and immutable; the policy is not
+    enforced until bound (or referenced by policy_id on a pre-trade request). Once a tenant
+    stores any policy, client-supplied policies only act as tightening overrides.
+    This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    stored = create_trade_policy(
+        tenant_id,
+        request.policy_id,
+        request.policy.model_dump(),
+        created_by=key_metadata["user_id"],
+        note=request.note,
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="trade_policy_created",
+        target=f"trade-policy:{stored['policy_id']}:v{stored['version']}",
+        details={"version": stored["version"], "checksum": stored["checksum"], "note": request.note},
+    )
+    return stored
+
+
+@router.get("/tenants/{tenant_id}/trade-policies")
+async def list_trade_policies_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """List every stored policy version and the current bindings."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    return {
+        "tenant_id": tenant_id,
+        "policies": list_trade_policies(tenant_id),
+        "bindings": list_trade_policy_bindings(tenant_id),
+    }
+
+
+@router.get("/tenants/{tenant_id}/trade-policies/{policy_id}")
+async def get_trade_policy_endpoint(
+    tenant_id: str,
+    policy_id: str,
+    version: Optional[int] = None,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Return one policy version (the latest unless `version` is given)."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    stored = get_trade_policy(tenant_id, policy_id, version)
+    if stored is None:
+        raise HTTPException(status_code=404, detail="Trade policy not found")
+    return stored
+
+
+@router.put("/tenants/{tenant_id}/trade-policy-bindings")
+async def bind_trade_policy_endpoint(
+    tenant_id: str,
+    request: TradePolicyBindingRequest,
+    force: bool = False,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Enforce a policy version for an account and/or strategy (omit both for the tenant default).
+    
+    The most specific binding applies to a pre-trade request. The policy is analyzed against
+    the active constraint bundle first; analyzer errors reject the binding with 409 unless
+    `force=true`. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    stored = get_trade_policy(tenant_id, request.policy_id, request.version)
+    if stored is None:
+        raise HTTPException(status_code=404, detail="Trade policy not found")
+    
+    definitions = resolve_constraint_definitions(get_active_constraint_bundle(tenant_id))
+    report = analyze(definitions, TradePolicyConfig(**stored["policy"]))
+    if not report.ok and not force:
+        raise HTTPException(
+            status_code=409,
+            detail={"message": "Trade policy failed analysis", "analysis": report.to_dict()},
+        )
+    
+    binding = bind_trade_policy(
+        tenant_id,
+        stored["policy_id"],
+        stored["version"],
+        account_id=request.account_id,
+        strategy_id=request.strategy_id,
+        bound_by=key_metadata["user_id"],
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="trade_policy_bound",
+        target=_binding_target(request.account_id, request.strategy_id),
+        details={
+            "policy_id": stored["policy_id"],
+            "version": stored["version"],
+            "checksum": stored["checksum"],
+            "analysis_errors": len(report.errors),
+            "analysis_warnings": len(report.warnings),
+            "forced": force and not report.ok,
+        },
+    )
+    return {**binding, "analysis": report.to_dict()}
+
+
+@router.delete("/tenants/{tenant_id}/trade-policy-bindings", status_code=204)
+async def unbind_trade_policy_endpoint(
+    tenant_id: str,
+    account_id: Optional[str] = None,
+    strategy_id: Optional[str] = None,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Remove the binding for exactly this account / strategy scope. Requests in that scope fall
+    back to the next less specific binding. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    if not unbind_trade_policy(tenant_id, account_id=account_id, strategy_id=strategy_id):
+        raise HTTPException(status_code=404, detail="No trade policy bound for this scope")
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="trade_policy_unbound",
+        target=_binding_target(account_id, strategy_id),
+        details={"account_id": account_id, "strategy_id": strategy_id},
+    )
+    return {}
+
+
+@router.get("/tenants/{tenant_id}/client-trade-policy")
+async def get_client_trade_policy_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Whether inline pre-trade policies are enforced as-is while the tenant stores none."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    enabled = bool(get_tenant_validation_settings(tenant_id).get(CLIENT_POLICY_SETTING))
+    return {"tenant_id": tenant_id, "enabled": enabled}
+
+
+@router.put("/tenants/{tenant_id}/client-trade-policy")
+async def set_client_trade_policy_endpoint(
+    tenant_id: str,
+    request: ClientTradePolicyRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Opt in (or back out) of enforcing the agent's inline `policy` as-is on
+    /v4/execution/pre-trade while the tenant has no stored trade policy. Off by default;
+    once a policy is stored the setting has no effect. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    update_tenant_validation_settings(tenant_id, {CLIENT_POLICY_SETTING: request.enabled or None})
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="client_trade_policy_changed",
+        target="client-trade-policy",
+        details={"enabled": request.enabled},
+    )
+    return {"tenant_id": tenant_id, "enabled": request.enabled}
+
+
+def _tenant_key(tenant_id: str, key_id: str) -> None:
+    if not any(key["key_id"] == key_id for key in list_api_keys(tenant_id)):
+        raise HTTPException(status_code=404, detail="API key not found")
+
+
+@router.get("/tenants/{tenant_id}/api-keys/{key_id}/trading-scope")
+async def get_trading_scope_endpoint(
+    tenant_id: str,
+    key_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """The account / strategy this key's pre-trade requests are bound by (null = tenant default)."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    _tenant_key(tenant_id, key_id)
+    scope = get_api_key_trading_scope(tenant_id, key_id)
+    return scope or {"tenant_id": tenant_id, "key_id": key_id, "account_id": None, "strategy_id": None}
+
+
+@router.put("/tenants/{tenant_id}/api-keys/{key_id}/trading-scope")
+async def set_trading_scope_endpoint(
+    tenant_id: str,
+    key_id: str,
+    request: TradingScopeRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Bind a key's pre-trade and basket requests to an account and/or strategy.
+    
+    The trade policy binding for that scope applies to every request made with the key;
+    requests naming another `account_id` / `strategy_id` are refused. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    _tenant_key(tenant_id, key_id)
+    scope = set_api_key_trading_scope(
+        tenant_id,
+        key_id,
+        account_id=request.account_id,
+        strategy_id=request.strategy_id,
+        assigned_by=key_metadata["user_id"],
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="api_key_trading_scope_set",
+        target=f"api-key:{key_id}",
+        details={"account_id": request.account_id, "strategy_id": request.strategy_id},
+    )
+    return scope
+
+
+@router.delete("/tenants/{tenant_id}/api-keys/{key_id}/trading-scope", status_code=204)
+async def clear_trading_scope_endpoint(
+    tenant_id: str,
+    key_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Return a key to the tenant default binding. This action is audited."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    if not clear_api_key_trading_scope(tenant_id, key_id):
+        raise HTTPException(status_code=404, detail="No trading scope assigned to this key")
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="api_key_trading_scope_cleared",
+        target=f"api-key:{key_id}",
+        details={},
+    )
+    return {}
+
+
+@router.get("/tenants/{tenant_id}/instruments")
+async def list_instruments_endpoint(
+    tenant_id: str,
+    current_tenant: str = Depends(get_current_tenant),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """List the tenant instrument reference used by sector and asset-class limits."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot access other tenants")
+    
+    return {"tenant_id": tenant_id, "instruments": list_instruments(tenant_id)}
+
+
+@router.put("/tenants/{tenant_id}/instruments")
+async def upsert_instruments_endpoint(
+    tenant_id: str,
+    request: InstrumentReferenceRequest,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """
+    Set the sector and asset class of one or more symbols, replacing earlier entries.
+    
+    Orders for a symbol without a sector (or asset class) are blocked by policies that set
+    sector (or asset-class) allocation limits. This action is audited.
+    """
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    symbols = [entry.symbol.strip().upper() for entry in request.instruments]
+    if len(set(symbols)) != len(symbols):
+        raise HTTPException(status_code=422, detail="Each symbol may appear only once")
+    
+    stored = upsert_instruments(
+        tenant_id,
+        [entry.model_dump() for entry in request.instruments],
+        updated_by=key_metadata["user_id"],
+    )
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="instruments_updated",
+        target="instrument-reference",
+        details={
+            "instruments": [
+                {"symbol": entry["symbol"], "sector": entry["sector"], "asset_class": entry["asset_class"]}
+                for entry in stored
+            ]
+        },
+    )
+    return {"tenant_id": tenant_id, "instruments": stored}
+
+
+@router.delete("/tenants/{tenant_id}/instruments/{symbol}", status_code=204)
+async def delete_instrument_endpoint(
+    tenant_id: str,
+    symbol: str,
+    current_tenant: str = Depends(get_current_tenant),
+    key_metadata: Dict[str, Any] = Depends(get_current_key_metadata),
+    _: bool = Depends(require_permission("manage_config"))
+):
+    """Remove a symbol from the instrument reference. This action is audited."""
+    if tenant_id != current_tenant:
+        raise HTTPException(status_code=403, detail="Cannot manage other tenants")
+    
+    if not delete_instrument(tenant_id, symbol):
+        raise HTTPException(status_code=404, detail="Instrument not found")
+    record_config_change(
+        tenant_id=tenant_id,
+        actor_id=key_metadata["user_id"],
+        event_type="instrument_removed",
+        target=f"instrument-reference:{symbol.strip().upper()}",
+        details={"symbol": symbol.strip().upper()},
+    )
+    return {}
+
+
 # ============================================================================
 # JWT AUTHENTICATION ENDPOINTS
 # ============================================================================
@@ -0,0 +1,110 @@
+#!/usr/bin/env python3
+"""
+Constraint/policy analyzer CLI - calls /v1/onboarding/tenants/{id}/constraint-bundles/analyze
+
+With --local the analysis runs in-process on the given files (no server or API key), which
+is what CI uses to reject contradictory bundles before they are uploaded.
+Exit codes: 0 = no errors, 1 = analyzer errors, 2+ = usage/connection failures.
+"""
+import argparse
+import json
+import sys
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+import requests
+
+BASE_URL = "http://localhost:8000"
+
+
+def _load(path: Optional[str]) -> Optional[Dict[str, Any]]:
+    if path is None:
+        return None
+    return json.loads(Path(path).read_text(encoding="utf-8"))
+
+
+def _print_findings(data: Dict[str, Any]) -> int:
+    for finding in data["findings"]:
+        print(f"{finding['severity'].upper():7} {finding['code']:27} {finding['target']}: {finding['message']}")
+    marker = "✓" if data["ok"] else "✗"
+    print(f"{marker} {data['errors']} error(s), {data['warnings']} warning(s)")
+    return 0 if data["ok"] else 1
+
+
+def analyze_local(bundle: Optional[Dict[str, Any]], policy: Optional[Dict[str, Any]]) -> int:
+    """Analyze files in-process (bundle defaults to the shipped definitions)."""
+    sys.path.insert(0, str(Path(__file__).resolve().parents[2]))
+    from app.core.constraint_rules import (
+        ConstraintDefinitionError,
+        get_constraint_definitions,
+        parse_bundle_definitions,
+    )
+    from app.core.policy_analyzer import analyze
+    from app.models import TradePolicyConfig
+
+    try:
+        definitions = (
+            parse_bundle_definitions(bundle, version="local") if bundle is not None
+            else get_constraint_definitions()
+        )
+    except ConstraintDefinitionError as e:
+        print(f"✗ Invalid bundle: {e}")
+        return 2
+    report = analyze(definitions, TradePolicyConfig(**policy) if policy is not None else None)
+    return _print_findings(report.to_dict())
+
+
+def analyze_remote(
+    tenant_id: str,
+    bundle: Optional[Dict[str, Any]],
+    version: Optional[int],
+    policy: Optional[Dict[str, Any]],
+    api_key: Optional[str] = None,
+) -> int:
+    """Analyze via the API (a stored version, the submitted bundle, or the active bundle)."""
+    try:
+        headers = {"Content-Type": "application/json"}
+        if api_key:
+            headers["Authorization"] = f"Bearer {api_key}"
+
+        payload = {"bundle": bundle, "version": version, "policy": policy}
+        response = requests.post(
+            f"{BASE_URL}/v1/onboarding/tenants/{tenant_id}/constraint-bundles/analyze",
+            headers=headers,
+            json={key: value for key, value in payload.items() if value is not None},
+            timeout=10,
+        )
+
+        if response.status_code == 200:
+            data = response.json()
+            print(f"Analyzed bundle: {data['bundle']}")
+            return _print_findings(data)
+        print(f"Error: HTTP {response.status_code}")
+        print(response.text)
+        return 3
+
+    except requests.exceptions.ConnectionError:
+        print("✗ Connection failed - is the server running?")
+        return 4
+    except Exception as e:
+        print(f"✗ Error: {e}")
+        return 5
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(description="Analyze a constraint bundle and/or trade policy")
+    parser.add_argument("tenant_id", nargs="?", help="Tenant to analyze against (not needed with --local)")
+    parser.add_argument("--bundle", help="Bundle JSON file ({rules, relations, temporal, checks})")
+    parser.add_argument("--version", type=int, help="Stored bundle version to analyze")
+    parser.add_argument("--policy", help="TradePolicyConfig JSON file")
+    parser.add_argument("--api-key", default=None)
+    parser.add_argument("--local", action="store_true", help="Analyze files in-process without the API")
+    args = parser.parse_args()
+
+    if args.local:
+        if args.version is not None:
+            parser.error("--version needs the API; pass --bundle with --local")
+        sys.exit(analyze_local(_load(args.bundle), _load(args.policy)))
+    if not args.tenant_id:
+        parser.error("tenant_id is required unless --local is given")
+    sys.exit(analyze_remote(args.tenant_id, _load(args.bundle), args.version, _load(args.policy), args.api_key))
@@ -0,0 +1,70 @@
+#!/usr/bin/env python3
+"""
+Confidence calibration CLI - offline re-fit against the configured database
+
+Fits isotonic or Platt calibration on a tenant's labelled /v3 results (SQLITE_DB_PATH),
+prints the raw and calibrated reliability diagrams with Brier scores, and stores the
+result as a new calibration version unless --dry-run is given.
+Exit codes: 0 = fitted, 1 = not enough labelled data, 2 = usage error.
+"""
+import argparse
+import sys
+from pathlib import Path
+
+
+def _print_diagram(title: str, diagram, brier: float) -> None:
+    from app.core.calibration import format_reliability_diagram
+
+    print(f"{title} (Brier {brier:.4f})")
+    print(format_reliability_diagram(diagram))
+    print()
+
+
+def calibrate(tenant_id: str, method: str, bins: int, min_samples: int, dry_run: bool, activate: bool) -> int:
+    sys.path.insert(0, str(Path(__file__).resolve().parents[2]))
+    from app.core.calibration import CALIBRATION_SETTING, CalibrationError, fit_tenant_calibration
+    from app.core.storage import init_db, update_tenant_validation_settings
+
+    init_db()
+    try:
+        calibration = fit_tenant_calibration(
+            tenant_id,
+            method,
+            fitted_by="ops-cli",
+            min_samples=min_samples,
+            bins=bins,
+            persist=not dry_run,
+        )
+    except CalibrationError as e:
+        print(f"✗ {e}")
+        return 1
+
+    metrics = calibration["metrics"]
+    print(f"Tenant {tenant_id}: {calibration['sample_count']} labelled results, safe rate {metrics['safe_rate']:.3f}")
+    print()
+    _print_diagram("Raw confidence", metrics["reliability_raw"], metrics["brier_raw"])
+    _print_diagram(f"Calibrated ({method})", metrics["reliability_calibrated"], metrics["brier_calibrated"])
+
+    if dry_run:
+        print("✓ Dry run - nothing stored")
+        return 0
+    print(f"✓ Stored calibration v{calibration['version']}")
+    if activate:
+        update_tenant_validation_settings(tenant_id, {CALIBRATION_SETTING: calibration["version"]})
+        print(f"✓ Activated v{calibration['version']} for {tenant_id}")
+    return 0
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser(description="Re-fit a tenant's confidence calibration offline")
+    parser.add_argument("tenant_id")
+    parser.add_argument("--method", choices=["isotonic", "platt"], default="isotonic")
+    parser.add_argument("--bins", type=int, default=10, help="Reliability diagram bins")
+    parser.add_argument("--min-samples", type=int, default=30)
+    parser.add_argument("--dry-run", action="store_true", help="Report only; do not store a version")
+    parser.add_argument("--activate", action="store_true", help="Apply the new version to later validations")
+    args = parser.parse_args()
+
+    if args.dry_run and args.activate:
+        parser.error("--activate cannot be combined with --dry-run")
+    sys.exit(calibrate(args.tenant_id, args.method, args.bins, args.min_samples, args.dry_run, args.activate))
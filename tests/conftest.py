@@ -55,3 +55,4 @@ def test_client():
     from app.main import app
 
     return TestClient(app)
+
@@ -0,0 +1,81 @@
+"""Shared builders for tests; fixtures stay in conftest.py."""
+
+import uuid
+
+from app.core import parallel_validation
+from app.core.constraint_rules import parse_constraint_definitions
+from app.core.storage import get_validation_result
+from app.models import AgentIntentRequest
+
+
+def leverage_bundle(limit, **sections):
+    """Constraint bundle with a single leverage_ratio rule capped at ``limit``."""
+    return {
+        "rules": [
+            {
+                "id": "leverage_limit",
+                "variables": ["leverage_ratio"],
+                "operator": "<=",
+                "limit": limit,
+                "unit": "x",
+                "severity_weight": 2.0,
+                "message": "Leverage limit breached: hard limit is {limit:g}x maximum",
+                "articles": ["EU-AIA-14-HumanOversight"],
+            }
+        ],
+        **sections,
+    }
+
+
+def leverage_definitions(**sections):
+    """Parsed definitions for a 10x leverage bundle plus extra sections (relations, temporal)."""
+    return parse_constraint_definitions({"version": "test", **leverage_bundle(10, **sections)})
+
+
+def validate_leverage(leverage, tenant_id="tenant-a", agent_id="agent-1"):
+    """Run a rebalance intent carrying ``leverage`` through validation and return the stored result."""
+    request_id = str(uuid.uuid4())
+    request = AgentIntentRequest(
+        agent_id=agent_id,
+        intent="rebalance",
+        tenant_id=tenant_id,
+        samples=["Hold the position"] * 3,
+        metrics={"leverage_ratio": leverage},
+    )
+    parallel_validation.run_parallel_validation(request_id, request)
+    return get_validation_result(request_id, tenant_id=tenant_id)
+
+
+def bootstrap_tenant_auth(client, scope="admin"):
+    """Onboard a tenant with an admin user over HTTP; returns (tenant, user, auth headers)."""
+    suffix = uuid.uuid4().hex[:12]
+    tenant_response = client.post(
+        "/v1/onboarding/tenants",
+        json={
+            "org_name": f"Tenant {suffix}",
+            "domain": f"tenant-{suffix}.example.com",
+            "support_tier": "standard",
+        },
+    )
+    assert tenant_response.status_code == 201
+    tenant = tenant_response.json()
+
+    user_response = client.post(
+        f"/v1/onboarding/tenants/{tenant['tenant_id']}/users",
+        json={"email": f"admin-{suffix}@example.com", "role": "admin", "mfa_enabled": True},
+    )
+    assert user_response.status_code == 201
+    user = user_response.json()
+
+    return tenant, user, issue_api_key(client, tenant["tenant_id"], user["user_id"], scope)
+
+
+def issue_api_key(client, tenant_id, user_id, scope, headers=None):
+    """Issue a key for ``user_id``; the first key of a tenant needs no ``headers``."""
+    response = client.post(
+        f"/v1/onboarding/tenants/{tenant_id}/api-keys",
+        json={"user_id": user_id, "scope": scope, "expires_in_days": 30},
+        headers=headers or {},
+    )
+    assert response.status_code == 201
+    return {"Authorization": f"Bearer {response.json()['api_key']}"}
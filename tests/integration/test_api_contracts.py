@@ -47,6 +47,15 @@ def bootstrap_tenant_auth(scope: str = "admin"):
     return tenant, {"Authorization": f"Bearer {api_key}"}
 
 
+def allow_client_trade_policy(tenant_id: str, headers: dict) -> None:
+    response = client.put(
+        f"/v1/onboarding/tenants/{tenant_id}/client-trade-policy",
+        headers=headers,
+        json={"enabled": True},
+    )
+    assert response.status_code == 200
+
+
 class TestValidationAPIContracts:
     """Test /v2/validate endpoint contract"""
     
@@ -377,6 +386,7 @@ class TestExecutionTradeGuard:
 
     def test_pre_trade_block_kill_switch(self):
         tenant, headers = bootstrap_tenant_auth(scope="admin")
+        allow_client_trade_policy(tenant["tenant_id"], headers)
         payload = {
             "idempotency_key": f"idem-{uuid.uuid4()}",
             "tenant_id": tenant["tenant_id"],
@@ -427,6 +437,7 @@ class TestExecutionTradeGuard:
 
     def test_pre_trade_modify_position_size(self):
         tenant, headers = bootstrap_tenant_auth(scope="admin")
+        allow_client_trade_policy(tenant["tenant_id"], headers)
         payload = {
             "idempotency_key": f"idem-{uuid.uuid4()}",
             "tenant_id": tenant["tenant_id"],
@@ -468,6 +479,7 @@ class TestExecutionTradeGuard:
 
     def test_pre_trade_decision_is_tenant_scoped(self):
         tenant1, headers1 = bootstrap_tenant_auth(scope="admin")
+        allow_client_trade_policy(tenant1["tenant_id"], headers1)
         _, headers2 = bootstrap_tenant_auth(scope="admin")
         payload = {
             "idempotency_key": f"idem-{uuid.uuid4()}",
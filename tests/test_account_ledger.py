@@ -0,0 +1,259 @@
+"""
+Test Suite: Server-Side Account Ledger
+Approved orders, fills and cancels maintain a ledger; pre-trade checks use the worse of it and the snapshot.
+"""
+
+import threading
+import time
+import uuid
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core.ledger import DISCREPANCY_EVENT, account_lock, reconcile_account, record_ledger_discrepancies
+from app.core.onboarding import list_api_keys
+from app.core.storage import (
+    get_account_ledger,
+    get_execution_decision,
+    get_ledger_order,
+    insert_execution_decision,
+    list_audit_events,
+    record_execution_report,
+    set_api_key_trading_scope,
+    update_tenant_validation_settings,
+)
+from app.core.trade_guard import evaluate_pre_trade
+from app.core.trade_policies import CLIENT_POLICY_SETTING
+from app.main import app
+from app.models import AccountSnapshot, ExecutionReportRequest, TradeOrder, TradePolicyConfig
+from tests.helpers import bootstrap_tenant_auth, issue_api_key
+
+
+client = TestClient(app)
+
+
+def _decide(decision_id, side="BUY", quantity=10.0, price=100.0, decision="ALLOW", account_id="acct-1", **kwargs):
+    return insert_execution_decision(
+        decision_id=decision_id,
+        tenant_id="tenant-a",
+        agent_id="agent-1",
+        decision=decision,
+        reason="test",
+        triggered_controls=[],
+        order={"symbol": "aapl", "side": side, "quantity": quantity, "price": price},
+        account={},
+        policy={},
+        modified_order=kwargs.get("modified_order"),
+        idempotency_key=f"key-{decision_id}",
+        account_id=account_id,
+    )
+
+
+def test_approved_orders_are_pending_until_filled_or_cancelled():
+    _decide("d-1", quantity=10.0)
+    _decide("d-2", side="SELL", quantity=4.0)
+    _decide("d-3", decision="BLOCK")
+
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {"AAPL": 6.0}
+
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=10.0, price=101.0)
+    record_execution_report("tenant-a", "exec-2", "d-2", "CANCEL")
+    ledger = get_account_ledger("tenant-a", "acct-1")
+
+    assert ledger["pending_orders"] == {}
+    assert ledger["positions"] == {"AAPL": {"quantity": 10.0, "avg_price": 101.0}}
+    assert get_ledger_order("tenant-a", "d-1")["status"] == "FILLED"
+    assert get_ledger_order("tenant-a", "d-2")["status"] == "CANCELLED"
+    assert get_ledger_order("tenant-a", "d-3") is None
+
+
+def test_modified_orders_hold_the_approved_quantity():
+    _decide(
+        "d-1",
+        decision="MODIFY",
+        modified_order={"symbol": "AAPL", "side": "BUY", "quantity": 3.0, "price": 100.0},
+    )
+
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {"AAPL": 3.0}
+
+
+def test_partial_fill_leaves_the_remainder_pending():
+    _decide("d-1", quantity=10.0)
+
+    record_execution_report("tenant-a", "exec-1", "d-1", "PARTIAL_FILL", quantity=4.0, price=100.0)
+    ledger = get_account_ledger("tenant-a", "acct-1")
+
+    assert ledger["pending_orders"] == {"AAPL": 6.0}
+    assert ledger["positions"]["AAPL"]["quantity"] == 4.0
+    assert ledger["gross_exposure"] == pytest.approx(1000.0)
+
+
+def test_reducing_fills_realize_pnl_at_average_cost():
+    _decide("d-1", quantity=10.0)
+    _decide("d-2", quantity=10.0)
+    _decide("d-3", side="SELL", quantity=15.0)
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=10.0, price=100.0)
+    record_execution_report("tenant-a", "exec-2", "d-2", "FILL", quantity=10.0, price=110.0)
+
+    report = record_execution_report("tenant-a", "exec-3", "d-3", "FILL", quantity=15.0, price=95.0)
+    ledger = get_account_ledger("tenant-a", "acct-1")
+
+    assert report["realized_pnl"] == pytest.approx(-150.0)
+    assert ledger["daily_pnl"] == pytest.approx(-150.0)
+    assert ledger["positions"]["AAPL"] == {"quantity": 5.0, "avg_price": pytest.approx(105.0)}
+
+
+def test_reports_are_idempotent_and_need_a_known_decision():
+    _decide("d-1", quantity=10.0)
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=5.0, price=100.0)
+
+    replay = record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=5.0, price=100.0)
+
+    assert replay["duplicate"]
+    assert get_account_ledger("tenant-a", "acct-1")["positions"]["AAPL"]["quantity"] == 5.0
+    with pytest.raises(KeyError):
+        record_execution_report("tenant-a", "exec-9", "unknown", "CANCEL")
+    with pytest.raises(KeyError):
+        record_execution_report("tenant-b", "exec-9", "d-1", "CANCEL")
+
+
+def test_ledgers_are_scoped_per_account():
+    _decide("d-1", account_id="acct-1")
+    _decide("d-2", account_id=None, quantity=2.0)
+
+    assert get_account_ledger("tenant-a", "acct-2")["pending_orders"] == {}
+    assert get_account_ledger("tenant-a")["pending_orders"] == {"AAPL": 2.0}
+
+
+def test_understated_snapshot_is_overridden_and_audited():
+    _decide("d-1", quantity=900.0)
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=900.0, price=100.0)
+    snapshot = AccountSnapshot(capital=1_000_000.0, open_positions={"aapl": 0.0, "MSFT": 50.0})
+
+    evaluated, record = reconcile_account("tenant-a", "acct-1", snapshot)
+    result = evaluate_pre_trade(
+        TradeOrder(symbol="AAPL", side="BUY", quantity=200.0, price=100.0),
+        evaluated,
+        TradePolicyConfig(abnormal_order_zscore=1000.0),
+    )
+
+    assert evaluated.open_positions == {"AAPL": 900.0, "MSFT": 50.0}
+    assert evaluated.current_gross_exposure == pytest.approx(90000.0)
+    assert {d["field"]: d["used"] for d in record["discrepancies"]} == {
+        "open_positions.AAPL": "ledger",
+        "open_positions.MSFT": "snapshot",
+        "current_gross_exposure": "ledger",
+    }
+    assert result["decision"] == "MODIFY"
+    assert result["modified_order"]["quantity"] == 100.0
+
+    record_ledger_discrepancies("d-2", "tenant-a", "agent-1", "acct-1", record["discrepancies"])
+    (event,) = [e for e in list_audit_events("tenant-a") if e["event_type"] == DISCREPANCY_EVENT]
+    assert "2 understated" in event["message"]
+
+
+def test_worse_daily_loss_wins_and_agreement_is_not_audited():
+    _decide("d-1", quantity=10.0)
+    _decide("d-2", side="SELL", quantity=10.0)
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=10.0, price=100.0)
+    record_execution_report("tenant-a", "exec-2", "d-2", "FILL", quantity=10.0, price=90.0)
+
+    evaluated, record = reconcile_account("tenant-a", "acct-1", AccountSnapshot(capital=10000.0, daily_pnl=-40.0))
+    assert evaluated.daily_pnl == pytest.approx(-100.0)
+
+    _, agreed = reconcile_account("tenant-a", "acct-1", AccountSnapshot(capital=10000.0, daily_pnl=-100.0))
+    assert agreed["discrepancies"] == []
+    assert record_ledger_discrepancies("d-3", "tenant-a", None, "acct-1", agreed["discrepancies"]) is None
+
+
+def test_decision_row_keeps_the_ledger_view():
+    evaluated, record = reconcile_account("tenant-a", "acct-1", AccountSnapshot(capital=1000.0))
+    _decide("d-1")
+    insert_execution_decision(
+        decision_id="d-2",
+        tenant_id="tenant-a",
+        agent_id=None,
+        decision="BLOCK",
+        reason="test",
+        triggered_controls=[],
+        order={},
+        account=evaluated.model_dump(mode="json"),
+        policy={},
+        modified_order=None,
+        idempotency_key="key-d-2",
+        account_id="acct-1",
+        ledger=record,
+    )
+
+    stored = get_execution_decision("d-2", "tenant-a")
+    assert (stored["account_id"], stored["ledger"]["discrepancies"]) == ("acct-1", [])
+
+
+def test_account_lock_keeps_concurrent_orders_from_sharing_headroom():
+    policy = TradePolicyConfig(max_position_size=15.0, abnormal_order_zscore=1000.0)
+    order = TradeOrder(symbol="AAPL", side="BUY", quantity=10.0, price=100.0)
+    decisions = []
+
+    def _submit(decision_id):
+        with account_lock("tenant-a", "acct-1"):
+            evaluated, _ = reconcile_account("tenant-a", "acct-1", AccountSnapshot(capital=1_000_000.0))
+            time.sleep(0.05)  # Widen the window between reading the ledger and storing the decision
+            result = evaluate_pre_trade(order, evaluated, policy)
+            _decide(decision_id, decision=result["decision"], modified_order=result["modified_order"])
+            decisions.append(result["decision"])
+
+    threads = [threading.Thread(target=_submit, args=(f"d-{index}",)) for index in range(2)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+
+    assert sorted(decisions) == ["ALLOW", "MODIFY"]
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {"AAPL": 15.0}
+
+
+def test_default_account_is_locked_too():
+    acquired = threading.Event()
+
+    def _contend():
+        with account_lock("tenant-a", ""):
+            acquired.set()
+
+    with account_lock("tenant-a", None):
+        thread = threading.Thread(target=_contend)
+        thread.start()
+        assert not acquired.wait(0.1)
+    thread.join()
+    assert acquired.is_set()
+
+
+def test_pre_trade_ledger_account_comes_from_the_api_key():
+    tenant, user, admin_headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    update_tenant_validation_settings(tenant_id, {CLIENT_POLICY_SETTING: True})
+    agent_headers = issue_api_key(client, tenant_id, user["user_id"], "validate-only", headers=admin_headers)
+    (agent_key,) = [key for key in list_api_keys(tenant_id) if key["scope"] == "validate-only"]
+    set_api_key_trading_scope(tenant_id, agent_key["key_id"], account_id="acct-1")
+
+    def _pre_trade(headers, **fields):
+        payload = {
+            "idempotency_key": f"order-{uuid.uuid4().hex[:12]}",
+            "tenant_id": tenant_id,
+            "order": {"symbol": "AAPL", "side": "BUY", "quantity": 10, "price": 100.0},
+            "account": {"capital": 1_000_000.0},
+            "policy": {"max_position_size": 100.0, "abnormal_order_zscore": 1000.0},
+            **fields,
+        }
+        return client.post("/v4/execution/pre-trade", json=payload, headers=headers)
+
+    assert _pre_trade(agent_headers).json()["decision"] == "ALLOW"
+    assert get_account_ledger(tenant_id, "acct-1")["pending_orders"] == {"AAPL": 10.0}
+    assert _pre_trade(agent_headers, account_id="acct-fresh").status_code == 403
+    assert _pre_trade(admin_headers, account_id="acct-fresh").status_code == 403
+    assert get_account_ledger(tenant_id, "acct-fresh")["pending_orders"] == {}
+
+
+def test_fill_reports_require_quantity_and_price():
+    with pytest.raises(ValueError):
+        ExecutionReportRequest(report_id="exec-1", decision_id="d-1", report_type="FILL", quantity=1.0)
+    ExecutionReportRequest(report_id="exec-1", decision_id="d-1", report_type="CANCEL")
@@ -0,0 +1,220 @@
+"""
+Test Suite: Atomic Multi-Leg and Basket Orders
+Legs share one projected exposure and succeed, scale down or block together.
+"""
+
+import uuid
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core.shadow import SHADOW_TRADE_POLICY, shadow_basket
+from app.core.storage import (
+    bind_trade_policy,
+    create_trade_policy,
+    get_account_ledger,
+    get_basket_decision,
+    get_execution_decision,
+    insert_basket_decision,
+    list_shadow_divergences,
+    set_shadow_candidate,
+)
+from app.core.trade_guard import BASKET_ALL_OR_NONE, BASKET_SCALE_DOWN, evaluate_basket, evaluate_pre_trade
+from app.main import app
+from app.models import AccountSnapshot, TradeOrder, TradePolicyConfig
+from tests.helpers import bootstrap_tenant_auth
+
+
+client = TestClient(app)
+
+POLICY = TradePolicyConfig(
+    max_position_size=1000.0,
+    max_capital_allocation_pct=0.5,
+    abnormal_order_zscore=1000.0,
+    min_lot_size=1.0,
+)
+
+
+def _leg(symbol, side="BUY", quantity=100.0, price=100.0):
+    return TradeOrder(symbol=symbol, side=side, quantity=quantity, price=price)
+
+
+def _store(basket_id, result, legs, idempotency_key="basket-0001"):
+    return insert_basket_decision(
+        basket_id=basket_id,
+        tenant_id="tenant-a",
+        agent_id="agent-1",
+        mode=BASKET_SCALE_DOWN,
+        decision=result["decision"],
+        reason=result["reason"],
+        triggered_controls=result["triggered_controls"],
+        scale=result["scale"],
+        legs=[
+            {**leg_result, "decision_id": f"{basket_id}-leg{i}", "order": leg.model_dump()}
+            for i, (leg, leg_result) in enumerate(zip(legs, result["legs"]))
+        ],
+        account={},
+        policy=POLICY.model_dump(),
+        idempotency_key=idempotency_key,
+        account_id="acct-1",
+    )
+
+
+def test_legs_that_pass_alone_can_breach_together():
+    account = AccountSnapshot(capital=40000.0)
+    legs = [_leg("AAPL"), _leg("MSFT", quantity=200.0)]
+
+    assert [evaluate_pre_trade(leg, account, POLICY)["decision"] for leg in legs] == ["ALLOW", "ALLOW"]
+
+    result = evaluate_basket(legs, account, POLICY, BASKET_ALL_OR_NONE)
+
+    assert result["decision"] == "BLOCK"
+    assert result["triggered_controls"] == ["basket_all_or_none", "basket_capital_allocation"]
+    assert all(leg["decision"] == "BLOCK" and leg["modified_order"] is None for leg in result["legs"])
+    assert "basket:" in result["legs"][0]["reason"]
+
+
+def test_scale_down_keeps_leg_ratios():
+    account = AccountSnapshot(capital=40000.0)
+    legs = [_leg("AAPL"), _leg("MSFT", quantity=200.0)]
+
+    result = evaluate_basket(legs, account, POLICY, BASKET_SCALE_DOWN)
+
+    assert result["decision"] == "MODIFY"
+    assert result["scale"] == pytest.approx(20000.0 / 30000.0)
+    assert [leg["modified_order"]["quantity"] for leg in result["legs"]] == [66.0, 133.0]
+
+
+def test_a_blocked_leg_blocks_every_leg_in_both_modes():
+    policy = POLICY.model_copy(update={"restricted_instruments": ["TSLA"]})
+    legs = [_leg("AAPL", quantity=1.0), _leg("TSLA", side="SELL", quantity=1.0)]
+
+    for mode in (BASKET_ALL_OR_NONE, BASKET_SCALE_DOWN):
+        result = evaluate_basket(legs, AccountSnapshot(capital=1_000_000.0), policy, mode)
+        assert [leg["decision"] for leg in result["legs"]] == ["BLOCK", "BLOCK"]
+        assert "restricted_instrument" in result["legs"][1]["triggered_controls"]
+
+
+def test_legs_on_one_symbol_net_against_the_position_cap():
+    account = AccountSnapshot(capital=1_000_000.0, open_positions={"AAPL": 900.0})
+    spread = [_leg("AAPL", quantity=50.0), _leg("AAPL", side="SELL", quantity=50.0)]
+    stacked = [_leg("AAPL", quantity=80.0), _leg("AAPL", quantity=80.0)]
+
+    assert evaluate_basket(spread, account, POLICY)["decision"] == "ALLOW"
+    scaled = evaluate_basket(stacked, account, POLICY, BASKET_SCALE_DOWN)
+    assert scaled["triggered_controls"] == ["basket_position_size"]
+    assert [leg["modified_order"]["quantity"] for leg in scaled["legs"]] == [50.0, 50.0]
+
+
+def test_legs_reducing_an_over_cap_position_are_not_flagged_by_the_basket_cap():
+    policy = POLICY.model_copy(update={"symbol_position_limits": {"AAPL": 100.0}})
+    account = AccountSnapshot(capital=1_000_000.0, open_positions={"AAPL": 300.0})
+    basket_caps = {"basket_position_size", "basket_symbol_position_limit"}
+
+    reducing = evaluate_basket([_leg("AAPL", side="SELL"), _leg("MSFT", quantity=10.0)], account, policy)
+    growing = evaluate_basket([_leg("AAPL", quantity=10.0), _leg("MSFT", quantity=10.0)], account, policy)
+
+    assert not basket_caps & set(reducing["triggered_controls"])
+    assert "beyond cap" not in reducing["reason"]
+    assert reducing["scale"] == 1.0
+    assert "basket_symbol_position_limit" in growing["triggered_controls"]
+
+
+def test_each_leg_counts_toward_the_frequency_cap():
+    policy = POLICY.model_copy(update={"max_orders_per_minute": 3})
+    legs = [_leg(symbol, quantity=1.0) for symbol in ("AAPL", "MSFT", "AMZN")]
+
+    result = evaluate_basket(legs, AccountSnapshot(capital=1_000_000.0, orders_last_minute=1), policy)
+
+    assert result["decision"] == "BLOCK"
+    assert "trading_frequency_cap" in result["legs"][2]["triggered_controls"]
+
+
+def test_scale_below_minimum_lot_blocks_the_basket():
+    account = AccountSnapshot(capital=1000.0)
+    legs = [_leg("AAPL", quantity=1.0), _leg("MSFT", quantity=9.0)]
+
+    result = evaluate_basket(legs, account, POLICY, BASKET_SCALE_DOWN)
+
+    assert result["decision"] == "BLOCK"
+    assert "invalid_modified_order" in result["triggered_controls"]
+
+
+def test_invalid_mode_is_rejected():
+    with pytest.raises(ValueError):
+        evaluate_basket([_leg("AAPL")], AccountSnapshot(capital=1000.0), POLICY, "best_effort")
+
+
+def test_basket_decision_links_its_legs_and_opens_ledger_orders_together():
+    legs = [_leg("AAPL"), _leg("MSFT", quantity=200.0)]
+    result = evaluate_basket(legs, AccountSnapshot(capital=40000.0), POLICY, BASKET_SCALE_DOWN)
+
+    stored = _store("basket-1", result, legs)
+    replay = _store("basket-2", result, legs)
+
+    assert replay["basket_id"] == "basket-1"
+    assert [leg["decision_id"] for leg in get_basket_decision("basket-1", "tenant-a")["legs"]] == [
+        "basket-1-leg0",
+        "basket-1-leg1",
+    ]
+    leg = get_execution_decision("basket-1-leg1", "tenant-a")
+    assert (leg["basket_id"], leg["leg_index"], leg["decision"]) == ("basket-1", 1, "MODIFY")
+    assert stored["scale"] == pytest.approx(2 / 3)
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {"AAPL": 66.0, "MSFT": 133.0}
+    assert get_basket_decision("basket-1", "tenant-b") is None
+
+
+def test_blocked_basket_opens_no_ledger_orders_and_rejects_key_reuse():
+    legs = [_leg("AAPL"), _leg("MSFT", quantity=200.0)]
+    blocked = evaluate_basket(legs, AccountSnapshot(capital=40000.0), POLICY, BASKET_ALL_OR_NONE)
+    _store("basket-1", blocked, legs)
+
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {}
+    with pytest.raises(ValueError):
+        _store("basket-2", blocked, legs[:1] + [_leg("MSFT", quantity=1.0)])
+
+
+def test_shadow_candidate_re_evaluates_the_whole_basket():
+    account = AccountSnapshot(capital=40000.0)
+    legs = [_leg("AAPL"), _leg("MSFT")]
+    live = evaluate_basket(legs, account, POLICY, BASKET_SCALE_DOWN)
+    set_shadow_candidate(
+        "tenant-a", SHADOW_TRADE_POLICY, POLICY.model_copy(update={"max_capital_allocation_pct": 0.25}).model_dump()
+    )
+
+    shadow_action = shadow_basket("basket-1", "tenant-a", "agent-1", legs, account, BASKET_SCALE_DOWN, live)
+
+    assert (live["decision"], shadow_action) == ("ALLOW", "MODIFY")
+    (divergence,) = list_shadow_divergences("tenant-a", kind=SHADOW_TRADE_POLICY)
+    assert divergence["reference_id"] == "basket-1"
+    assert divergence["shadow"]["triggered_controls"] == ["basket_capital_allocation"]
+
+
+def test_basket_endpoint_approves_and_scales_against_the_bound_policy():
+    tenant, _, headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    create_trade_policy(tenant_id, "desk", POLICY.model_dump())
+    bind_trade_policy(tenant_id, "desk", 1)
+
+    def _basket(legs, capital, mode=BASKET_ALL_OR_NONE):
+        payload = {
+            "idempotency_key": f"basket-{uuid.uuid4().hex[:12]}",
+            "tenant_id": tenant_id,
+            "legs": [leg.model_dump(mode="json") for leg in legs],
+            "mode": mode,
+            "account": {"capital": capital},
+        }
+        response = client.post("/v4/execution/basket", json=payload, headers=headers)
+        assert response.status_code == 200
+        return response.json()
+
+    scaled = _basket([_leg("AAPL"), _leg("MSFT", quantity=200.0)], 40000.0, BASKET_SCALE_DOWN)
+    assert (scaled["decision"], scaled["policy_id"], scaled["policy_version"]) == ("MODIFY", "desk", 1)
+    assert scaled["scale"] == pytest.approx(20000.0 / 30000.0)
+    assert [leg["modified_order"]["quantity"] for leg in scaled["legs"]] == [66.0, 133.0]
+    assert get_basket_decision(scaled["basket_id"], tenant_id)["decision"] == "MODIFY"
+
+    approved = _basket([_leg("AAPL", quantity=1.0), _leg("MSFT", quantity=2.0)], 1_000_000.0)
+    assert approved["decision"] == "ALLOW"
+    assert [leg["decision"] for leg in approved["legs"]] == ["ALLOW", "ALLOW"]
+    assert get_account_ledger(tenant_id)["pending_orders"] == {"AAPL": 67.0, "MSFT": 135.0}
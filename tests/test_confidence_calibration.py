@@ -0,0 +1,184 @@
+"""
+Test Suite: Confidence Calibration
+Raw heuristic confidence is mapped to the observed safe rate from labelled outcomes.
+"""
+
+import uuid
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core import parallel_validation
+from app.core.calibration import (
+    CALIBRATION_SETTING,
+    CalibrationError,
+    CalibrationModel,
+    active_calibration,
+    brier_score,
+    fit_isotonic,
+    fit_platt,
+    fit_tenant_calibration,
+    reliability_diagram,
+)
+from app.core.confidence import calculate_confidence
+from app.core.storage import (
+    get_validation_result,
+    insert_validation_result,
+    list_confidence_calibrations,
+    update_tenant_validation_settings,
+    upsert_validation_feedback,
+)
+from app.main import app
+from app.models import AgentIntentRequest
+from tests.helpers import bootstrap_tenant_auth
+
+
+client = TestClient(app)
+
+
+def _labelled_result(tenant_id, raw_score, outcome, legacy=False):
+    request_id = str(uuid.uuid4())
+    confidence = {"score": raw_score, "risk_level": "GREEN", "explanation": ""}
+    if not legacy:
+        confidence.update(raw_score=raw_score, calibrated_score=None, calibration_version=None)
+    insert_validation_result(
+        request_id=request_id,
+        tenant_id=tenant_id,
+        status_code=200,
+        action="ALLOW",
+        divergence_score=round(1 - raw_score, 3),
+        violations=[],
+        confidence=confidence,
+        severity={},
+        drift={},
+        recommendation="",
+        context_reset=False,
+        latency_ms=1.0,
+    )
+    upsert_validation_feedback(
+        tenant_id=tenant_id,
+        reference_type="validation",
+        reference_id=request_id,
+        agent_id=None,
+        action="ALLOW",
+        outcome=outcome,
+        rule_ids=[],
+        divergence_score=round(1 - raw_score, 3),
+    )
+
+
+def _overconfident_history(tenant_id="tenant-a"):
+    # The heuristic says 0.9 but only half of those actions were safe
+    for i in range(10):
+        _labelled_result(tenant_id, 0.9, "safe" if i % 2 else "unsafe", legacy=i < 4)
+        _labelled_result(tenant_id, 0.3, "unsafe")
+
+
+def test_isotonic_fit_is_monotonic_and_pools_violators():
+    model = fit_isotonic([0.1, 0.2, 0.3, 0.4, 0.5, 0.6], [0, 1, 0, 1, 1, 1])
+
+    assert model.parameters["y"] == sorted(model.parameters["y"])
+    assert model.predict(0.25) == pytest.approx(0.5)
+    assert model.predict(0.0) == 0.0
+    assert model.predict(0.9) == 1.0
+
+
+def test_platt_fit_orders_probabilities_and_stays_finite_when_separable():
+    model = fit_platt([0.1, 0.2, 0.3, 0.7, 0.8, 0.9], [0, 0, 0, 1, 1, 1])
+
+    assert model.parameters["slope"] > 0
+    assert 0.0 < model.predict(0.1) < 0.5 < model.predict(0.9) < 1.0
+
+
+def test_brier_and_reliability_diagram():
+    probabilities = [0.05, 0.15, 0.95, 0.95]
+    labels = [0, 0, 1, 0]
+
+    assert brier_score(probabilities, labels) == pytest.approx((0.05**2 + 0.15**2 + 0.05**2 + 0.95**2) / 4)
+    diagram = reliability_diagram(probabilities, labels, bins=10)
+    assert [(row["lower"], row["count"], row["observed_rate"]) for row in diagram] == [
+        (0.0, 1, 0.0), (0.1, 1, 0.0), (0.9, 2, 0.5)
+    ]
+
+
+def test_calculate_confidence_reports_raw_and_calibrated_values():
+    calibration = CalibrationModel(method="isotonic", parameters={"x": [0.0, 1.0], "y": [0.0, 0.5]}, version=3)
+
+    confidence = calculate_confidence(0.1, [], calibration)
+
+    assert (confidence.raw_score, confidence.calibrated_score, confidence.score) == (0.9, 0.45, 0.45)
+    assert confidence.calibration_version == 3
+    assert confidence.risk_level == "RED"
+    uncalibrated = calculate_confidence(0.1, [])
+    assert (uncalibrated.score, uncalibrated.raw_score, uncalibrated.calibrated_score) == (0.9, 0.9, None)
+
+
+@pytest.mark.parametrize("method", ["isotonic", "platt"])
+def test_tenant_fit_is_versioned_and_improves_brier(method):
+    _overconfident_history()
+
+    first = fit_tenant_calibration("tenant-a", method, min_samples=20)
+    second = fit_tenant_calibration("tenant-a", method, min_samples=20)
+
+    assert (first["version"], second["version"]) == (1, 2)
+    assert first["sample_count"] == 20
+    assert first["metrics"]["brier_calibrated"] < first["metrics"]["brier_raw"]
+    assert [c["version"] for c in list_confidence_calibrations("tenant-a")] == [1, 2]
+
+
+def test_dry_run_does_not_store_a_version():
+    _overconfident_history()
+
+    report = fit_tenant_calibration("tenant-a", min_samples=20, persist=False)
+
+    assert report["version"] is None
+    assert list_confidence_calibrations("tenant-a") == []
+
+
+def test_fit_needs_enough_labels_of_both_outcomes():
+    for _ in range(5):
+        _labelled_result("tenant-a", 0.9, "safe")
+
+    with pytest.raises(CalibrationError):
+        fit_tenant_calibration("tenant-a", min_samples=10)
+    with pytest.raises(CalibrationError):
+        fit_tenant_calibration("tenant-a", min_samples=5)
+    with pytest.raises(CalibrationError):
+        fit_tenant_calibration("tenant-a", method="bayesian", min_samples=1)
+
+
+def test_selected_calibration_is_applied_to_new_validations_for_that_tenant_only():
+    _overconfident_history()
+    calibration = fit_tenant_calibration("tenant-a", "isotonic", min_samples=20)
+    assert active_calibration("tenant-a") is None
+
+    update_tenant_validation_settings("tenant-a", {CALIBRATION_SETTING: calibration["version"]})
+
+    def _validate(tenant_id):
+        request_id = str(uuid.uuid4())
+        request = AgentIntentRequest(
+            agent_id="agent-1", intent="rebalance", tenant_id=tenant_id, samples=["Hold the position"] * 3
+        )
+        parallel_validation.run_parallel_validation(request_id, request)
+        return get_validation_result(request_id, tenant_id=tenant_id)["confidence"]
+
+    calibrated = _validate("tenant-a")
+    assert calibrated["calibration_version"] == 1
+    assert calibrated["raw_score"] > calibrated["calibrated_score"] == calibrated["score"]
+    assert _validate("tenant-b")["calibrated_score"] is None
+
+
+def test_v2_applies_the_callers_selected_calibration():
+    tenant, _, headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    _overconfident_history(tenant_id)
+    calibration = fit_tenant_calibration(tenant_id, "isotonic", min_samples=20)
+    update_tenant_validation_settings(tenant_id, {CALIBRATION_SETTING: calibration["version"]})
+    payload = {"samples": ["Hold the position"] * 3}
+
+    calibrated = client.post("/v2/validate", json=payload, headers=headers).json()["confidence"]
+    anonymous = client.post("/v2/validate", json=payload).json()["confidence"]
+
+    assert calibrated["calibration_version"] == 1
+    assert calibrated["raw_score"] > calibrated["calibrated_score"] == calibrated["score"]
+    assert anonymous["calibrated_score"] is None
@@ -0,0 +1,86 @@
+"""
+Test Suite: Per-Tenant Constraint Bundles
+Immutable bundle versions, activate/rollback, and the applied version on stored results.
+"""
+
+import pytest
+
+from app.core.constraint_rules import (
+    ConstraintDefinitionError,
+    parse_bundle_definitions,
+    resolve_constraint_definitions,
+)
+from app.core.storage import (
+    activate_constraint_bundle,
+    create_constraint_bundle,
+    get_active_constraint_bundle,
+    get_constraint_bundle,
+    list_audit_events,
+    list_constraint_bundles,
+    rollback_constraint_bundle,
+)
+from tests.helpers import leverage_bundle, validate_leverage
+
+
+def test_versions_are_assigned_per_tenant_and_immutable():
+    first = create_constraint_bundle("tenant-a", leverage_bundle(5), created_by="user-1")
+    second = create_constraint_bundle("tenant-a", leverage_bundle(4))
+    other = create_constraint_bundle("tenant-b", leverage_bundle(8))
+
+    assert (first["version"], second["version"], other["version"]) == (1, 2, 1)
+    assert get_constraint_bundle("tenant-a", 1)["definitions"] == leverage_bundle(5)
+    assert first["checksum"] != second["checksum"]
+    assert [b["active"] for b in list_constraint_bundles("tenant-a")] == [False, False]
+
+
+def test_tenant_bundle_limits_apply_and_version_is_recorded():
+    assert validate_leverage(7.0, tenant_id="tenant-a")["constraint_bundle_version"] is None
+
+    create_constraint_bundle("tenant-a", leverage_bundle(5))
+    activate_constraint_bundle("tenant-a", 1, actor_id="user-1")
+
+    blocked = validate_leverage(7.0, tenant_id="tenant-a")
+    assert blocked["status_code"] == 424
+    assert blocked["constraint_bundle_version"] == 1
+    assert blocked["violations"] == ["Leverage limit breached: hard limit is 5x maximum"]
+    (event,) = [e for e in list_audit_events("tenant-a") if e["event_type"] == "EXECUTION_BLOCKED_424"]
+    assert "Constraint bundle: v1." in event["message"]
+
+    # Other tenants keep the shipped defaults
+    assert validate_leverage(7.0, tenant_id="tenant-b")["status_code"] != 424
+
+
+def test_rollback_returns_to_previous_activation_then_defaults():
+    create_constraint_bundle("tenant-a", leverage_bundle(5))
+    create_constraint_bundle("tenant-a", leverage_bundle(4))
+    activate_constraint_bundle("tenant-a", 1)
+    assert activate_constraint_bundle("tenant-a", 2) == {"previous_version": 1, "active_version": 2}
+
+    assert rollback_constraint_bundle("tenant-a") == {"previous_version": 2, "active_version": 1}
+    assert get_active_constraint_bundle("tenant-a")["version"] == 1
+    assert rollback_constraint_bundle("tenant-a") == {"previous_version": 1, "active_version": None}
+    assert get_active_constraint_bundle("tenant-a") is None
+
+    with pytest.raises(LookupError):
+        rollback_constraint_bundle("tenant-a")
+
+
+def test_activating_unknown_version_is_rejected():
+    with pytest.raises(KeyError):
+        activate_constraint_bundle("tenant-a", 99)
+
+
+def test_invalid_bundle_is_rejected_before_storage():
+    bundle = leverage_bundle(5)
+    bundle["rules"][0]["operator"] = "=>"
+    with pytest.raises(ConstraintDefinitionError):
+        parse_bundle_definitions(bundle, version="pending")
+
+
+def test_bundle_without_checks_inherits_builtin_tags():
+    stored = {"tenant_id": "tenant-a", "version": 1, "definitions": leverage_bundle(5)}
+    definitions = resolve_constraint_definitions(stored)
+
+    assert definitions.version == "1"
+    assert definitions.articles_for("Balance invariant breached") == ["EU-AIA-12-RecordKeeping"]
+    assert definitions.rule_for("pressure") is None
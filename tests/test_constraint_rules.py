@@ -0,0 +1,116 @@
+"""
+Test Suite: Declarative Constraint Definitions
+Every shipped rule fires at its limit, renders its message and carries its regulatory tags.
+"""
+
+import copy
+import json
+
+import pytest
+
+from app.core.compliance import map_violations_to_articles
+from app.core.constraint_rules import (
+    DEFAULT_DEFINITIONS_PATH,
+    ConstraintDefinitionError,
+    get_constraint_definitions,
+    load_constraint_definitions,
+    parse_constraint_definitions,
+)
+from app.core.physical_validator import PhysicalValidator
+from app.core.severity import calculate_violation_severity
+
+
+DEFINITIONS = get_constraint_definitions()
+RULES = list(DEFINITIONS.rules)
+
+_STEP = 1.0
+
+
+def _beyond(rule):
+    """A value on the wrong side of the rule's limit."""
+    if rule.operator in {"<", "<="}:
+        return rule.limit + _STEP
+    if rule.operator in {">", ">="}:
+        return rule.limit - _STEP
+    if rule.operator == "==":
+        return rule.limit + _STEP
+    return rule.limit
+
+
+def _valid_document():
+    with open(DEFAULT_DEFINITIONS_PATH, encoding="utf-8") as handle:
+        return json.load(handle)
+
+
+@pytest.mark.parametrize("rule", RULES)
+def test_rule_allows_value_at_limit_and_blocks_beyond(rule):
+    validator = PhysicalValidator()
+    variable = rule.variables[0]
+
+    if rule.operator in {"<=", ">=", "=="}:
+        ok, reason = validator.validate(variable, rule.limit)
+        assert ok and reason is None
+
+    ok, reason = validator.validate(variable, _beyond(rule))
+    assert not ok
+    assert reason == rule.render(variable, _beyond(rule))
+    assert sorted(map_violations_to_articles([reason])) == sorted(set(rule.articles))
+    assert calculate_violation_severity(reason, 0.0).weight >= rule.severity_weight
+
+
+@pytest.mark.parametrize("rule", RULES)
+def test_every_alias_resolves_to_its_rule(rule):
+    for alias in rule.variables:
+        assert DEFINITIONS.rule_for(alias.upper()) is rule
+
+
+def test_unknown_variable_passes():
+    assert PhysicalValidator().validate("humidity", -5.0) == (True, None)
+
+
+@pytest.mark.parametrize("check", list(DEFINITIONS.checks))
+def test_checks_are_tagged_by_prefix(check):
+    violation = f"{check.prefix}: example"
+    assert sorted(map_violations_to_articles([violation])) == sorted(set(check.articles))
+    assert DEFINITIONS.severity_weight_for(violation) == check.severity_weight
+
+
+def test_untagged_violation_falls_back_to_default_articles():
+    assert map_violations_to_articles(["Something unexpected"]) == [
+        "CA-SB243-Transparency",
+        "EU-AIA-12-RecordKeeping",
+    ]
+
+
+@pytest.mark.parametrize(
+    "mutate,error",
+    [
+        (lambda doc: doc["rules"][0].update(operator="=>"), "operator"),
+        (lambda doc: doc["rules"][0].update(limit="ten"), "limit"),
+        (lambda doc: doc["rules"][0].update(severity_weight=5), "severity_weight"),
+        (lambda doc: doc["rules"][0].update(message="Breached {threshold}"), "placeholders"),
+        (lambda doc: doc["rules"][0].update(message="{value} too low"), "literal text"),
+        (lambda doc: doc["rules"][0].update(articles=["not a tag"]), "article"),
+        (lambda doc: doc["rules"][1].update(variables=list(doc["rules"][0]["variables"][:1])), "more than one rule"),
+        (lambda doc: doc["rules"][1].update(id=doc["rules"][0]["id"]), "unique"),
+        (lambda doc: doc.update(rules=[]), "rules"),
+    ],
+)
+def test_invalid_definitions_are_rejected_at_load(mutate, error):
+    document = copy.deepcopy(_valid_document())
+    mutate(document)
+    with pytest.raises(ConstraintDefinitionError) as excinfo:
+        parse_constraint_definitions(document)
+    assert error in str(excinfo.value)
+
+
+def test_definitions_load_from_custom_file(tmp_path):
+    document = _valid_document()
+    document["rules"][2]["limit"] = 5
+    path = tmp_path / "constraints.json"
+    path.write_text(json.dumps(document), encoding="utf-8")
+
+    definitions = load_constraint_definitions(path)
+    ok, reason = PhysicalValidator(definitions).validate("leverage_ratio", 6.0)
+    assert not ok
+    assert "5x" in reason
@@ -0,0 +1,115 @@
+"""
+Test Suite: Decision Engine Conformance
+The same payload must yield the same verdict on /v1/validate, /v2/validate and /v3/intent.
+"""
+
+import uuid
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core import parallel_validation
+from app.core.decision_engine import DECISION_TABLE_VERSION, decide
+from app.core.storage import activate_constraint_bundle, create_constraint_bundle
+from app.main import app
+from app.models import AgentIntentRequest
+from tests.helpers import bootstrap_tenant_auth, leverage_bundle
+
+
+client = TestClient(app)
+
+
+CASES = [
+    (
+        "clean",
+        {"samples": ["Hold the position"] * 3},
+        ("ALLOW", 200),
+    ),
+    (
+        "hard_violation",
+        {"samples": ["Hold the position"] * 3, "physics": {"energy_in": 100, "energy_out": 150}},
+        ("BLOCK", 424),
+    ),
+    (
+        "minor_violation_still_blocks",
+        {"samples": ["Hold the position"] * 3, "metrics": {"leverage_ratio": 10.5}},
+        ("BLOCK", 424),
+    ),
+    (
+        "high_divergence",
+        {
+            "samples": [
+                "The velocity is 10 m/s due to Newton's second law.",
+                "Quantum entanglement causes instantaneous communication.",
+                "Photosynthesis converts sunlight into chemical energy.",
+            ]
+        },
+        ("REVIEW", 400),
+    ),
+    (
+        "numeric_disagreement",
+        {"samples": ["Buy 100 AAPL", "Buy 100 AAPL", "Buy 1000 AAPL"]},
+        ("BLOCK", 424),
+    ),
+]
+
+
+def _v1_verdict(payload):
+    response = client.post("/v1/validate", json=payload)
+    body = response.json()
+    if response.status_code == 200:
+        return (body["action"], 200), body["decision_table_version"]
+    detail = body["detail"]
+    return (detail["action"], detail["decision_status_code"]), detail["decision_table_version"]
+
+
+def _v2_verdict(payload):
+    body = client.post("/v2/validate", json=payload).json()
+    return (body["action"], body["status_code"]), body["decision_table_version"]
+
+
+def _v3_verdict(payload):
+    request = AgentIntentRequest(agent_id="conformance-agent", intent="conformance", **payload)
+    result = parallel_validation.run_parallel_validation(str(uuid.uuid4()), request)
+    return (result["action"], result["status_code"]), result["decision_table_version"]
+
+
+@pytest.mark.parametrize("name,payload,expected", CASES)
+def test_endpoints_agree_on_verdict(monkeypatch, name, payload, expected):
+    # Latency is only a tie-breaker for otherwise-ALLOW verdicts; keep CI timing out of it
+    monkeypatch.setattr(parallel_validation, "LATENCY_GUARD_SECONDS", 60.0)
+
+    verdicts = {
+        "v1": _v1_verdict(payload),
+        "v2": _v2_verdict(payload),
+        "v3": _v3_verdict(payload),
+    }
+
+    for endpoint, (verdict, version) in verdicts.items():
+        assert verdict == expected, f"{name}: {endpoint} returned {verdict}"
+        assert version == DECISION_TABLE_VERSION
+
+
+def test_authenticated_v1_and_v2_apply_tenant_bundle():
+    tenant, _, headers = bootstrap_tenant_auth(client)
+    create_constraint_bundle(tenant["tenant_id"], leverage_bundle(5))
+    activate_constraint_bundle(tenant["tenant_id"], 1)
+    payload = {"samples": ["Hold the position"] * 3, "metrics": {"leverage_ratio": 7.0}}
+
+    assert client.post("/v1/validate", json=payload).status_code == 200
+    assert client.post("/v1/validate", json=payload, headers=headers).status_code == 424
+
+    anonymous = client.post("/v2/validate", json=payload).json()
+    tenant_scoped = client.post("/v2/validate", json=payload, headers=headers).json()
+    assert (anonymous["action"], tenant_scoped["action"]) == ("ALLOW", "BLOCK")
+    assert "5x" in tenant_scoped["violations"][0]
+
+
+def test_violation_outranks_latency_guard():
+    decision = decide(0.0, ["Balance invariant breached"], latency_exceeded=True)
+    assert (decision.action, decision.status_code, decision.rule) == ("BLOCK", 424, "hard_violation")
+
+
+def test_latency_guard_cautions_clean_verdict():
+    decision = decide(0.0, [], latency_exceeded=True)
+    assert (decision.action, decision.status_code) == ("CAUTION", 202)
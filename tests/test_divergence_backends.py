@@ -0,0 +1,115 @@
+"""
+Test Suite: Divergence Backends
+Registry lookup, ensembles, numeric claims, fallback and tenant defaults.
+"""
+
+import pytest
+from pydantic import ValidationError
+
+from app.core import divergence_backends
+from app.core.divergence_backends import (
+    EnsembleBackend,
+    UnknownBackendError,
+    get_backend,
+    list_backends,
+    register_backend,
+    resolve_backend,
+)
+from app.core.entropy import semantic_entropy
+from app.core.storage import get_tenant_validation_settings, update_tenant_validation_settings
+from app.models import ValidationRequest
+
+
+class _BrokenBackend:
+    name = "broken-test"
+    version = "0"
+    cluster_threshold = 0.5
+
+    def similarity_matrix(self, responses):
+        raise RuntimeError("model not installed")
+
+
+def test_builtin_backends_are_registered():
+    assert {"lexical-js", "minilm", "onnx-minilm", "numeric-claims"} <= set(list_backends())
+
+
+def test_unknown_backend_is_rejected():
+    with pytest.raises(UnknownBackendError):
+        resolve_backend(name="does-not-exist")
+
+
+def test_result_records_backend_name_and_version():
+    result = semantic_entropy(["Buy 100 AAPL"] * 3, backend=get_backend("lexical-js"))
+    assert result.backend == "lexical-js"
+    assert result.backend_version == "1.0"
+
+
+def test_numeric_backend_splits_disagreeing_quantities():
+    result = semantic_entropy(
+        ["Buy 100 AAPL", "Buy 100 AAPL", "Buy 1000 AAPL"],
+        backend=get_backend("numeric-claims"),
+    )
+    assert result.clusters[0] == result.clusters[1]
+    assert result.clusters[2] != result.clusters[0]
+
+
+def test_ensemble_weights_are_normalized_and_versioned():
+    backend = resolve_backend(ensemble={"lexical-js": 3.0, "numeric-claims": 1.0})
+    assert isinstance(backend, EnsembleBackend)
+    assert [round(weight, 2) for _, weight in backend.members] == [0.75, 0.25]
+    assert "lexical-js@1.0" in backend.version and "numeric-claims@1.1" in backend.version
+
+    result = semantic_entropy(["Sell 50 MSFT"] * 3, backend=backend)
+    assert result.score == 0.0
+    assert result.backend == "ensemble"
+
+
+@pytest.fixture
+def isolated_registry(monkeypatch):
+    """Backends registered by a test are dropped afterwards."""
+    monkeypatch.setattr(divergence_backends, "_FACTORIES", dict(divergence_backends._FACTORIES))
+    monkeypatch.setattr(divergence_backends, "_INSTANCES", dict(divergence_backends._INSTANCES))
+
+
+def test_unavailable_backend_falls_back_to_lexical(isolated_registry):
+    register_backend(_BrokenBackend.name, _BrokenBackend)
+    result = semantic_entropy(["Hold the position"] * 3, backend=get_backend("broken-test"))
+    assert result.backend == "lexical-js"
+    assert result.score == 0.0
+
+
+def test_request_choice_overrides_tenant_default():
+    update_tenant_validation_settings("tenant-a", {"divergence_backend": "numeric-claims"})
+    settings = get_tenant_validation_settings("tenant-a")
+
+    assert resolve_backend(tenant_settings=settings).name == "numeric-claims"
+    assert resolve_backend(name="lexical-js", tenant_settings=settings).name == "lexical-js"
+
+    update_tenant_validation_settings("tenant-a", {"divergence_backend": None})
+    assert get_tenant_validation_settings("tenant-a") == {}
+
+
+def test_stale_tenant_default_falls_back_to_the_global_default(isolated_registry):
+    register_backend("plugin-test", divergence_backends.NumericClaimBackend)
+    settings = {"divergence_backend": "plugin-test"}
+    assert resolve_backend(tenant_settings=settings).name == "numeric-claims"
+
+    divergence_backends._FACTORIES.pop("plugin-test")
+    divergence_backends._INSTANCES.pop("plugin-test")
+
+    assert resolve_backend(tenant_settings=settings).name == divergence_backends.default_backend_name()
+    with pytest.raises(UnknownBackendError):
+        resolve_backend(name="plugin-test")
+
+
+def test_request_cannot_set_backend_and_ensemble():
+    with pytest.raises(ValueError):
+        resolve_backend(name="lexical-js", ensemble={"numeric-claims": 1.0})
+
+
+def test_ensemble_weights_must_be_positive():
+    with pytest.raises(ValidationError):
+        ValidationRequest(
+            samples=["a", "b", "c"],
+            divergence_ensemble={"lexical-js": 0.0},
+        )
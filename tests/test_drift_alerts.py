@@ -0,0 +1,86 @@
+"""
+Test Suite: Drift & Health Alerts
+Detections and health status transitions are written to the chained runtime audit log.
+"""
+
+from app.core.drift import (
+    ALERT_EVENT_TYPES,
+    CRITICAL_VIOLATION_EVENT_TYPES,
+    DriftRegistry,
+    record_drift_audit_events,
+)
+from app.core.storage import insert_audit_event, list_runtime_audit_events_by_type, verify_runtime_audit_chain
+
+
+def _record(registry, tenant_id, agent_id, divergence, violations=0, request_id="req"):
+    alert = registry.record(tenant_id, agent_id, divergence, violations)
+    record_drift_audit_events(request_id, tenant_id, agent_id, alert)
+    return alert
+
+
+def _alerts(tenant_id, **filters):
+    return list_runtime_audit_events_by_type(tenant_id, list(ALERT_EVENT_TYPES), **filters)
+
+
+def test_health_transitions_are_audited_once_per_change():
+    registry = DriftRegistry()
+    for value in [0.1, 0.1, 0.6, 0.6, 0.6, 0.9, 0.9]:
+        _record(registry, "tenant-a", "agent-1", value)
+
+    messages = [e["message"] for e in reversed(_alerts("tenant-a")) if e["event_type"] == "HEALTH_STATUS_CHANGED"]
+
+    assert len(messages) == 2
+    assert "from HEALTHY to DEGRADED" in messages[0]
+    assert "from DEGRADED to CRITICAL" in messages[1]
+    assert DriftRegistry().get("tenant-a", "agent-1").health_status == "CRITICAL"
+    assert verify_runtime_audit_chain("tenant-a")
+
+
+def test_zscore_detection_is_one_drift_event_for_the_agent():
+    registry = DriftRegistry()
+    for value in [0.10, 0.11, 0.09, 0.10, 0.11, 0.10]:
+        _record(registry, "tenant-a", "agent-1", value)
+
+    alert = _record(registry, "tenant-a", "agent-1", 0.3, request_id="req-spike")
+
+    events = _alerts("tenant-a", agent_id="agent-1")
+    drift = [e for e in events if e["event_type"] == "DRIFT_DETECTED"]
+    assert alert.fired_detectors == ["zscore"]
+    assert len(drift) == 1
+    assert drift[0]["request_id"] == "req-spike"
+    assert drift[0]["message"].startswith("Drift detected for agent agent-1 by zscore")
+
+
+def test_alert_queries_are_scoped_and_support_polling():
+    registry = DriftRegistry()
+    _record(registry, "tenant-a", "agent-1", 0.9)
+    _record(registry, "tenant-a", "agent-2", 0.9)
+    _record(registry, None, None, 0.9)
+    latest = _alerts("tenant-a")[0]["audit_id"]
+
+    assert {e["agent_id"] for e in _alerts("tenant-a")} == {"agent-1", "agent-2"}
+    assert [e["agent_id"] for e in _alerts("tenant-a", agent_id="agent-2")] == ["agent-2"]
+    assert [e["tenant_id"] for e in _alerts(None)] == [None]
+    assert _alerts("tenant-a", after_id=latest) == []
+    assert _alerts("tenant-b") == []
+
+    _record(registry, "tenant-a", "agent-1", 0.1)  # Mean falls to 0.5: CRITICAL -> DEGRADED
+    assert [e["event_type"] for e in _alerts("tenant-a", after_id=latest)] == ["HEALTH_STATUS_CHANGED"]
+
+
+def test_latest_blocked_execution_is_the_last_critical_violation():
+    for request_id in ("req-1", "req-2"):
+        insert_audit_event(
+            request_id=request_id,
+            tenant_id="tenant-a",
+            agent_id="agent-1",
+            event_type="EXECUTION_BLOCKED_424",
+            violations=["Position limit exceeded"],
+            regulatory_articles=[],
+            message="Execution BLOCKED",
+        )
+
+    latest = list_runtime_audit_events_by_type("tenant-a", list(CRITICAL_VIOLATION_EVENT_TYPES), limit=1)
+
+    assert [e["request_id"] for e in latest] == ["req-2"]
+    assert list_runtime_audit_events_by_type("tenant-a", list(CRITICAL_VIOLATION_EVENT_TYPES), agent_id="x") == []
@@ -0,0 +1,145 @@
+"""
+Test Suite: Change-Point Drift Detectors
+CUSUM, Page-Hinkley, EWMA and windowed KS catch shifts the all-time z-score misses.
+"""
+
+import random
+
+import pytest
+
+from app.core.drift import DriftRegistry, RequestHistory
+from app.core.drift_detectors import (
+    DETECTORS_SETTING,
+    CusumDetector,
+    DetectorConfig,
+    EwmaDetector,
+    KsWindowDetector,
+    PageHinkleyDetector,
+    ks_p_value,
+    ks_statistic,
+    parse_detector_config,
+)
+from app.core.storage import update_tenant_validation_settings
+
+
+def _stream(seed=7, stable=60, shifted=40, shift=0.15):
+    rng = random.Random(seed)
+    values = [0.1 + rng.gauss(0, 0.03) for _ in range(stable)]
+    values += [0.1 + shift + rng.gauss(0, 0.03) for _ in range(shifted)]
+    return values
+
+
+def _first_alarm(detector, values):
+    for index, value in enumerate(values):
+        if detector.update(value).fired:
+            return index
+    return None
+
+
+@pytest.mark.parametrize(
+    "detector",
+    [CusumDetector(), PageHinkleyDetector(), EwmaDetector(), KsWindowDetector(window=20)],
+)
+def test_each_detector_flags_a_sustained_shift_after_it_starts(detector):
+    alarm = _first_alarm(detector, _stream())
+
+    assert alarm is not None and alarm >= 60
+
+
+@pytest.mark.parametrize(
+    "detector",
+    [CusumDetector(), PageHinkleyDetector(), EwmaDetector(), KsWindowDetector(window=20)],
+)
+def test_detectors_stay_quiet_on_a_stable_stream(detector):
+    assert _first_alarm(detector, _stream(shifted=0, stable=150)) is None
+
+
+def test_one_sigma_shift_is_missed_by_zscore_but_caught_by_cusum():
+    history = RequestHistory()
+    config = DetectorConfig(enabled=("zscore", "cusum"))
+    values = [0.1 + 0.02 * (-1) ** i for i in range(100)] + [0.12 + 0.02 * (-1) ** i for i in range(40)]
+
+    alarms = [(i, history.observe(v, 0, config).fired_detectors) for i, v in enumerate(values)]
+    fired = [(i, names) for i, names in alarms if names]
+
+    assert fired and all(names == ["cusum"] for _, names in fired)
+    assert 100 <= fired[0][0] < 115
+
+
+def test_state_round_trips_through_the_tracker():
+    history = RequestHistory()
+    config = DetectorConfig(enabled=("cusum",))
+    values = _stream()
+    for value in values[:70]:
+        history.observe(value, 0, config)
+
+    restored = RequestHistory.from_dict(history.to_dict())
+    tail = [history.observe(v, 0, config).detectors["cusum"]["statistic"] for v in values[70:]]
+    restored_tail = [restored.observe(v, 0, config).detectors["cusum"]["statistic"] for v in values[70:]]
+
+    assert tail == restored_tail
+
+
+def test_ks_statistic_and_p_value():
+    assert ks_statistic([1, 2, 3], [1, 2, 3]) == 0.0
+    assert ks_statistic([1, 2, 3], [4, 5, 6]) == 1.0
+    assert ks_p_value(0.0, 30, 30) == 1.0
+    assert ks_p_value(0.5, 30, 30) < 0.001
+
+
+def test_alert_reports_every_detector_but_only_enabled_ones_fire():
+    history = RequestHistory()
+    for value in _stream(shifted=0, stable=30):
+        history.observe(value, 0)
+
+    alert = history.observe(0.9, 0)
+
+    assert set(alert.detectors) == {
+        "zscore", "cusum", "page_hinkley", "ewma", "ks", "semantic", "violation_rate"
+    }
+    assert alert.detectors["zscore"]["enabled"] and not alert.detectors["cusum"]["enabled"]
+    assert alert.detectors["ks"]["ready"] is False
+    assert alert.fired_detectors == ["zscore"]
+    assert alert.detected
+
+
+def test_disabled_zscore_does_not_raise_an_alert():
+    history = RequestHistory()
+    config = DetectorConfig(enabled=("ks",))
+    for value in _stream(shifted=0, stable=30):
+        history.observe(value, 0, config)
+
+    alert = history.observe(0.9, 0, config)
+
+    assert alert.detectors["zscore"]["fired"]
+    assert not alert.detected
+    assert "disabled" in alert.explanation
+
+
+def test_tenant_settings_select_detectors_and_parameters():
+    update_tenant_validation_settings(
+        "tenant-a",
+        {DETECTORS_SETTING: {"enabled": ["ewma"], "parameters": {"ewma": {"warmup": 5, "L": 2.0}}}},
+    )
+    registry = DriftRegistry()
+    alerts = [registry.record("tenant-a", "agent-1", value, 0) for value in [0.1] * 5 + [0.5]]
+    other = [registry.record("tenant-b", "agent-1", value, 0) for value in [0.1] * 5 + [0.5]]
+
+    assert alerts[-1].fired_detectors == ["ewma"]
+    assert alerts[-1].detectors["ewma"]["threshold"] == 2.0
+    assert other[-1].fired_detectors == []
+
+
+@pytest.mark.parametrize(
+    "raw",
+    [
+        {"enabled": ["zscore", "bogus"]},
+        {"parameters": {"zscore": {"threshold": 3}}},
+        {"parameters": {"cusum": {"h": -1}}},
+        {"parameters": {"ks": {"windows": 10}}},
+        {"parameters": {"ewma": {"lambda": True}}},
+    ],
+)
+def test_invalid_detector_settings_are_rejected(raw):
+    with pytest.raises(ValueError):
+        parse_detector_config(raw)
@@ -0,0 +1,134 @@
+"""
+Test Suite: Scoped Drift Trackers
+Drift baselines are kept per (tenant, agent) and persisted between processes.
+"""
+
+import uuid
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core import parallel_validation
+from app.core.drift import DriftRegistry, IncrementalStats, RequestHistory
+from app.core.storage import get_validation_result, list_audit_events, list_drift_states
+from app.main import app
+from app.models import AgentIntentRequest
+from tests.helpers import bootstrap_tenant_auth
+
+
+client = TestClient(app)
+
+
+def _feed(registry, tenant_id, agent_id, values, violations=0):
+    alert = None
+    for value in values:
+        alert = registry.record(tenant_id, agent_id, value, violations)
+    return alert
+
+
+def test_noisy_agent_does_not_shift_another_agents_baseline():
+    registry = DriftRegistry()
+    _feed(registry, "tenant-a", "steady", [0.10, 0.11, 0.09, 0.10, 0.11, 0.10])
+    _feed(registry, "tenant-b", "noisy", [0.9, 0.1, 0.8, 0.2, 0.95, 0.05])
+
+    steady = registry.scope("tenant-a", "steady").get_stats()
+    assert steady["count"] == 6
+    assert steady["mean"] == pytest.approx(0.1016, abs=1e-3)
+    assert registry.record("tenant-a", "steady", 0.6, 0).detected
+
+
+def test_state_survives_a_new_registry_instance():
+    _feed(DriftRegistry(), "tenant-a", "agent-1", [0.2, 0.3], violations=1)
+
+    restored = DriftRegistry().get("tenant-a", "agent-1")
+
+    assert restored.get_stats()["count"] == 2
+    assert restored.get_stats()["mean"] == pytest.approx(0.25)
+    assert list(restored.violation_counts) == [1, 1]
+
+
+def test_tenant_scope_pools_its_agents_only():
+    registry = DriftRegistry()
+    _feed(registry, "tenant-a", "agent-1", [0.1, 0.3], violations=1)
+    _feed(registry, "tenant-a", "agent-2", [0.5, 0.7])
+    _feed(registry, "tenant-b", "agent-1", [0.9] * 4)
+
+    pooled = registry.scope("tenant-a")
+
+    assert pooled.get_stats()["count"] == 4
+    assert pooled.get_stats()["mean"] == pytest.approx(0.4)
+    assert pooled.get_stats()["std_dev"] == pytest.approx(0.2582, abs=1e-4)
+    assert pooled.violation_rate() == 0.5
+    assert {s["agent_id"] for s in list_drift_states("tenant-a")} == {"agent-1", "agent-2"}
+    assert registry.scope("tenant-c").get_stats()["count"] == 0
+
+
+def test_public_scope_is_separate_from_tenants():
+    registry = DriftRegistry()
+    _feed(registry, None, None, [0.4, 0.6])
+    _feed(registry, "tenant-a", None, [0.1])
+
+    assert registry.scope(None).get_stats()["count"] == 2
+    assert registry.scope("tenant-a").get_stats()["count"] == 1
+
+
+def test_combined_stats_match_a_single_stream():
+    values = [0.1, 0.4, 0.2, 0.8, 0.5, 0.3, 0.9]
+    whole = IncrementalStats()
+    left, right = IncrementalStats(), IncrementalStats()
+    for i, value in enumerate(values):
+        whole.update(value)
+        (left if i < 3 else right).update(value)
+
+    pooled = IncrementalStats.combine([left, right, IncrementalStats()])
+
+    for key in ("count", "mean", "std_dev"):
+        assert pooled.get_stats()[key] == pytest.approx(whole.get_stats()[key])
+
+
+def test_request_history_round_trip_keeps_window_and_latest_drift():
+    history = RequestHistory(window_size=3)
+    for value in [0.1, 0.1, 0.1, 0.12, 0.1]:
+        history.record(value, 2)
+    history.detect_drift(0.9)
+
+    restored = RequestHistory.from_dict(history.to_dict())
+
+    assert list(restored.violation_counts) == [2, 2, 2]
+    assert restored.drift_detected is True
+    assert restored.get_stats() == history.get_stats()
+
+
+def test_v3_results_use_the_agents_own_baseline():
+    def _validate(agent_id, tenant_id="tenant-a"):
+        request_id = str(uuid.uuid4())
+        request = AgentIntentRequest(
+            agent_id=agent_id, intent="rebalance", tenant_id=tenant_id, samples=["Hold the position"] * 3
+        )
+        parallel_validation.run_parallel_validation(request_id, request)
+        return get_validation_result(request_id, tenant_id=tenant_id)["drift"]
+
+    _validate("agent-1")
+    _validate("agent-1", tenant_id="tenant-b")
+
+    assert DriftRegistry().get("tenant-a", "agent-1").get_stats()["count"] == 1
+    assert DriftRegistry().get("tenant-b", "agent-1").get_stats()["count"] == 1
+    assert _validate("agent-2")["explanation"] == "Insufficient history for drift detection"
+
+
+def test_authenticated_v2_calls_use_the_callers_scope_and_a_request_id():
+    tenant, _, headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    payload = {"samples": ["Hold the position"] * 3, "agent_id": "agent-1"}
+    public_count = DriftRegistry().get(None, None).get_stats()["count"]
+
+    body = client.post("/v2/validate", json=payload, headers=headers).json()
+    blocked = client.post(
+        "/v2/validate", json={**payload, "metrics": {"leverage_ratio": 12.0}}, headers=headers
+    ).json()
+
+    assert body["request_id"] and blocked["request_id"] != body["request_id"]
+    assert DriftRegistry().get(tenant_id, "agent-1").get_stats()["count"] == 2
+    assert DriftRegistry().get(None, None).get_stats()["count"] == public_count
+    (event,) = [e for e in list_audit_events(tenant_id, "agent-1") if e["event_type"] == "EXECUTION_BLOCKED"]
+    assert event["request_id"] == blocked["request_id"]
@@ -0,0 +1,178 @@
+"""
+Test Suite: Execution Reports and Post-Trade Reconciliation
+Broker reports are reconciled against the approved order; breaches are audited.
+"""
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core.storage import (
+    get_account_ledger,
+    get_ledger_order,
+    insert_execution_decision,
+    list_audit_events,
+    list_execution_reports,
+    record_execution_report,
+)
+from app.main import app
+from app.models import ExecutionReportRequest
+from tests.helpers import bootstrap_tenant_auth, issue_api_key
+
+
+client = TestClient(app)
+
+
+def _decide(decision_id, decision="ALLOW", quantity=10.0, modified_order=None, tenant_id="tenant-a"):
+    insert_execution_decision(
+        decision_id=decision_id,
+        tenant_id=tenant_id,
+        agent_id="agent-1",
+        decision=decision,
+        reason="test",
+        triggered_controls=[],
+        order={"symbol": "AAPL", "side": "BUY", "quantity": quantity, "price": 100.0},
+        account={},
+        policy={},
+        modified_order=modified_order,
+        idempotency_key=f"key-{decision_id}",
+        account_id="acct-1",
+    )
+
+
+def _report(report_id, decision_id, report_type, quantity=None, price=100.0, **kwargs):
+    if report_type not in ("FILL", "PARTIAL_FILL"):
+        price = None
+    return record_execution_report(
+        "tenant-a", report_id, decision_id, report_type, quantity=quantity, price=price, **kwargs
+    )
+
+
+def _events(event_type):
+    return [e for e in list_audit_events("tenant-a") if e["event_type"] == event_type]
+
+
+def test_partial_fills_accumulate_until_filled():
+    _decide("d-1")
+
+    first = _report("exec-1", "d-1", "PARTIAL_FILL", quantity=4.0)
+    second = _report("exec-2", "d-1", "PARTIAL_FILL", quantity=6.0)
+
+    assert (first["order_status"], second["order_status"]) == ("PARTIALLY_FILLED", "FILLED")
+    assert first["breaches"] == second["breaches"] == []
+    assert _events("EXECUTION_RECONCILIATION_BREACH") == []
+
+
+def test_overfill_against_the_modified_order_is_a_breach():
+    _decide("d-1", decision="MODIFY", modified_order={"symbol": "AAPL", "side": "BUY", "quantity": 6.0, "price": 100.0})
+
+    _report("exec-1", "d-1", "PARTIAL_FILL", quantity=5.0)
+    report = _report("exec-2", "d-1", "FILL", quantity=5.0)
+
+    assert [b["code"] for b in report["breaches"]] == ["overfill"]
+    assert get_account_ledger("tenant-a", "acct-1")["positions"]["AAPL"]["quantity"] == 10.0
+    (event,) = _events("EXECUTION_RECONCILIATION_BREACH")
+    assert event["request_id"] == "d-1" and "excess 4" in event["message"]
+
+
+def test_fill_after_cancel_is_applied_and_flagged():
+    _decide("d-1")
+    _report("exec-1", "d-1", "CANCEL")
+
+    report = _report("exec-2", "d-1", "PARTIAL_FILL", quantity=3.0)
+
+    assert [b["code"] for b in report["breaches"]] == ["fill_after_cancel"]
+    assert report["order_status"] == "CANCELLED"
+    assert get_account_ledger("tenant-a", "acct-1")["positions"]["AAPL"]["quantity"] == 3.0
+
+
+def test_execution_without_an_approving_decision():
+    _decide("d-blocked", decision="BLOCK")
+
+    blocked = _report("exec-1", "d-blocked", "FILL", quantity=2.0)
+    orphan = _report("exec-2", None, "FILL", quantity=1.0, account_id="acct-1", symbol="msft", side="SELL")
+    unknown = _report("exec-3", "d-missing", "FILL", quantity=1.0)
+
+    assert [b["code"] for b in blocked["breaches"]] == ["unapproved_execution"]
+    assert [b["code"] for b in orphan["breaches"]] == ["no_decision"]
+    assert "not applied" in unknown["breaches"][0]["detail"]
+    positions = get_account_ledger("tenant-a", "acct-1")["positions"]
+    assert positions["AAPL"]["quantity"] == 2.0 and positions["MSFT"]["quantity"] == -1.0
+    assert len(_events("EXECUTION_RECONCILIATION_BREACH")) == 3
+
+
+def test_mismatched_symbol_and_price_through_limit():
+    _decide("d-1")
+
+    report = _report("exec-1", "d-1", "PARTIAL_FILL", quantity=2.0, price=101.5, symbol="TSLA")
+
+    assert {b["code"] for b in report["breaches"]} == {"order_mismatch", "price_through_limit"}
+    assert get_ledger_order("tenant-a", "d-1")["filled_quantity"] == 0.0
+    assert get_account_ledger("tenant-a", "acct-1")["positions"]["TSLA"]["quantity"] == 2.0
+
+
+def test_underfilled_fill_is_flagged_and_keeps_the_remainder_pending():
+    _decide("d-1")
+    _decide("d-2")
+
+    short = _report("exec-1", "d-1", "FILL", quantity=4.0)
+    mismatched = _report("exec-2", "d-2", "FILL", quantity=10.0, symbol="TSLA")
+
+    assert [b["code"] for b in short["breaches"]] == ["underfill"]
+    assert {b["code"] for b in mismatched["breaches"]} == {"order_mismatch", "underfill"}
+    assert short["order_status"] == mismatched["order_status"] == "PARTIALLY_FILLED"
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {"AAPL": 16.0}
+
+
+def test_unknown_state_keeps_exposure_pending_until_resolved():
+    _decide("d-1")
+
+    unknown = _report("exec-1", "d-1", "UNKNOWN")
+    assert unknown["order_status"] == "UNKNOWN"
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {"AAPL": 10.0}
+    assert len(_events("EXECUTION_STATE_UNKNOWN")) == 1
+
+    _report("exec-2", "d-1", "REJECT")
+    assert get_account_ledger("tenant-a", "acct-1")["pending_orders"] == {}
+    assert get_ledger_order("tenant-a", "d-1")["status"] == "REJECTED"
+
+
+def test_reports_are_listed_with_breaches():
+    _decide("d-1", quantity=1.0)
+    _report("exec-1", "d-1", "FILL", quantity=1.0)
+    _report("exec-2", "d-1", "FILL", quantity=1.0)
+
+    assert [r["report_id"] for r in list_execution_reports("tenant-a", decision_id="d-1")] == ["exec-2", "exec-1"]
+    (breach,) = list_execution_reports("tenant-a", breaches_only=True)
+    assert breach["report_id"] == "exec-2" and breach["breaches"][0]["code"] == "overfill"
+    assert list_execution_reports("tenant-b") == []
+
+
+@pytest.mark.parametrize(
+    "payload",
+    [
+        {"report_type": "PARTIAL_FILL", "decision_id": "d-1", "quantity": 1.0},
+        {"report_type": "CANCEL"},
+        {"report_type": "UNKNOWN"},
+    ],
+)
+def test_incomplete_reports_are_rejected(payload):
+    with pytest.raises(ValueError):
+        ExecutionReportRequest(report_id="exec-1", **payload)
+
+
+def test_reports_endpoint_requires_the_execution_reports_scope():
+    tenant, user, admin_headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    agent_headers = issue_api_key(client, tenant_id, user["user_id"], "validate-only", headers=admin_headers)
+    broker_headers = issue_api_key(client, tenant_id, user["user_id"], "execution-reports", headers=admin_headers)
+    _decide("d-http", tenant_id=tenant_id)
+    report = {"report_id": "exec-1", "decision_id": "d-http", "report_type": "FILL", "quantity": 10, "price": 100.0}
+
+    assert client.post("/v4/execution/reports", json=report, headers=agent_headers).status_code == 403
+    assert list_execution_reports(tenant_id) == []
+
+    response = client.post("/v4/execution/reports", json=report, headers=broker_headers)
+
+    assert response.status_code == 200
+    assert response.json()["breaches"] == []
+    assert get_account_ledger(tenant_id, "acct-1")["positions"]["AAPL"]["quantity"] == 10.0
@@ -0,0 +1,270 @@
+"""
+Test Suite: Per-Instrument, Sector and Asset-Class Exposure Limits
+Symbol caps, sector / asset-class allocation and single-name concentration each scale down or block.
+"""
+
+import pytest
+
+from app.core.ledger import reconcile_account
+from app.core.policy_analyzer import analyze
+from app.core.storage import (
+    delete_instrument,
+    get_instrument_map,
+    insert_execution_decision,
+    list_instruments,
+    record_execution_report,
+    upsert_instruments,
+)
+from app.core.trade_guard import BASKET_SCALE_DOWN, evaluate_basket, evaluate_pre_trade
+from app.core.trade_policies import looser_fields
+from app.models import AccountSnapshot, TradeOrder, TradePolicyConfig
+
+POLICY = TradePolicyConfig(
+    max_position_size=10_000.0,
+    max_capital_allocation_pct=1.0,
+    abnormal_order_zscore=1000.0,
+    min_lot_size=1.0,
+)
+INSTRUMENTS = {
+    "AAPL": {"sector": "Technology", "asset_class": "equity"},
+    "MSFT": {"sector": "technology", "asset_class": "equity"},
+    "XOM": {"sector": "Energy", "asset_class": "equity"},
+    "TLT": {"sector": None, "asset_class": "bond"},
+}
+
+
+def _order(symbol="AAPL", side="BUY", quantity=100.0, price=100.0):
+    return TradeOrder(symbol=symbol, side=side, quantity=quantity, price=price)
+
+
+def _policy(**update):
+    return POLICY.model_copy(update=update)
+
+
+def test_symbol_limit_scales_down_and_blocks_independently_of_the_global_cap():
+    policy = _policy(symbol_position_limits={"AAPL": 150.0})
+    account = AccountSnapshot(capital=1_000_000.0, open_positions={"AAPL": 100.0})
+
+    scaled = evaluate_pre_trade(_order(), account, policy)
+    blocked = evaluate_pre_trade(_order(), account.model_copy(update={"open_positions": {"AAPL": 150.0}}), policy)
+    other = evaluate_pre_trade(_order("MSFT"), account, policy)
+
+    assert (scaled["decision"], scaled["triggered_controls"]) == ("MODIFY", ["symbol_position_limit"])
+    assert scaled["modified_order"]["quantity"] == 50.0
+    assert (blocked["decision"], blocked["triggered_controls"]) == ("BLOCK", ["symbol_position_limit"])
+    assert other["decision"] == "ALLOW"
+
+
+def test_reducing_an_oversized_position_is_not_limited():
+    policy = _policy(symbol_position_limits={"AAPL": 100.0}, sector_allocation_pct={"Technology": 0.01})
+    account = AccountSnapshot(capital=100_000.0, open_positions={"AAPL": 500.0})
+
+    result = evaluate_pre_trade(_order(side="SELL", quantity=200.0), account, policy, INSTRUMENTS)
+
+    assert result["decision"] == "ALLOW"
+
+
+def test_sector_limit_counts_other_names_at_their_marks():
+    policy = _policy(sector_allocation_pct={"TECHNOLOGY": 0.2})
+    account = AccountSnapshot(
+        capital=100_000.0,
+        open_positions={"MSFT": 50.0, "XOM": 500.0},
+        mark_prices={"MSFT": 200.0, "XOM": 10.0},
+    )
+
+    result = evaluate_pre_trade(_order(quantity=150.0), account, policy, INSTRUMENTS)
+
+    # 20k sector cap, 10k already in MSFT -> 100 AAPL at 100; XOM is another sector
+    assert (result["decision"], result["triggered_controls"]) == ("MODIFY", ["sector_allocation"])
+    assert result["modified_order"]["quantity"] == 100.0
+    assert "technology within 20.00% of capital" in result["reason"]
+
+
+def test_asset_class_limit_blocks_when_the_class_is_full():
+    policy = _policy(asset_class_allocation_pct={"bond": 0.1})
+    account = AccountSnapshot(capital=100_000.0, open_positions={"TLT": 100.0})
+
+    result = evaluate_pre_trade(_order("TLT"), account, policy, INSTRUMENTS)
+
+    assert (result["decision"], result["triggered_controls"]) == ("BLOCK", ["asset_class_allocation"])
+
+
+def test_unclassified_instruments_are_blocked_only_by_policies_that_need_the_class():
+    sector_policy = _policy(sector_allocation_pct={"Energy": 0.5})
+
+    unknown = evaluate_pre_trade(_order("GME"), AccountSnapshot(capital=100_000.0), sector_policy, INSTRUMENTS)
+    no_sector = evaluate_pre_trade(_order("TLT"), AccountSnapshot(capital=100_000.0), sector_policy, INSTRUMENTS)
+    unlimited = evaluate_pre_trade(_order("GME"), AccountSnapshot(capital=100_000.0), POLICY)
+
+    assert unknown["triggered_controls"] == no_sector["triggered_controls"] == ["unclassified_instrument"]
+    assert unknown["decision"] == "BLOCK"
+    assert unlimited["decision"] == "ALLOW"
+
+
+def test_single_name_concentration_caps_the_share_of_gross():
+    policy = _policy(max_single_name_pct=0.1)
+    account = AccountSnapshot(
+        capital=1_000_000.0,
+        open_positions={"MSFT": 450.0, "XOM": 400.0},
+        mark_prices={"MSFT": 100.0, "XOM": 100.0},
+    )
+
+    result = evaluate_pre_trade(_order(quantity=500.0), account, policy)
+
+    # 85k in other names -> AAPL may hold 85k * 0.1 / 0.9 = 9.44k, i.e. 94 shares
+    assert (result["decision"], result["triggered_controls"]) == ("MODIFY", ["single_name_concentration"])
+    assert result["modified_order"]["quantity"] == 94.0
+
+
+def test_concentration_waits_for_the_gross_floor():
+    policy = _policy(max_single_name_pct=0.1, concentration_min_gross_notional=50_000.0)
+
+    first = evaluate_pre_trade(_order(quantity=100.0), AccountSnapshot(capital=1_000_000.0), policy)
+    large = evaluate_pre_trade(_order(quantity=600.0), AccountSnapshot(capital=1_000_000.0), policy)
+
+    assert first["decision"] == "ALLOW"
+    assert (large["decision"], large["triggered_controls"]) == ("BLOCK", ["single_name_concentration"])
+
+
+def test_basket_legs_share_the_sector_cap():
+    policy = _policy(sector_allocation_pct={"technology": 0.2})
+    legs = [_order("AAPL", quantity=150.0), _order("MSFT", quantity=150.0)]
+
+    result = evaluate_basket(legs, AccountSnapshot(capital=100_000.0), policy, BASKET_SCALE_DOWN, INSTRUMENTS)
+
+    assert [evaluate_pre_trade(leg, AccountSnapshot(capital=100_000.0), policy, INSTRUMENTS)["decision"] for leg in legs] == [
+        "ALLOW",
+        "ALLOW",
+    ]
+    assert result["triggered_controls"] == ["basket_sector_allocation"]
+    assert [leg["modified_order"]["quantity"] for leg in result["legs"]] == [100.0, 100.0]
+
+
+def test_dropping_or_raising_a_bound_limit_is_looser():
+    bound = _policy(
+        symbol_position_limits={"AAPL": 100.0},
+        sector_allocation_pct={"Technology": 0.2},
+        max_single_name_pct=0.1,
+    )
+    tighter = bound.model_copy(
+        update={"symbol_position_limits": {"AAPL": 50.0, "MSFT": 10.0}, "sector_allocation_pct": {"technology": 0.1}}
+    )
+    looser = bound.model_copy(
+        update={"symbol_position_limits": {}, "sector_allocation_pct": {"Technology": 0.3}, "max_single_name_pct": None}
+    )
+
+    assert looser_fields(tighter, bound) == []
+    assert looser_fields(looser, bound) == ["symbol_position_limits", "sector_allocation_pct", "max_single_name_pct"]
+
+
+def test_analyzer_flags_a_concentration_cap_without_a_gross_floor():
+    floorless = analyze(policy=_policy(max_single_name_pct=0.1)).findings
+    floored = analyze(policy=_policy(max_single_name_pct=0.1, concentration_min_gross_notional=50_000.0)).findings
+
+    assert ("error", "concentration_min_gross_notional") in {(f.severity, f.target) for f in floorless}
+    assert "concentration_min_gross_notional" not in {f.target for f in floored}
+
+
+def test_analyzer_flags_limits_that_never_bind():
+    policy = TradePolicyConfig(
+        max_position_size=100.0,
+        min_lot_size=10.0,
+        symbol_position_limits={"aapl": 5.0, "MSFT": 200.0},
+        sector_allocation_pct={"Energy": 0.5},
+    )
+
+    targets = {(f.severity, f.target) for f in analyze(policy=policy).findings}
+
+    assert ("error", "symbol_position_limits['AAPL']") in targets
+    assert ("warning", "symbol_position_limits['MSFT']") in targets
+    assert ("warning", "sector_allocation_pct['Energy']") in targets
+
+
+@pytest.mark.parametrize(
+    "update",
+    [
+        {"symbol_position_limits": {"AAPL": 0.0}},
+        {"sector_allocation_pct": {"Technology": 1.5}},
+        {"asset_class_allocation_pct": {" ": 0.1}},
+    ],
+)
+def test_invalid_limits_are_rejected(update):
+    with pytest.raises(ValueError):
+        TradePolicyConfig(**update)
+
+
+def test_instrument_reference_is_stored_per_tenant():
+    upsert_instruments("tenant-a", [{"symbol": "aapl", "sector": "Technology", "asset_class": "equity"}])
+    upsert_instruments("tenant-a", [{"symbol": "AAPL", "sector": "Consumer", "asset_class": "equity"}], "ops")
+
+    assert get_instrument_map("tenant-a") == {"AAPL": {"sector": "Consumer", "asset_class": "equity"}}
+    assert list_instruments("tenant-a")[0]["updated_by"] == "ops"
+    assert list_instruments("tenant-b") == []
+    assert delete_instrument("tenant-a", "aapl")
+    assert not delete_instrument("tenant-a", "aapl")
+
+
+def test_ledger_average_cost_marks_unpriced_positions():
+    insert_execution_decision(
+        decision_id="d-1",
+        tenant_id="tenant-a",
+        agent_id=None,
+        decision="ALLOW",
+        reason="test",
+        triggered_controls=[],
+        order={"symbol": "MSFT", "side": "BUY", "quantity": 50.0, "price": 200.0},
+        account={},
+        policy={},
+        modified_order=None,
+        idempotency_key="key-d-1",
+        account_id="acct-1",
+    )
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=50.0, price=200.0)
+
+    evaluated, _ = reconcile_account("tenant-a", "acct-1", AccountSnapshot(capital=100_000.0))
+
+    assert evaluated.mark_prices == {"MSFT": 200.0}
+
+
+def test_client_marks_cannot_loosen_limits_below_ledger_cost():
+    insert_execution_decision(
+        decision_id="d-1",
+        tenant_id="tenant-a",
+        agent_id=None,
+        decision="ALLOW",
+        reason="test",
+        triggered_controls=[],
+        order={"symbol": "MSFT", "side": "BUY", "quantity": 50.0, "price": 200.0},
+        account={},
+        policy={},
+        modified_order=None,
+        idempotency_key="key-d-1",
+        account_id="acct-1",
+    )
+    record_execution_report("tenant-a", "exec-1", "d-1", "FILL", quantity=50.0, price=200.0)
+    policy = _policy(sector_allocation_pct={"technology": 0.2})
+
+    evaluated, record = reconcile_account(
+        "tenant-a", "acct-1", AccountSnapshot(capital=100_000.0, mark_prices={"MSFT": 1.0})
+    )
+    result = evaluate_pre_trade(_order(quantity=150.0), evaluated, policy, INSTRUMENTS)
+
+    assert (evaluated.mark_prices, evaluated.concentration_mark_prices) == ({"MSFT": 200.0}, {"MSFT": 1.0})
+    assert result["modified_order"]["quantity"] == 100.0
+    (mark,) = [d for d in record["discrepancies"] if d["field"] == "mark_prices.MSFT"]
+    assert (mark["snapshot"], mark["ledger"]) == (1.0, 200.0)
+
+
+def test_concentration_denominator_uses_the_lower_mark():
+    policy = _policy(max_single_name_pct=0.1, concentration_min_gross_notional=1.0)
+    account = AccountSnapshot(
+        capital=1_000_000.0,
+        open_positions={"MSFT": 900.0},
+        mark_prices={"MSFT": 100.0},
+        concentration_mark_prices={"MSFT": 50.0},
+    )
+
+    result = evaluate_pre_trade(_order(quantity=500.0), account, policy)
+
+    # 45k in MSFT at the lower mark -> AAPL may hold 45k * 0.1 / 0.9 = 5k, i.e. 50 shares
+    assert result["modified_order"]["quantity"] == 50.0
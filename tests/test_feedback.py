@@ -0,0 +1,175 @@
+"""
+Test Suite: Validation Feedback Loop
+Labelled outcomes are stored per result and turned into precision/recall metrics.
+"""
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core.feedback import (
+    REFERENCE_PRE_TRADE,
+    ConfusionMatrix,
+    compute_feedback_metrics,
+    feedback_subject,
+)
+from app.core.storage import (
+    get_validation_result,
+    insert_execution_decision,
+    list_validation_feedback,
+    upsert_validation_feedback,
+)
+from app.main import app
+from app.models import FeedbackRequest
+from tests.helpers import bootstrap_tenant_auth, issue_api_key, validate_leverage
+
+
+client = TestClient(app)
+
+
+def _label(tenant_id, outcome, reviewer_id="reviewer-1", **reference):
+    subject = feedback_subject(tenant_id, **reference)
+    return upsert_validation_feedback(tenant_id=tenant_id, outcome=outcome, reviewer_id=reviewer_id, **subject)
+
+
+def _entry(action, outcome, agent_id="agent-1", rule_ids=(), divergence_score=0.0, reference_type="validation"):
+    return {
+        "reference_type": reference_type,
+        "agent_id": agent_id,
+        "action": action,
+        "outcome": outcome,
+        "rule_ids": list(rule_ids),
+        "divergence_score": divergence_score,
+    }
+
+
+def test_validation_result_label_captures_verdict_and_reviewer():
+    request_id = validate_leverage(12.0)["request_id"]
+
+    stored = _label("tenant-a", "unsafe", request_id=request_id)
+
+    assert get_validation_result(request_id, tenant_id="tenant-a")["agent_id"] == "agent-1"
+    assert (stored["reference_type"], stored["reference_id"]) == ("validation", request_id)
+    assert (stored["action"], stored["outcome"], stored["reviewer_id"]) == ("BLOCK", "unsafe", "reviewer-1")
+    assert stored["rule_ids"] == ["leverage_limit"]
+
+
+def test_relabelling_replaces_outcome_but_keeps_first_review_time():
+    request_id = validate_leverage(2.0)["request_id"]
+    first = _label("tenant-a", "safe", request_id=request_id)
+    second = _label("tenant-a", "unsafe", reviewer_id="reviewer-2", request_id=request_id)
+
+    (entry,) = list_validation_feedback("tenant-a")
+    assert entry["feedback_id"] == first["feedback_id"] == second["feedback_id"]
+    assert (entry["outcome"], entry["reviewer_id"]) == ("unsafe", "reviewer-2")
+    assert entry["created_at"] == first["created_at"]
+
+
+def test_pre_trade_decision_is_labelled_with_triggered_controls():
+    insert_execution_decision(
+        decision_id="decision-1",
+        tenant_id="tenant-a",
+        agent_id="agent-9",
+        decision="BLOCK",
+        reason="Restricted instrument",
+        triggered_controls=["restricted_instrument"],
+        order={},
+        account={},
+        policy={},
+        modified_order=None,
+        idempotency_key="idem-0001",
+    )
+
+    stored = _label("tenant-a", "safe", decision_id="decision-1")
+
+    assert stored["reference_type"] == REFERENCE_PRE_TRADE
+    assert (stored["agent_id"], stored["rule_ids"], stored["divergence_score"]) == (
+        "agent-9", ["restricted_instrument"], None
+    )
+
+
+def test_unknown_or_foreign_references_have_no_subject():
+    request_id = validate_leverage(2.0, tenant_id="tenant-b")["request_id"]
+
+    assert feedback_subject("tenant-a", request_id=request_id) is None
+    assert feedback_subject("tenant-a", decision_id="missing") is None
+
+
+def test_feedback_request_needs_exactly_one_reference():
+    with pytest.raises(ValueError):
+        FeedbackRequest(outcome="safe")
+    with pytest.raises(ValueError):
+        FeedbackRequest(request_id="r", decision_id="d", outcome="safe")
+    with pytest.raises(ValueError):
+        FeedbackRequest(request_id="r", outcome="maybe")
+
+
+def test_confusion_matrix_ratios_are_none_without_data():
+    matrix = ConfusionMatrix()
+    assert (matrix.precision, matrix.recall, matrix.f1, matrix.accuracy) == (None, None, None, None)
+
+    for predicted, outcome in [(True, "unsafe"), (True, "safe"), (False, "unsafe"), (False, "safe"), (False, "safe")]:
+        matrix.add(predicted, outcome)
+    assert matrix.to_dict() == {
+        "labelled": 5,
+        "true_positive": 1,
+        "false_positive": 1,
+        "false_negative": 1,
+        "true_negative": 2,
+        "precision": 0.5,
+        "recall": 0.5,
+        "f1": 0.5,
+        "accuracy": 0.6,
+    }
+
+
+def test_metrics_break_down_by_agent_and_rule():
+    metrics = compute_feedback_metrics([
+        _entry("BLOCK", "unsafe", rule_ids=["leverage_limit"]),
+        _entry("BLOCK", "safe", rule_ids=["leverage_limit", "var_limit"]),
+        _entry("ALLOW", "unsafe", agent_id="agent-2"),
+        _entry("CAUTION", "safe", agent_id="agent-2"),
+        _entry("MODIFY", "unsafe", agent_id="agent-3", rule_ids=["max_position_size"], reference_type="pre_trade"),
+    ])
+
+    assert metrics["overall"]["true_positive"] == 2
+    assert metrics["overall"]["false_negative"] == 1
+    assert metrics["by_agent"]["agent-1"]["precision"] == 0.5
+    assert metrics["by_agent"]["agent-2"]["recall"] == 0.0
+    assert metrics["by_rule"]["leverage_limit"]["precision"] == 0.5
+    assert metrics["by_rule"]["var_limit"]["false_positive"] == 1
+    # Pre-trade controls are only scored against pre-trade labels
+    assert metrics["by_rule"]["max_position_size"]["labelled"] == 1
+    assert metrics["by_reference_type"]["validation"]["labelled"] == 4
+
+
+def test_divergence_sweep_rescores_validation_labels():
+    entries = [
+        _entry("REVIEW", "unsafe", divergence_score=0.45),
+        _entry("ALLOW", "unsafe", divergence_score=0.35),
+        _entry("ALLOW", "safe", divergence_score=0.1),
+        _entry("ALLOW", "safe", divergence_score=None, reference_type="pre_trade"),
+    ]
+
+    sweep = {row["threshold"]: row for row in compute_feedback_metrics(entries, (0.4, 0.3))["divergence_thresholds"]}
+
+    assert sweep[0.4]["current"] and not sweep[0.3]["current"]
+    assert (sweep[0.4]["labelled"], sweep[0.4]["recall"]) == (3, 0.5)
+    assert (sweep[0.3]["recall"], sweep[0.3]["precision"]) == (1.0, 1.0)
+
+
+def test_feedback_endpoint_requires_manage_config():
+    tenant, user, admin_headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    agent_headers = issue_api_key(client, tenant_id, user["user_id"], "validate-only", headers=admin_headers)
+    request_id = validate_leverage(12.0, tenant_id=tenant_id)["request_id"]
+    label = {"request_id": request_id, "outcome": "unsafe"}
+
+    assert client.post("/v3/feedback", json=label, headers=agent_headers).status_code == 403
+    assert list_validation_feedback(tenant_id) == []
+
+    response = client.post("/v3/feedback", json=label, headers=admin_headers)
+
+    assert response.status_code == 200
+    body = response.json()
+    assert (body["reference_id"], body["action"], body["outcome"]) == (request_id, "BLOCK", "unsafe")
+    assert body["reviewer_id"] == user["user_id"]
@@ -17,7 +17,7 @@ def test_trap_1_perpetual_motion():
         physics=PhysicsPayload(energy_in=100, energy_out=120),
     )
     violations = check_logic(request)
-    assert "Balance invariant breached" in violations
+    assert "Balance invariant breached" in [v.message for v in violations]
 
 
 def test_trap_2_negative_temperature():
@@ -43,7 +43,7 @@ def test_trap_4_var_loss_threshold():
         financial=FinancialPayload(proposed_loss=-12000),
     )
     violations = check_logic(request)
-    assert any("VaR" in v or "threshold" in v for v in violations)
+    assert any("VaR" in v.message or "threshold" in v.message for v in violations)
 
 
 def test_trap_5_negative_pressure():
@@ -83,7 +83,7 @@ def test_trap_8_metrics_temperature_violation():
         metrics={"temperature": -100},
     )
     violations = check_logic(request)
-    assert any("absolute zero" in v for v in violations)
+    assert any("absolute zero" in v.message for v in violations)
 
 
 def test_trap_9_metrics_multiple_violations():
@@ -0,0 +1,163 @@
+"""
+Test Suite: Readiness Checks
+Readiness fails on dependency problems that divergence averages never show.
+"""
+
+import threading
+import time
+
+from app.core import divergence_backends, entropy, health, parallel_validation
+from app.core.storage import (
+    _LOCK,
+    insert_audit_event,
+    list_audit_chain_verifications,
+)
+
+
+def _audit(tenant_id):
+    insert_audit_event(
+        request_id="req",
+        tenant_id=tenant_id,
+        agent_id="agent-1",
+        event_type="EXECUTION_BLOCKED_424",
+        violations=[],
+        regulatory_articles=[],
+        message="blocked",
+    )
+
+
+def test_ready_on_a_healthy_deployment():
+    report = health.readiness()
+
+    assert report["ready"], report
+    assert set(report["checks"]) == {"database", "audit_chain", "embeddings", "queue", "disk"}
+    assert report["checks"]["embeddings"]["state"] == "disabled"
+
+
+def test_locked_database_fails_the_write_probe(monkeypatch):
+    monkeypatch.setenv("HEALTH_DB_TIMEOUT_SECONDS", "0.05")
+    released = threading.Event()
+
+    def _hold():
+        with _LOCK:
+            released.wait(1)
+
+    holder = threading.Thread(target=_hold)
+    holder.start()
+    time.sleep(0.05)
+    try:
+        result = health.check_database()
+    finally:
+        released.set()
+        holder.join()
+
+    assert not result.ok
+    assert "busy" in result.detail["error"]
+
+
+def test_unverified_chains_are_verified_and_then_reported_fresh():
+    _audit("tenant-a")
+
+    first = health.check_audit_chain()
+    second = health.check_audit_chain()
+
+    assert first.ok and first.detail["reverified"] == 1
+    assert second.ok and second.detail["reverified"] == 0
+    assert list_audit_chain_verifications()[0]["intact"] is True
+
+
+def test_failed_verification_marks_the_tenant_broken(monkeypatch):
+    monkeypatch.setattr(health, "list_audit_chain_verifications", lambda: [
+        {"tenant_id": "tenant-a", "intact": False, "event_count": 1, "verified_at": "2999-01-01T00:00:00"}
+    ])
+
+    result = health.check_audit_chain()
+
+    assert not result.ok
+    assert result.detail["broken_tenants"] == ["tenant-a"]
+
+
+def test_reverification_is_capped_per_probe_oldest_first(monkeypatch):
+    monkeypatch.setenv("HEALTH_AUDIT_MAX_REVERIFY", "1")
+    for tenant_id in ("tenant-a", "tenant-b"):
+        _audit(tenant_id)
+
+    first = health.check_audit_chain()
+    second = health.check_audit_chain()
+
+    assert first.ok and (first.detail["reverified"], first.detail["stale"]) == (1, 1)
+    assert second.ok and (second.detail["reverified"], second.detail["stale"]) == (1, 0)
+
+
+def test_public_report_carries_only_booleans_and_counts(monkeypatch):
+    monkeypatch.setattr(health, "list_audit_chain_verifications", lambda: [
+        {"tenant_id": "tenant-a", "intact": False, "event_count": 1, "verified_at": "2999-01-01T00:00:00"}
+    ])
+
+    report = health.public_report(health.readiness())
+
+    assert not report["ready"]
+    assert (report["checks"]["audit_chain"]["ok"], report["checks"]["audit_chain"]["broken_count"]) == (False, 1)
+    assert "tenant-a" not in str(report)
+
+
+def test_recent_report_is_reused_within_max_age(monkeypatch):
+    first = health.readiness()
+    monkeypatch.setattr(health, "READINESS_CHECKS", ())
+
+    assert health.readiness(max_age_seconds=60) is first
+    assert health.readiness()["checks"] == {}
+
+
+def test_only_default_backend_fallbacks_count_against_readiness(monkeypatch):
+    class _Failing:
+        name = "tenant-model"
+        version = "1"
+        cluster_threshold = 0.5
+
+        def similarity_matrix(self, responses):
+            raise RuntimeError("tenant model offline")
+
+    monkeypatch.setattr(entropy, "_LAST_LEXICAL_FALLBACK", None)
+    entropy.semantic_entropy(["Buy AAPL", "Buy AAPL"], backend=_Failing())
+    tenant_fallback = entropy._LAST_LEXICAL_FALLBACK
+
+    monkeypatch.setattr(divergence_backends, "default_backend_name", lambda: "tenant-model")
+    entropy.semantic_entropy(["Buy AAPL", "Buy AAPL"], backend=_Failing())
+
+    assert tenant_fallback is None
+    assert entropy._LAST_LEXICAL_FALLBACK is not None
+
+
+def test_embedding_fallback_and_load_failure_fail_readiness(monkeypatch):
+    monkeypatch.setenv("ORIPHIM_DISABLE_EMBEDDINGS", "false")
+    monkeypatch.setattr(entropy, "_EMBEDDING_MODEL", None)
+    monkeypatch.setattr(entropy, "_EMBEDDING_LOAD_ERROR", "model download failed")
+    failed = health.check_embeddings()
+
+    monkeypatch.setattr(entropy, "_EMBEDDING_MODEL", object())
+    monkeypatch.setattr(entropy, "_EMBEDDING_LOAD_ERROR", None)
+    monkeypatch.setattr(entropy, "_LAST_LEXICAL_FALLBACK", time.time())
+    fell_back = health.check_embeddings()
+
+    monkeypatch.setattr(entropy, "_LAST_LEXICAL_FALLBACK", time.time() - 3600)
+    recovered = health.check_embeddings()
+
+    assert (failed.ok, failed.detail["state"]) == (False, "failed")
+    assert (fell_back.ok, fell_back.detail["state"]) == (False, "loaded")
+    assert recovered.ok
+
+
+def test_queue_depth_and_disk_thresholds(monkeypatch):
+    monkeypatch.setenv("HEALTH_MAX_QUEUE_DEPTH", "1")
+    monkeypatch.setenv("HEALTH_MIN_FREE_DISK_MB", str(10**12))
+    parallel_validation.mark_validation_queued()
+    parallel_validation.mark_validation_queued()
+    try:
+        queue = health.check_queue()
+    finally:
+        monkeypatch.setattr(parallel_validation, "_QUEUED", 0)
+
+    assert (queue.ok, queue.detail["depth"]) == (False, 2)
+    assert not health.check_disk().ok
+    assert not health.readiness()["ready"]
@@ -45,6 +45,15 @@ def bootstrap_tenant_auth(scope: str = "admin"):
     return tenant, user, {"Authorization": f"Bearer {api_key}"}, api_key
 
 
+def allow_client_trade_policy(tenant_id: str, headers: dict) -> None:
+    response = client.put(
+        f"/v1/onboarding/tenants/{tenant_id}/client-trade-policy",
+        headers=headers,
+        json={"enabled": True},
+    )
+    assert response.status_code == 200
+
+
 def test_api_key_digest_is_persisted_and_used_for_validation():
     tenant, user, _, api_key = bootstrap_tenant_auth()
 
@@ -70,6 +79,7 @@ def test_api_key_digest_is_persisted_and_used_for_validation():
 
 def test_pre_trade_blocks_parallel_frequency_bypass():
     tenant, _, headers, _ = bootstrap_tenant_auth()
+    allow_client_trade_policy(tenant["tenant_id"], headers)
     agent_id = f"{tenant['tenant_id']}-agent-risk"
 
     request_payload = {
@@ -118,6 +128,7 @@ def test_pre_trade_blocks_parallel_frequency_bypass():
 
 def test_pre_trade_is_idempotent_for_same_request():
     tenant, _, headers, _ = bootstrap_tenant_auth()
+    allow_client_trade_policy(tenant["tenant_id"], headers)
     payload = {
         "idempotency_key": "same-request-0001",
         "tenant_id": tenant["tenant_id"],
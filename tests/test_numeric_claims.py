@@ -0,0 +1,63 @@
+"""
+Test Suite: Numeric Claim Consistency
+Cross-sample disagreement on quantities, prices, percentages and tickers.
+"""
+
+from app.core.compliance import map_violations_to_articles
+from app.core.constraints import check_logic
+from app.core.entropy import semantic_entropy
+from app.core.numeric_claims import extract_claims, find_numeric_disagreements
+from app.core.severity import calculate_overall_severity_score, calculate_violation_severities
+from app.models import ValidationRequest
+
+
+def test_extracts_claim_kinds_and_scales():
+    claims = extract_claims("Buy 2,500 AAPL at $150.25, sized at 5% of book ($1.2M)")
+    assert [(c.kind, c.value) for c in claims] == [
+        ("quantity", 2500.0),
+        ("price", 150.25),
+        ("percentage", 5.0),
+        ("price", 1_200_000.0),
+    ]
+    assert claims[0].subject == "AAPL"
+
+
+def test_ten_x_quantity_disagreement_is_a_violation_despite_low_entropy():
+    samples = ["Buy 100 AAPL at market", "Buy 100 AAPL at market", "Buy 1000 AAPL at market"]
+    violations = check_logic(ValidationRequest(samples=samples))
+
+    assert len(violations) == 1
+    assert violations[0].message.startswith("Numeric claim disagreement: quantity AAPL (100 vs 1,000")
+    assert semantic_entropy(samples).score <= 0.4
+
+    severities = calculate_violation_severities(violations)
+    assert severities[0].weight == 4.0
+    assert calculate_overall_severity_score(severities) >= 3.0
+
+
+def test_prices_within_tolerance_agree():
+    samples = ["Limit buy at $100.00", "Limit buy at $100.40", "Limit buy at $99.80"]
+    assert find_numeric_disagreements(samples) == []
+
+
+def test_extra_number_in_one_sample_does_not_shift_alignment():
+    samples = [
+        "Buy 100 AAPL at $150 with stop at $140",
+        "Buy 100 AAPL at $150, target $170, stop at $140",
+        "Buy 100 AAPL at $150 with stop at $140",
+    ]
+    assert find_numeric_disagreements(samples) == []
+
+    shifted = samples[:2] + ["Buy 100 AAPL at $150 with stop at $120"]
+    (disagreement,) = find_numeric_disagreements(shifted)
+    assert disagreement.values == ("140", "120")
+
+
+def test_different_tickers_disagree():
+    disagreements = find_numeric_disagreements(["Sell 10 MSFT", "Sell 10 MSFT", "Sell 10 GOOG"])
+    assert [d.kind for d in disagreements] == ["ticker"]
+
+
+def test_numeric_disagreement_maps_to_compliance_articles():
+    violations = check_logic(ValidationRequest(samples=["Risk 2% of NAV", "Risk 20% of NAV", "Risk 2% of NAV"]))
+    assert "EU-AIA-15-Accuracy" in map_violations_to_articles(violations)
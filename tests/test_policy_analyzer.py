@@ -0,0 +1,164 @@
+"""
+Test Suite: Constraint & Policy Analyzer
+Well-formed but contradictory, dead or ambiguous bundles and trade policies are reported.
+"""
+
+from app.core.constraint_rules import (
+    get_constraint_definitions,
+    parse_bundle_definitions,
+    resolve_constraint_definitions,
+)
+from app.core.policy_analyzer import analyze
+from app.core.storage import create_constraint_bundle
+from app.models import TradePolicyConfig
+
+
+def _rule(rule_id, variables, operator, limit, unit=None, message="Limit breached", articles=("EU-AIA-12-RecordKeeping",)):
+    return {
+        "id": rule_id,
+        "variables": variables,
+        "operator": operator,
+        "limit": limit,
+        "unit": unit,
+        "severity_weight": 2.0,
+        "message": message,
+        "articles": list(articles),
+    }
+
+
+def _relation(rule_id, expression, articles=("EU-AIA-12-RecordKeeping",), **extra):
+    return {
+        "id": rule_id,
+        "expression": expression,
+        "severity_weight": 2.0,
+        "message": "Relation breached",
+        "articles": list(articles),
+        **extra,
+    }
+
+
+def _bundle(rules, relations=(), temporal=()):
+    return parse_bundle_definitions(
+        {"rules": list(rules), "relations": list(relations), "temporal": list(temporal), "checks": []},
+        version="test",
+    )
+
+
+def _codes(report):
+    return sorted((f.severity, f.code, f.target) for f in report.findings)
+
+
+def test_shipped_defaults_and_default_policy_are_clean():
+    report = analyze(get_constraint_definitions(), TradePolicyConfig())
+    assert report.ok
+    assert report.findings == ()
+
+
+def test_rule_and_relation_bounds_that_cannot_both_hold_are_contradictions():
+    report = analyze(_bundle(
+        [_rule("max_drawdown", ["drawdown"], "<=", 5)],
+        [_relation("drawdown_floor", "drawdown >= floor", parameters={"floor": 10})],
+    ))
+
+    assert not report.ok
+    (error,) = report.errors
+    assert (error.code, error.target) == ("contradiction", "max_drawdown")
+    assert "drawdown <= 5" in error.message and "drawdown >= 10" in error.message
+
+
+def test_rule_implied_by_stricter_relation_is_unreachable():
+    report = analyze(_bundle(
+        [_rule("exposure_cap", ["exposure"], "<=", 10)],
+        [_relation("exposure_band", "0 <= exposure <= 8")],
+    ))
+
+    assert _codes(report) == [("warning", "unreachable_rule", "exposure_cap")]
+
+
+def test_rule_with_unbounded_alias_is_still_reachable():
+    report = analyze(_bundle(
+        [_rule("exposure_cap", ["exposure", "gross_exposure"], "<=", 10)],
+        [_relation("exposure_band", "exposure <= 8")],
+    ))
+
+    assert report.findings == ()
+
+
+def test_temporal_limit_wider_than_allowed_range_is_unreachable():
+    report = analyze(_bundle(
+        [_rule("utilisation", ["utilisation"], "<=", 1)],
+        [_relation("utilisation_floor", "utilisation >= 0")],
+        [{
+            "id": "utilisation_step",
+            "variables": ["utilisation"],
+            "kind": "max_delta",
+            "limit": 2,
+            "severity_weight": 2.0,
+            "message": "Utilisation moved {previous:g} -> {current:g}",
+            "articles": ["EU-AIA-12-RecordKeeping"],
+        }],
+    ))
+
+    assert _codes(report) == [("warning", "unreachable_rule", "utilisation_step")]
+
+
+def test_overlapping_aliases_and_missing_articles_are_warned():
+    report = analyze(_bundle(
+        [_rule("var_cap", ["var99"], "<=", 1_000_000, articles=())],
+        [_relation("var_99_coverage", "abs(proposed_loss) <= var_99")],
+    ))
+
+    assert _codes(report) == [
+        ("warning", "missing_regulatory_mapping", "var_cap"),
+        ("warning", "overlapping_aliases", "var_99_coverage"),
+    ]
+
+
+def test_unit_mismatches_in_relations_messages_and_policy():
+    report = analyze(
+        _bundle(
+            [
+                _rule("leverage_cap", ["leverage_ratio"], "<=", 10, unit="%", message="Leverage above {limit:g}%"),
+                _rule("loss_cap", ["proposed_loss"], ">=", -5_000, unit="USD", message="Loss above {limit:g}x"),
+            ],
+            [_relation("mixed", "leverage_ratio + proposed_loss <= 100")],
+        ),
+        TradePolicyConfig(),
+    )
+
+    assert _codes(report) == [
+        ("warning", "unit_mismatch", "leverage_cap"),
+        ("warning", "unit_mismatch", "loss_cap"),
+        ("warning", "unit_mismatch", "mixed"),
+    ]
+
+
+def test_policy_position_cap_below_min_lot_is_a_contradiction():
+    report = analyze(policy=TradePolicyConfig(max_position_size=0.00005, min_lot_size=0.0001))
+
+    (error,) = report.errors
+    assert (error.code, error.target) == ("contradiction", "max_position_size")
+
+
+def test_restricted_instruments_that_never_match_are_reported():
+    policy = TradePolicyConfig(restricted_instruments=["AAPL", "aapl", " TSLA", "NASDAQ:MSFT", "BRK.B"])
+    findings = {(f.code, f.target) for f in analyze(policy=policy).findings}
+
+    assert findings == {
+        ("overlapping_aliases", "restricted_instruments['aapl']"),
+        ("unreachable_rule", "restricted_instruments[' TSLA']"),
+        ("unreachable_rule", "restricted_instruments['NASDAQ:MSFT']"),
+        ("overlapping_aliases", "restricted_instruments['BRK.B']"),
+    }
+
+
+def test_stored_bundle_is_analyzed_with_inherited_defaults():
+    bundle = create_constraint_bundle("tenant-a", {"rules": [_rule("leverage_cap", ["leverage_ratio"], ">=", 20)]})
+    report = analyze(resolve_constraint_definitions(bundle))
+
+    # The inherited leverage_step/leverage_window rules also cover `leverage` and `debt_to_equity`
+    assert report.ok
+    assert {(f.code, f.target) for f in report.findings} == {
+        ("overlapping_aliases", "leverage_step"),
+        ("overlapping_aliases", "leverage_window"),
+    }
@@ -0,0 +1,86 @@
+"""
+Test Suite: Chain-of-Thought Step Auditing
+Contradictions, numeric drift between steps, and final-step/intent agreement.
+"""
+
+from app.core.parallel_validation import run_parallel_validation
+from app.core.reasoning_audit import audit_chain_of_thought
+from app.core.storage import get_validation_result, list_audit_events
+from app.models import AgentIntentRequest
+
+
+def test_consistent_trace_has_no_findings():
+    audit = audit_chain_of_thought(
+        [
+            "AAPL broke resistance on strong volume.",
+            "Risk budget allows a position of 100 AAPL.",
+            "Therefore buy 100 AAPL at market.",
+        ],
+        intent="buy 100 AAPL",
+    )
+    assert audit.steps_checked == 3
+    assert audit.first_divergent_step is None
+    assert audit.findings == []
+
+
+def test_numeric_restatement_mismatch_flags_step():
+    audit = audit_chain_of_thought(
+        [
+            "Position limit check: buy 100 AAPL fits the budget.",
+            "Volatility is normal.",
+            "Final: buy 1000 AAPL.",
+        ]
+    )
+    assert audit.first_divergent_step == 2
+    assert audit.findings[0].kind == "numeric_inconsistency"
+
+
+def test_final_step_contradicting_intent_is_flagged():
+    audit = audit_chain_of_thought(
+        ["Momentum is fading on MSFT.", "Sell 50 MSFT now."],
+        intent="buy 50 MSFT",
+    )
+    assert [f.kind for f in audit.findings] == ["intent_mismatch"]
+    assert audit.first_divergent_step == 1
+
+
+def test_internal_contradiction_within_step():
+    audit = audit_chain_of_thought(["The hedge is active. The hedge is not active."])
+    assert audit.findings[0].kind == "internal_contradiction"
+    assert audit.first_divergent_step == 0
+
+
+def test_blocked_intent_records_divergent_step_in_audit_evidence():
+    request = AgentIntentRequest(
+        tenant_id="tenant-cot",
+        agent_id="agent-cot",
+        intent="buy 100 AAPL",
+        samples=["Buy 100 AAPL"] * 3,
+        chain_of_thought=["Budget supports 100 AAPL.", "Execute: sell 100 AAPL."],
+    )
+    result = run_parallel_validation("req-cot", request)
+
+    assert result["status_code"] == 424
+    assert result["reasoning_audit"]["first_divergent_step"] == 1
+    assert any(v.startswith("Reasoning trace diverged at step 1") for v in result["violations"])
+
+    stored = get_validation_result("req-cot", tenant_id="tenant-cot")
+    assert stored["reasoning_audit"]["first_divergent_step"] == 1
+
+    event = list_audit_events("tenant-cot")[0]
+    assert event["event_type"] == "EXECUTION_BLOCKED_424"
+    assert "First divergent reasoning step: 1." in event["message"]
+
+
+def test_long_traces_are_accepted_and_audited_up_to_the_step_cap():
+    steps = ["Budget supports 100 AAPL."] * 60 + ["Execute: sell 100 AAPL."]
+    request = AgentIntentRequest(
+        agent_id="agent-cot",
+        intent="buy 100 AAPL",
+        samples=["Buy 100 AAPL"] * 3,
+        chain_of_thought=steps,
+    )
+
+    audit = audit_chain_of_thought(request.chain_of_thought, intent=request.intent)
+
+    assert audit.first_divergent_step is None
@@ -0,0 +1,114 @@
+"""
+Test Suite: Relational Constraints
+Rules over several inputs, evaluated without eval() and reported with every input value.
+"""
+
+import pytest
+
+from app.core.constraint_expressions import ExpressionError, compile_expression
+from app.core.constraint_rules import ConstraintDefinitionError
+from app.core.constraints import check_logic
+from app.core.severity import calculate_violation_severities
+from app.models import FinancialPayload, PhysicsPayload, ValidationRequest
+from tests.helpers import leverage_definitions
+
+
+def _relation(expression, message="Relation breached", **extra):
+    return {"id": "relation", "expression": expression, "severity_weight": 2.0, "message": message, **extra}
+
+
+def test_leverage_capital_relation_reports_every_input():
+    request = ValidationRequest(
+        samples=["Hold"] * 3,
+        metrics={"leverage_ratio": 8, "notional": 2_000_000, "capital": 1_000_000},
+    )
+    (violation,) = check_logic(request)
+
+    assert violation.rule_id == "leverage_capital"
+    assert violation.inputs == {"capital": 1_000_000.0, "leverage_ratio": 8.0, "notional": 2_000_000.0}
+    assert (violation.actual, violation.limit) == (16_000_000.0, 10_000_000.0)
+    assert violation.message == (
+        "Leverage exposure breached: 8x on 2,000,000 notional exceeds 10x capital of 1,000,000"
+    )
+    assert calculate_violation_severities([violation])[0].severity_pct == pytest.approx(60.0)
+
+
+def test_relation_is_skipped_when_an_input_is_missing():
+    request = ValidationRequest(samples=["Hold"] * 3, metrics={"leverage_ratio": 8, "notional": 2_000_000})
+    assert check_logic(request) == []
+
+
+def test_proposed_loss_is_checked_against_var_99_metric():
+    request = ValidationRequest(
+        samples=["Hold"] * 3,
+        financial=FinancialPayload(proposed_loss=-8_000),
+        metrics={"var_99": 5_000},
+    )
+    (violation,) = check_logic(request)
+
+    assert violation.rule_id == "var_99_coverage"
+    assert violation.inputs == {"proposed_loss": -8_000.0, "var_99": 5_000.0}
+    assert violation.message == "Proposed loss exceeds 99% VaR: 8,000 vs var_99 5,000"
+
+
+def test_energy_balance_is_a_relation():
+    request = ValidationRequest(samples=["Hold"] * 3, physics=PhysicsPayload(energy_in=100, energy_out=150))
+    (violation,) = check_logic(request)
+
+    assert violation.message == "Balance invariant breached"
+    assert violation.inputs == {"energy_in": 100.0, "energy_out": 150.0}
+
+
+def test_chained_efficiency_bound_reports_failing_side():
+    definitions = leverage_definitions(
+        relations=[
+            _relation("0.2 <= energy_out / energy_in <= 0.95", "Efficiency out of bounds: {lhs:.2f} vs {rhs:.2f}")
+        ]
+    )
+    request = ValidationRequest(samples=["Hold"] * 3, physics=PhysicsPayload(energy_in=100, energy_out=98))
+    (violation,) = check_logic(request, definitions)
+
+    assert violation.variable == "energy_out / energy_in"
+    assert violation.message == "Efficiency out of bounds: 0.98 vs 0.95"
+
+    # Division by zero cannot be evaluated, so the relation does not fire
+    zero = ValidationRequest(samples=["Hold"] * 3, physics=PhysicsPayload(energy_in=0, energy_out=0))
+    assert check_logic(zero, definitions) == []
+
+
+@pytest.mark.parametrize(
+    "expression",
+    [
+        "__import__('os').system('true') == 0",
+        "leverage.real <= 10",
+        "notional[0] <= 10",
+        "notional ** 2 <= 10",
+        "leverage_ratio",
+        "'a' < 'b'",
+        "(lambda: 1)() <= 2",
+        "min(notional) <= 10",
+        "max(notional, k=1) <= 10",
+        "abs(notional, capital) <= 10",
+    ],
+)
+def test_unsafe_or_unsupported_expressions_are_rejected(expression):
+    with pytest.raises(ExpressionError):
+        compile_expression(expression)
+
+
+def test_invalid_relation_definitions_fail_at_load():
+    with pytest.raises(ConstraintDefinitionError):
+        leverage_definitions(
+            relations=[_relation("capital * k >= notional", "Breach {unknown}", parameters={"k": 2})]
+        )
+    with pytest.raises(ConstraintDefinitionError):
+        leverage_definitions(relations=[_relation("Capital >= 1")])
+    with pytest.raises(ConstraintDefinitionError):
+        leverage_definitions(relations=[_relation("k >= 1", parameters={"k": 2})])
+
+
+def test_unevaluable_literal_skips_the_relation_instead_of_failing():
+    definitions = leverage_definitions(relations=[_relation("notional <= " + "9" * 400)])
+    request = ValidationRequest(samples=["Hold"] * 3, metrics={"notional": 1.0})
+
+    assert check_logic(request, definitions) == []
@@ -0,0 +1,101 @@
+"""
+Test Suite: Semantic and Violation-Rate Drift
+Embedding centroid shifts and rising violation rates are caught and audited.
+"""
+
+import math
+import random
+
+import pytest
+
+from app.core.drift import DriftRegistry, RequestHistory, record_drift_audit_events
+from app.core.drift_detectors import DetectorConfig, SemanticCentroidDetector, ViolationRateDetector
+from app.core.storage import list_audit_events
+
+
+def _samples(rng, topic, spread=0.05, count=3, dims=8):
+    """Unit vectors clustered around basis vector `topic`."""
+    samples = []
+    for _ in range(count):
+        vector = [rng.gauss(0, spread) for _ in range(dims)]
+        vector[topic] += 1.0
+        norm = math.sqrt(sum(x * x for x in vector))
+        samples.append([x / norm for x in vector])
+    return samples
+
+
+def _first_alarm(detector, stream):
+    for index, value in enumerate(stream):
+        if detector.update(value).fired:
+            return index
+    return None
+
+
+def test_centroid_shift_fires_after_the_topic_changes():
+    rng = random.Random(3)
+    stream = [_samples(rng, 0) for _ in range(40)] + [_samples(rng, 1) for _ in range(5)]
+
+    assert _first_alarm(SemanticCentroidDetector(), stream) == 40
+
+
+def test_dispersion_jump_fires_even_when_the_centroid_holds():
+    rng = random.Random(5)
+    detector = SemanticCentroidDetector()
+    for _ in range(40):
+        detector.update(_samples(rng, 0))
+
+    result = detector.update(_samples(rng, 0, spread=0.6, count=5))
+
+    assert result.fired
+    assert result.detail["dispersion_z"] > result.threshold
+
+
+def test_semantic_detector_is_quiet_on_a_stable_topic_and_skips_missing_embeddings():
+    rng = random.Random(11)
+    detector = SemanticCentroidDetector()
+
+    assert _first_alarm(detector, [_samples(rng, 2) for _ in range(150)]) is None
+    before = detector.state()
+    assert detector.update(None).ready is False
+    assert detector.state() == before
+
+
+def test_violation_rate_fires_on_increase_only():
+    rising = [1 if i % 10 == 0 else 0 for i in range(80)] + [1 if i % 2 else 0 for i in range(20)]
+    falling = [1 if i % 2 else 0 for i in range(80)] + [0] * 20
+
+    alarm = _first_alarm(ViolationRateDetector(), rising)
+
+    assert alarm is not None and alarm >= 80
+    assert _first_alarm(ViolationRateDetector(), falling) is None
+
+
+def test_history_routes_each_signal_to_its_detector():
+    rng = random.Random(3)
+    history = RequestHistory()
+    config = DetectorConfig(enabled=("semantic",))
+    for _ in range(40):
+        history.observe(0.1, 0, config, _samples(rng, 0))
+
+    alert = history.observe(0.1, 0, config, _samples(rng, 1))
+    restored = RequestHistory.from_dict(history.to_dict())
+
+    assert alert.fired_detectors == ["semantic"]
+    assert alert.detectors["semantic"]["centroid_distance"] > 0.5
+    assert restored.detector_states["semantic"] == history.detector_states["semantic"]
+
+
+def test_fired_detectors_are_written_to_the_audit_trail():
+    registry = DriftRegistry()
+    alert = None
+    for violations in [0] * 80 + [3] * 20:
+        alert = registry.record("tenant-a", "agent-1", 0.1, violations)
+        if alert.detected:
+            break
+
+    audit_ids = record_drift_audit_events("req-1", "tenant-a", "agent-1", alert)
+
+    assert alert.fired_detectors == ["violation_rate"]
+    events = [e for e in list_audit_events("tenant-a") if e["event_type"] == "VIOLATION_RATE_DRIFT_DETECTED"]
+    assert len(audit_ids) == len(events) == 1
+    assert "recent_rate" in events[0]["message"]
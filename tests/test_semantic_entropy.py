@@ -0,0 +1,66 @@
+"""
+Test Suite: Semantic Entropy
+N-sample meaning clustering and entropy over clusters.
+"""
+
+import pytest
+from pydantic import ValidationError
+
+from app.core.entropy import _contradicts, semantic_entropy, hallucination_divergence
+from app.models import ValidationRequest, MAX_SAMPLES
+
+
+def test_identical_samples_form_one_cluster():
+    result = semantic_entropy(["Buy 100 AAPL at market"] * 5)
+    assert result.clusters == [0, 0, 0, 0, 0]
+    assert result.cluster_count == 1
+    assert result.score == 0.0
+
+
+def test_all_distinct_samples_have_max_entropy():
+    responses = [
+        "The velocity is 10 m/s due to Newton's second law.",
+        "Quantum entanglement causes instantaneous communication.",
+        "Stock prices follow a random walk with drift.",
+        "Photosynthesis converts sunlight into chemical energy.",
+    ]
+    result = semantic_entropy(responses)
+    assert result.cluster_count == 4
+    assert result.score == pytest.approx(1.0)
+
+
+def test_contradicting_directives_split_clusters():
+    result = semantic_entropy(["buy 100 AAPL now", "buy 100 AAPL now", "sell 100 AAPL now"])
+    assert result.clusters[0] == result.clusters[1]
+    assert result.clusters[2] != result.clusters[0]
+    assert 0.0 < result.score < 1.0
+
+
+@pytest.mark.parametrize(
+    "a, b, expected",
+    [
+        ("The trade is safe", "The trade is not safe", True),
+        ("Never hedge the position", "Hedge the position", True),
+        ("Buy AAPL without delay", "Buy AAPL now", False),
+        ("Buy AAPL, no fees apply", "Buy AAPL today", False),
+        ("There is no dividend risk", "There is no dividend risk", False),
+    ],
+)
+def test_negation_must_flip_a_shared_content_word(a, b, expected):
+    assert _contradicts(a, b) is expected
+
+
+def test_majority_cluster_lowers_entropy():
+    agreeing = ["Hold the position overnight"] * 9 + ["Liquidate everything immediately"]
+    split = ["Hold the position overnight"] * 5 + ["Liquidate everything immediately"] * 5
+    assert semantic_entropy(agreeing).score < semantic_entropy(split).score
+
+
+def test_hallucination_divergence_accepts_twenty_samples():
+    score = hallucination_divergence(["Reduce leverage to 2x"] * MAX_SAMPLES)
+    assert score == 0.0
+
+
+def test_request_rejects_too_many_samples():
+    with pytest.raises(ValidationError):
+        ValidationRequest(samples=["ok"] * (MAX_SAMPLES + 1))
@@ -0,0 +1,98 @@
+"""
+Test Suite: Shadow-Mode Candidates
+Candidate bundles and trade policies are evaluated on live traffic but never enforced.
+"""
+
+from datetime import datetime, timedelta
+
+from app.core.shadow import SHADOW_CONSTRAINT_BUNDLE, SHADOW_TRADE_POLICY, shadow_pre_trade
+from app.core.storage import (
+    activate_constraint_bundle,
+    clear_shadow_candidate,
+    count_shadow_divergences,
+    create_constraint_bundle,
+    list_shadow_divergences,
+    set_shadow_candidate,
+)
+from app.core.trade_guard import evaluate_pre_trade
+from app.models import AccountSnapshot, TradeOrder, TradePolicyConfig
+from tests.helpers import leverage_bundle, validate_leverage
+
+
+def _shadow_bundle(tenant_id, limit):
+    bundle = create_constraint_bundle(tenant_id, leverage_bundle(limit))
+    return set_shadow_candidate(tenant_id, SHADOW_CONSTRAINT_BUNDLE, {"version": bundle["version"]})
+
+
+def test_stricter_candidate_records_allow_to_block_without_enforcing():
+    _shadow_bundle("tenant-a", 5)
+
+    result = validate_leverage(7.0)
+
+    assert result["action"] == "ALLOW"
+    (divergence,) = list_shadow_divergences("tenant-a")
+    assert divergence["reference_id"] == result["request_id"]
+    assert (divergence["live_action"], divergence["shadow_action"]) == ("ALLOW", "BLOCK")
+    assert divergence["shadow"]["violations"] == ["Leverage limit breached: hard limit is 5x maximum"]
+    assert divergence["shadow"]["constraint_bundle_version"] == 1
+
+
+def test_looser_candidate_records_block_to_allow():
+    strict = create_constraint_bundle("tenant-a", leverage_bundle(5))
+    activate_constraint_bundle("tenant-a", strict["version"])
+    _shadow_bundle("tenant-a", 10)
+
+    result = validate_leverage(7.0)
+
+    assert result["status_code"] == 424
+    assert count_shadow_divergences("tenant-a") == [
+        {"kind": SHADOW_CONSTRAINT_BUNDLE, "live_action": "BLOCK", "shadow_action": "ALLOW", "count": 1}
+    ]
+
+
+def test_matching_verdicts_and_detached_candidates_are_not_recorded():
+    _shadow_bundle("tenant-a", 5)
+    validate_leverage(3.0)
+    assert list_shadow_divergences("tenant-a") == []
+
+    assert clear_shadow_candidate("tenant-a", SHADOW_CONSTRAINT_BUNDLE)
+    validate_leverage(7.0)
+    assert list_shadow_divergences("tenant-a") == []
+    assert not clear_shadow_candidate("tenant-a", SHADOW_CONSTRAINT_BUNDLE)
+
+
+def test_candidates_are_scoped_to_their_tenant():
+    _shadow_bundle("tenant-a", 5)
+    validate_leverage(7.0, tenant_id="tenant-b")
+
+    assert list_shadow_divergences("tenant-a") == []
+    assert list_shadow_divergences("tenant-b") == []
+
+
+def test_trade_policy_candidate_is_compared_with_live_decision():
+    order = TradeOrder(symbol="AAPL", side="BUY", quantity=10, price=100.0, leverage_ratio=4.0)
+    account = AccountSnapshot(capital=1_000_000, avg_order_size=10, order_size_stddev=5)
+    live = evaluate_pre_trade(order, account, TradePolicyConfig())
+    set_shadow_candidate(
+        "tenant-a",
+        SHADOW_TRADE_POLICY,
+        TradePolicyConfig(policy_version="tight-leverage", max_leverage_ratio=3.0).model_dump(),
+    )
+
+    shadow_action = shadow_pre_trade("decision-1", "tenant-a", "agent-1", order, account, live)
+
+    assert (live["decision"], shadow_action) == ("ALLOW", "BLOCK")
+    (divergence,) = list_shadow_divergences("tenant-a", kind=SHADOW_TRADE_POLICY)
+    assert divergence["shadow"]["triggered_controls"] == ["max_leverage_ratio"]
+    assert divergence["shadow"]["policy_version"] == "tight-leverage"
+
+
+def test_report_counts_respect_time_range():
+    _shadow_bundle("tenant-a", 5)
+    validate_leverage(7.0)
+    validate_leverage(8.0)
+    now = datetime.utcnow()
+
+    assert count_shadow_divergences("tenant-a", since=now - timedelta(hours=1))[0]["count"] == 2
+    assert count_shadow_divergences("tenant-a", since=now + timedelta(seconds=1)) == []
+    assert count_shadow_divergences("tenant-a", kind=SHADOW_TRADE_POLICY) == []
@@ -0,0 +1,72 @@
+"""
+Test Suite: Structured Sample Divergence
+Field-by-field comparison of JSON tool-call samples.
+"""
+
+import json
+
+import pytest
+from pydantic import ValidationError
+
+from app.core.constraints import check_logic
+from app.core.divergence_backends import get_backend
+from app.core.severity import calculate_overall_severity_score, calculate_violation_severities
+from app.core.structured_divergence import structured_divergence
+from app.models import ValidationRequest
+
+
+def _calls(*orders):
+    return [json.dumps(order) for order in orders]
+
+
+def test_identical_tool_calls_have_zero_divergence():
+    samples = _calls(*[{"tool": "place_order", "args": {"symbol": "AAPL", "side": "BUY", "qty": 100}}] * 3)
+    result = structured_divergence(samples, None, get_backend("lexical-js"))
+    assert result.score == 0.0
+    assert result.cluster_count == 1
+    assert {f.field for f in result.fields} == {"tool", "args.symbol", "args.side", "args.qty"}
+
+
+def test_quantity_disagreement_blocks_as_structured_violation():
+    samples = _calls(
+        {"symbol": "AAPL", "side": "BUY", "qty": 100, "reason": "Momentum breakout on volume"},
+        {"symbol": "AAPL", "side": "BUY", "qty": 100, "reason": "Earnings beat expectations"},
+        {"symbol": "AAPL", "side": "BUY", "qty": 1000, "reason": "Momentum breakout on volume"},
+    )
+    request = ValidationRequest(samples=samples, sample_format="json")
+    violations = check_logic(request)
+    assert [v.message for v in violations] == ["Structured field disagreement: qty (100 vs 1000)"]
+    assert (violations[0].rule_id, violations[0].variable) == ("structured_fields", "qty")
+
+    severities = calculate_violation_severities(violations)
+    assert calculate_overall_severity_score(severities) >= 3.0
+
+    result = structured_divergence(samples, None, get_backend("lexical-js"))
+    qty = next(f for f in result.fields if f.field == "qty")
+    assert qty.comparison == "numeric" and qty.divergence == pytest.approx(1 / 3)
+    reason = next(f for f in result.fields if f.field == "reason")
+    assert reason.comparison == "semantic" and not reason.decision_field
+    assert result.clusters == [0, 0, 1]
+
+
+def test_float_fields_use_numeric_tolerance():
+    samples = _calls({"limit_price": 150.00}, {"limit_price": 150.50}, {"limit_price": 149.90})
+    assert check_logic(ValidationRequest(samples=samples, sample_format="json")) == []
+
+
+def test_decision_fields_restrict_scoring():
+    samples = _calls(
+        {"side": "SELL", "client_ref": "a-1"},
+        {"side": "SELL", "client_ref": "b-2"},
+        {"side": "SELL", "client_ref": "c-3"},
+    )
+    everything = structured_divergence(samples, None, get_backend("lexical-js"))
+    decision_only = structured_divergence(samples, ["side"], get_backend("lexical-js"))
+    assert everything.score > 0.4
+    assert decision_only.score == 0.0
+    assert check_logic(ValidationRequest(samples=samples, sample_format="json", decision_fields=["side"])) == []
+
+
+def test_json_mode_rejects_non_object_samples():
+    with pytest.raises(ValidationError):
+        ValidationRequest(samples=['{"qty": 1}', "[1, 2]", "buy 100"], sample_format="json")
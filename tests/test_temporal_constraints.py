@@ -0,0 +1,135 @@
+"""
+Test Suite: Temporal Constraints
+Per-agent rate-of-change rules checked against persisted metric history.
+"""
+
+import threading
+import time
+from datetime import datetime, timedelta
+
+import pytest
+
+from app.core import parallel_validation
+from app.core.constraint_rules import ConstraintDefinitionError, get_constraint_definitions
+from app.core.storage import insert_agent_metrics, list_agent_metric_history
+from app.core.temporal_constraints import check_temporal_constraints
+from tests.helpers import leverage_definitions, validate_leverage
+
+
+def _temporal(**overrides):
+    return {
+        "id": "temporal",
+        "variables": ["drawdown"],
+        "kind": "max_delta",
+        "limit": 1,
+        "severity_weight": 2.0,
+        "message": "Drawdown moved {previous:g} -> {current:g}",
+        **overrides,
+    }
+
+
+def test_leverage_jump_between_requests_blocks_with_history_values():
+    assert validate_leverage(1.0)["status_code"] == 200
+
+    result = validate_leverage(9.9)
+
+    assert result["status_code"] == 424
+    step, window = result["violation_records"]
+    assert step["rule_id"] == "leverage_step"
+    assert (step["previous"], step["current"], step["actual"], step["limit"]) == (1.0, 9.9, pytest.approx(8.9), 3.0)
+    assert step["window_seconds"] is None
+    assert (window["rule_id"], window["limit"], window["window_seconds"]) == ("leverage_window", 5.0, 3600.0)
+
+
+def test_blocked_values_do_not_become_the_baseline():
+    validate_leverage(1.0)
+    assert validate_leverage(9.9)["status_code"] == 424
+
+    history = list_agent_metric_history("tenant-a", "agent-1", "leverage_ratio")
+    assert [entry["value"] for entry in history] == [1.0]
+    assert validate_leverage(3.5)["status_code"] == 200
+
+
+def test_small_steps_are_caught_by_the_window_rule():
+    for leverage in (1.0, 3.5, 6.0):
+        assert validate_leverage(leverage)["status_code"] == 200
+
+    result = validate_leverage(8.0)
+
+    assert result["status_code"] == 424
+    (record,) = result["violation_records"]
+    assert record["rule_id"] == "leverage_window"
+    assert (record["previous"], record["current"], record["window_seconds"]) == (1.0, 8.0, 3600.0)
+
+
+def test_concurrent_requests_are_judged_against_each_others_values(monkeypatch):
+    monkeypatch.setattr(parallel_validation, "LATENCY_GUARD_SECONDS", 60.0)
+    check = parallel_validation.check_temporal_constraints
+
+    def _slow_check(*args, **kwargs):
+        violations = check(*args, **kwargs)
+        time.sleep(0.05)  # Widen the window between reading the history and recording to it
+        return violations
+
+    monkeypatch.setattr(parallel_validation, "check_temporal_constraints", _slow_check)
+    validate_leverage(1.0)
+    statuses = []
+
+    # Each is a step of <= 3x from 1x, but 0x and 3.5x are 3.5x apart
+    threads = [
+        threading.Thread(target=lambda value=value: statuses.append(validate_leverage(value)["status_code"]))
+        for value in (0.0, 3.5)
+    ]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+
+    assert sorted(statuses) == [200, 424]
+
+
+def test_window_ignores_values_older_than_the_window():
+    now = datetime.utcnow()
+    definitions = get_constraint_definitions()
+    insert_agent_metrics("tenant-a", "agent", "old", {"leverage_ratio": 1.0}, recorded_at=now - timedelta(hours=2))
+    insert_agent_metrics("tenant-a", "agent", "recent", {"leverage_ratio": 5.0}, recorded_at=now - timedelta(minutes=5))
+
+    assert check_temporal_constraints("tenant-a", "agent", {"leverage_ratio": 7.0}, definitions, now=now) == []
+    (violation,) = check_temporal_constraints("tenant-a", "agent", {"leverage": 0.5}, definitions, now=now)
+    assert violation.rule_id == "leverage_step"  # aliases share the canonical history
+
+
+def test_history_is_isolated_per_agent_and_tenant():
+    validate_leverage(1.0)
+
+    assert validate_leverage(9.9, agent_id="other-agent")["status_code"] == 200
+    assert validate_leverage(9.9, tenant_id="tenant-b")["status_code"] == 200
+
+
+def test_monotonic_rule_reports_reversal():
+    rule = _temporal(id="drawdown_monotonic", kind="monotonic", direction="increasing")
+    del rule["limit"]
+    definitions = leverage_definitions(temporal=[rule])
+    insert_agent_metrics("tenant-a", "agent", "r1", {"drawdown": 4.0})
+
+    assert check_temporal_constraints("tenant-a", "agent", {"drawdown": 4.5}, definitions) == []
+    (violation,) = check_temporal_constraints("tenant-a", "agent", {"drawdown": 3.0}, definitions)
+    assert (violation.previous, violation.current, violation.limit) == (4.0, 3.0, 0.0)
+    assert violation.message == "Drawdown moved 4 -> 3"
+
+
+@pytest.mark.parametrize(
+    "overrides",
+    [
+        {"kind": "max_rate"},
+        {"limit": -1},
+        {"kind": "max_window_delta"},
+        {"window_seconds": 60},
+        {"kind": "monotonic", "direction": "sideways"},
+        {"message": "Moved {unknown}"},
+        {"variables": []},
+    ],
+)
+def test_invalid_temporal_definitions_fail_at_load(overrides):
+    with pytest.raises(ConstraintDefinitionError):
+        leverage_definitions(temporal=[_temporal(**overrides)])
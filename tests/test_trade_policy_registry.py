@@ -0,0 +1,267 @@
+"""
+Test Suite: Server-Side Trade Policy Registry
+Pre-trade requests resolve stored, versioned policies; client overrides may only tighten them.
+"""
+
+import uuid
+
+import pytest
+from fastapi.testclient import TestClient
+
+from app.core.onboarding import list_api_keys
+from app.core.storage import (
+    bind_trade_policy,
+    create_trade_policy,
+    get_execution_decision,
+    get_trade_policy,
+    insert_execution_decision,
+    set_api_key_trading_scope,
+    unbind_trade_policy,
+    update_tenant_validation_settings,
+)
+from app.core.trade_policies import (
+    CLIENT_POLICY_SETTING,
+    LooserPolicyError,
+    TradePolicyError,
+    TradingScopeError,
+    UnknownTradePolicyError,
+    looser_fields,
+    resolve_trade_policy,
+    trading_scope,
+)
+from app.main import app
+from app.models import PreTradeRequest, TradePolicyConfig
+from tests.helpers import bootstrap_tenant_auth, issue_api_key
+
+
+client = TestClient(app)
+
+
+def _store(policy_id="desk", tenant_id="tenant-a", **limits):
+    return create_trade_policy(tenant_id, policy_id, TradePolicyConfig(**limits).model_dump())
+
+
+def _pre_trade(tenant_id, headers, **fields):
+    payload = {
+        "idempotency_key": f"order-{uuid.uuid4().hex[:12]}",
+        "tenant_id": tenant_id,
+        "order": {"symbol": "AAPL", "side": "BUY", "quantity": 1, "price": 100.0},
+        "account": {"capital": 100000.0},
+        **fields,
+    }
+    return client.post("/v4/execution/pre-trade", json=payload, headers=headers)
+
+
+def test_versions_are_immutable_and_latest_is_default():
+    first = _store(max_leverage_ratio=4.0)
+    second = _store(max_leverage_ratio=2.0)
+
+    assert (first["version"], second["version"]) == (1, 2)
+    assert get_trade_policy("tenant-a", "desk")["version"] == 2
+    assert get_trade_policy("tenant-a", "desk", 1)["policy"]["max_leverage_ratio"] == 4.0
+    assert get_trade_policy("tenant-b", "desk") is None
+
+
+def test_most_specific_binding_applies():
+    _store("default", max_position_size=1000.0)
+    _store("scalper", max_position_size=100.0)
+    bind_trade_policy("tenant-a", "default", 1)
+    bind_trade_policy("tenant-a", "scalper", 1, strategy_id="scalp")
+
+    assert resolve_trade_policy("tenant-a", account_id="acct-1").policy_id == "default"
+    scalp = resolve_trade_policy("tenant-a", account_id="acct-1", strategy_id="scalp")
+    assert (scalp.policy_id, scalp.version, scalp.source) == ("scalper", 1, "binding")
+
+    unbind_trade_policy("tenant-a", strategy_id="scalp")
+    assert resolve_trade_policy("tenant-a", strategy_id="scalp").policy_id == "default"
+
+
+def test_binding_pins_a_version():
+    _store(max_leverage_ratio=3.0)
+    bind_trade_policy("tenant-a", "desk", 1)
+    _store(max_leverage_ratio=5.0)
+
+    resolved = resolve_trade_policy("tenant-a")
+
+    assert resolved.version == 1
+    assert resolved.policy.max_leverage_ratio == 3.0
+
+
+def test_referenced_policy_may_not_be_looser_than_the_binding():
+    _store("strict", max_leverage_ratio=2.0)
+    _store("loose", max_leverage_ratio=5.0)
+    _store("tighter", max_leverage_ratio=1.5)
+    bind_trade_policy("tenant-a", "strict", 1)
+
+    with pytest.raises(LooserPolicyError) as exc:
+        resolve_trade_policy("tenant-a", policy_id="loose")
+
+    assert exc.value.fields == ["max_leverage_ratio"]
+    assert resolve_trade_policy("tenant-a", policy_id="tighter").source == "reference"
+
+
+def test_override_may_only_tighten_the_bound_policy():
+    _store(max_daily_loss=10000.0, restricted_instruments=["GME"], kill_switch_enabled=True)
+    bind_trade_policy("tenant-a", "desk", 1)
+    bound = get_trade_policy("tenant-a", "desk")["policy"]
+
+    tighter = TradePolicyConfig(**{**bound, "max_daily_loss": 5000.0, "restricted_instruments": ["gme", "AMC"]})
+    resolved = resolve_trade_policy("tenant-a", override=tighter)
+    assert resolved.override_applied and resolved.policy.max_daily_loss == 5000.0
+    assert (resolved.policy_id, resolved.version) == ("desk", 1)
+
+    looser = TradePolicyConfig(
+        **{**bound, "max_daily_loss": 20000.0, "restricted_instruments": [], "kill_switch_enabled": False}
+    )
+    with pytest.raises(LooserPolicyError) as exc:
+        resolve_trade_policy("tenant-a", override=looser)
+    assert set(exc.value.fields) == {"max_daily_loss", "restricted_instruments", "kill_switch_enabled"}
+
+
+def test_rounding_rules_must_match():
+    bound = TradePolicyConfig(tick_size=0.01)
+
+    assert looser_fields(TradePolicyConfig(tick_size=0.05), bound) == ["tick_size"]
+    assert looser_fields(TradePolicyConfig(tick_size=0.01, max_orders_per_minute=10), bound) == []
+
+
+def test_legacy_client_policy_only_without_stored_policies():
+    client = TradePolicyConfig(max_position_size=50.0)
+    with pytest.raises(TradePolicyError, match="only enforced for tenants that enable them"):
+        resolve_trade_policy("tenant-a", override=client)
+
+    update_tenant_validation_settings("tenant-a", {CLIENT_POLICY_SETTING: True})
+    legacy = resolve_trade_policy("tenant-a", override=client)
+    assert (legacy.source, legacy.policy_id, legacy.version) == ("client", None, None)
+
+    _store(tenant_id="tenant-a")
+    with pytest.raises(TradePolicyError):
+        resolve_trade_policy("tenant-a", override=client)
+    with pytest.raises(TradePolicyError):
+        resolve_trade_policy("tenant-b")
+
+
+def test_reference_without_an_applicable_binding_is_refused():
+    _store("loose", max_leverage_ratio=50.0)
+    _store("strict", max_leverage_ratio=2.0)
+    bind_trade_policy("tenant-a", "strict", 1, strategy_id="scalp")
+
+    with pytest.raises(TradePolicyError, match="no binding applies"):
+        resolve_trade_policy("tenant-a", policy_id="loose", strategy_id="other")
+
+    bind_trade_policy("tenant-a", "strict", 1)
+    with pytest.raises(LooserPolicyError):
+        resolve_trade_policy("tenant-a", policy_id="loose", strategy_id="other")
+
+
+def test_binding_scope_comes_from_the_api_key():
+    set_api_key_trading_scope("tenant-a", "key-scalp", strategy_id="scalp")
+
+    assert trading_scope("tenant-a", "key-scalp") == (None, "scalp")
+    assert trading_scope("tenant-a", "key-scalp", strategy_id="scalp") == (None, "scalp")
+    assert trading_scope("tenant-a", "key-plain") == (None, None)
+    assert trading_scope("tenant-a", None) == (None, None)
+    with pytest.raises(TradingScopeError):
+        trading_scope("tenant-a", "key-scalp", strategy_id="swing")
+    with pytest.raises(TradingScopeError):
+        trading_scope("tenant-a", "key-plain", strategy_id="scalp")
+    with pytest.raises(TradingScopeError):
+        trading_scope("tenant-b", "key-scalp", strategy_id="scalp")
+
+
+def test_unknown_reference_is_reported():
+    with pytest.raises(UnknownTradePolicyError):
+        resolve_trade_policy("tenant-a", policy_id="missing")
+    _store()
+    with pytest.raises(UnknownTradePolicyError):
+        resolve_trade_policy("tenant-a", policy_id="desk", policy_version=3)
+
+
+def test_bind_rejects_unknown_version():
+    _store()
+
+    with pytest.raises(KeyError):
+        bind_trade_policy("tenant-a", "desk", 2)
+
+
+def test_policy_version_requires_policy_id():
+    with pytest.raises(ValueError):
+        PreTradeRequest(
+            idempotency_key="order-0001",
+            tenant_id="tenant-a",
+            order={"symbol": "AAPL", "side": "BUY", "quantity": 1, "price": 100.0},
+            account={"capital": 100000.0},
+            policy_version=2,
+        )
+
+
+def test_decision_records_the_resolved_version():
+    _store(max_position_size=10.0)
+    bind_trade_policy("tenant-a", "desk", 1)
+    resolved = resolve_trade_policy("tenant-a")
+
+    insert_execution_decision(
+        decision_id="decision-1",
+        tenant_id="tenant-a",
+        agent_id=None,
+        decision="ALLOW",
+        reason="ok",
+        triggered_controls=[],
+        order={"symbol": "AAPL", "side": "BUY", "quantity": 5.0, "price": 100.0},
+        account={},
+        policy=resolved.policy.model_dump(),
+        modified_order=None,
+        idempotency_key="order-0001",
+        policy_id=resolved.policy_id,
+        policy_version=resolved.version,
+    )
+
+    stored = get_execution_decision("decision-1", "tenant-a")
+    assert (stored["policy_id"], stored["policy_version"]) == ("desk", 1)
+    assert stored["policy"]["max_position_size"] == 10.0
+
+
+def test_pre_trade_endpoint_maps_resolution_errors():
+    tenant, _, headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+
+    inline = _pre_trade(tenant_id, headers, policy={"max_position_size": 50.0})
+    assert inline.status_code == 422 and "enable them" in inline.json()["detail"]
+
+    _store("strict", tenant_id=tenant_id, max_leverage_ratio=2.0)
+    _store("loose", tenant_id=tenant_id, max_leverage_ratio=5.0)
+    unbound = _pre_trade(tenant_id, headers)
+    assert unbound.status_code == 422 and "No trade policy is bound" in unbound.json()["detail"]
+    reference = _pre_trade(tenant_id, headers, policy_id="loose")
+    assert reference.status_code == 422 and "no binding applies" in reference.json()["detail"]
+    assert _pre_trade(tenant_id, headers, policy_id="missing").status_code == 404
+
+    bind_trade_policy(tenant_id, "strict", 1)
+    looser = _pre_trade(tenant_id, headers, policy_id="loose")
+    assert looser.status_code == 403
+    assert looser.json()["detail"]["fields"] == ["max_leverage_ratio"]
+    assert _pre_trade(tenant_id, headers).json()["policy_id"] == "strict"
+
+
+def test_pre_trade_endpoint_uses_the_keys_trading_scope():
+    tenant, user, admin_headers = bootstrap_tenant_auth(client)
+    tenant_id = tenant["tenant_id"]
+    agent_headers = issue_api_key(client, tenant_id, user["user_id"], "validate-only", headers=admin_headers)
+    (agent_key,) = [key for key in list_api_keys(tenant_id) if key["scope"] == "validate-only"]
+    _store("default", tenant_id=tenant_id, max_position_size=1000.0)
+    _store("scalper", tenant_id=tenant_id, max_position_size=100.0)
+    bind_trade_policy(tenant_id, "default", 1)
+    bind_trade_policy(tenant_id, "scalper", 1, strategy_id="scalp")
+
+    scope = client.put(
+        f"/v1/onboarding/tenants/{tenant_id}/api-keys/{agent_key['key_id']}/trading-scope",
+        json={"strategy_id": "scalp"},
+        headers=admin_headers,
+    )
+    assert scope.status_code == 200
+
+    assert _pre_trade(tenant_id, agent_headers).json()["policy_id"] == "scalper"
+    assert _pre_trade(tenant_id, agent_headers, strategy_id="scalp").json()["policy_id"] == "scalper"
+    assert _pre_trade(tenant_id, agent_headers, strategy_id="swing").status_code == 403
+    assert _pre_trade(tenant_id, admin_headers).json()["policy_id"] == "default"
+    assert _pre_trade(tenant_id, admin_headers, strategy_id="scalp").status_code == 403
@@ -0,0 +1,86 @@
+"""
+Test Suite: Structured Violation Records
+check_logic records carry actual and limit values through severity, compliance and audit.
+"""
+
+import uuid
+
+import pytest
+
+from app.core import parallel_validation
+from app.core.compliance import map_violations_to_articles
+from app.core.constraints import check_logic
+from app.core.severity import calculate_violation_severities
+from app.core.storage import get_validation_result, list_audit_events, verify_runtime_audit_chain
+from app.core.violations import ConstraintViolation
+from app.models import AgentIntentRequest, FinancialPayload, PhysicsPayload, ValidationRequest
+
+
+def test_rule_violation_carries_actual_limit_and_unit():
+    violations = check_logic(ValidationRequest(samples=["Hold"] * 3, metrics={"leverage_ratio": 10.5}))
+
+    assert [v.to_dict() for v in violations] == [
+        {
+            "rule_id": "leverage_limit",
+            "message": "Leverage limit breached: hard limit is 10x maximum",
+            "variable": "leverage_ratio",
+            "actual": 10.5,
+            "limit": 10.0,
+            "unit": "x",
+            "inputs": None,
+            "previous": None,
+            "current": None,
+            "window_seconds": None,
+        }
+    ]
+
+
+def test_severity_is_scored_on_actual_overage():
+    request = ValidationRequest(
+        samples=["Hold"] * 3,
+        metrics={"leverage_ratio": 10.5},
+        financial=FinancialPayload(proposed_loss=-50_000),
+    )
+    by_rule = {
+        record.rule_id: severity
+        for record, severity in zip(check_logic(request), calculate_violation_severities(check_logic(request)))
+    }
+
+    assert by_rule["leverage_limit"].severity_pct == pytest.approx(5.0)
+    assert by_rule["leverage_limit"].weight == 2.0  # rule weight floor
+    assert by_rule["var_loss_limit"].severity_pct == pytest.approx(400.0)
+    assert by_rule["var_loss_limit"].weight == 4.0
+
+
+def test_balance_violation_records_energy_values():
+    request = ValidationRequest(samples=["Hold"] * 3, physics=PhysicsPayload(energy_in=100, energy_out=150))
+    (violation,) = check_logic(request)
+
+    assert (violation.rule_id, violation.actual, violation.limit) == ("energy_balance", 150, 100)
+    assert violation.inputs == {"energy_in": 100.0, "energy_out": 150.0}
+    assert calculate_violation_severities([violation])[0].severity_pct == pytest.approx(50.0)
+
+
+def test_compliance_matches_records_by_rule_id():
+    record = ConstraintViolation(rule_id="leverage_limit", message="Custom tenant wording", actual=12.0, limit=10.0)
+    assert map_violations_to_articles([record]) == ["CA-SB243-FinancialSafety", "EU-AIA-14-HumanOversight"]
+
+
+def test_records_are_stored_and_audited():
+    request_id = str(uuid.uuid4())
+    request = AgentIntentRequest(
+        agent_id="records-agent",
+        intent="rebalance",
+        tenant_id="tenant-a",
+        samples=["Hold the position"] * 3,
+        metrics={"leverage_ratio": 12.0},
+    )
+    parallel_validation.run_parallel_validation(request_id, request)
+
+    stored = get_validation_result(request_id, tenant_id="tenant-a")
+    assert stored["violations"] == ["Leverage limit breached: hard limit is 10x maximum"]
+    assert stored["violation_records"][0]["actual"] == 12.0
+
+    (event,) = [e for e in list_audit_events("tenant-a") if e["event_type"] == "EXECUTION_BLOCKED_424"]
+    assert event["violation_records"] == stored["violation_records"]
+    assert verify_runtime_audit_chain("tenant-a")